@@ -1,3 +1,5 @@
+import type { RobotStatus } from '$lib/types/robot';
+
 export function formatDate(dateString: string): string {
     const date = new Date(dateString);
     return formatStandardDate(date);
@@ -44,6 +46,13 @@ export const formatRobotElapsedTime = (start: string, end: string, useNanoSecond
     return formatTimeDiff(diff, useNanoSeconds);
 };
 
+export const formatRobotStatusElapsedTime = (status: RobotStatus, useNanoSeconds: boolean = true): string => {
+    if (status.elapsed !== undefined) {
+        return formatTimeDiff(Math.round(parseFloat(status.elapsed) * 1000), useNanoSeconds);
+    }
+    return formatRobotElapsedTime(status.start_time ?? '', status.end_time ?? '', useNanoSeconds);
+};
+
 const formatTimeDiff = (diff: number, useNanoSeconds: boolean = false): string => {
     const hours = Math.floor(diff / 3600000);
     const minutes = Math.floor((diff % 3600000) / 60000);
@@ -0,0 +1,86 @@
+// Robot Framework messages may be HTML: log messages with `html="true"` and status
+// messages starting with `*HTML*`. They come from the tested system and libraries,
+// so only formatting tags and links are kept before rendering them.
+
+const ALLOWED_TAGS = new Set([
+	'a',
+	'b',
+	'br',
+	'code',
+	'div',
+	'em',
+	'h1',
+	'h2',
+	'h3',
+	'h4',
+	'hr',
+	'i',
+	'img',
+	'li',
+	'ol',
+	'p',
+	'pre',
+	'span',
+	'strong',
+	'table',
+	'tbody',
+	'td',
+	'th',
+	'thead',
+	'tr',
+	'u',
+	'ul'
+]);
+
+const ALLOWED_ATTRIBUTES = new Set(['alt', 'colspan', 'height', 'href', 'rowspan', 'src', 'title', 'width']);
+
+const HTML_PREFIX = '*HTML*';
+
+// Relative links and images, e.g. screenshots, go through `resolveUrl`.
+export function sanitizeHtml(html: string, resolveUrl: (url: string) => string = (url) => url): string {
+	const document = new DOMParser().parseFromString(html, 'text/html');
+	sanitizeElement(document.body, resolveUrl);
+	return document.body.innerHTML;
+}
+
+// Status messages flag HTML with a prefix instead of an attribute.
+export function parseStatusMessage(message: string): { text: string; html: boolean } {
+	if (message.startsWith(HTML_PREFIX)) {
+		return { text: message.slice(HTML_PREFIX.length).trimStart(), html: true };
+	}
+	return { text: message, html: false };
+}
+
+function sanitizeElement(parent: Element, resolveUrl: (url: string) => string) {
+	for (const child of [...parent.children]) {
+		if (!ALLOWED_TAGS.has(child.tagName.toLowerCase())) {
+			child.replaceWith(child.ownerDocument.createTextNode(child.textContent ?? ''));
+			continue;
+		}
+		for (const attribute of [...child.attributes]) {
+			const name = attribute.name.toLowerCase();
+			if (!ALLOWED_ATTRIBUTES.has(name)) {
+				child.removeAttribute(attribute.name);
+			} else if (name === 'href' || name === 'src') {
+				if (isSafeUrl(attribute.value, name === 'src')) {
+					child.setAttribute(name, resolveUrl(attribute.value));
+				} else {
+					child.removeAttribute(attribute.name);
+				}
+			}
+		}
+		if (child.tagName.toLowerCase() === 'a') {
+			child.setAttribute('target', '_blank');
+			child.setAttribute('rel', 'noopener noreferrer');
+		}
+		sanitizeElement(child, resolveUrl);
+	}
+}
+
+function isSafeUrl(url: string, isImage: boolean): boolean {
+	const scheme = /^\s*([a-z][a-z0-9+.-]*):/i.exec(url)?.[1].toLowerCase();
+	if (scheme === undefined) {
+		return true;
+	}
+	return scheme === 'http' || scheme === 'https' || (isImage && /^\s*data:image\//i.test(url));
+}
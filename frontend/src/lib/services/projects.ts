@@ -1,6 +1,11 @@
 import { API_BASE_URL } from '$lib/config';
 
-import type { ProjectOverviewResponse, ProjectResponse } from '$lib/types/generated';
+import type {
+	ApiFlakyTest,
+	ApiTestRunPage,
+	ProjectOverviewResponse,
+	ProjectResponse
+} from '$lib/types/generated';
 
 const PROJECTS_BASE_API = `${API_BASE_URL}/projects`;
 
@@ -17,9 +22,10 @@ export async function getAllProjects(): Promise<ProjectOverviewResponse[]> {
 	}
 }
 
-export async function getProjectById(id: string): Promise<ProjectResponse | null> {
+/** `search` filters the test runs, e.g. `?environment=prod&branch=main`. */
+export async function getProjectById(id: string, search = ''): Promise<ProjectResponse | null> {
 	try {
-		const response = await fetch(`${PROJECTS_BASE_API}/${id}`);
+		const response = await fetch(`${PROJECTS_BASE_API}/${id}${search}`);
 		if (!response.ok) throw new Error('Failed to fetch project details');
 		return await response.json();
 	} catch (error) {
@@ -27,3 +33,32 @@ export async function getProjectById(id: string): Promise<ProjectResponse | null
 		return null;
 	}
 }
+
+/** `search` takes the filters of `getProjectById`, `cursor` the next page. */
+export async function getProjectTestRuns(
+	id: string,
+	search = '',
+	cursor?: string
+): Promise<ApiTestRunPage | null> {
+	try {
+		const params = new URLSearchParams(search);
+		if (cursor) params.set('cursor', cursor);
+		const response = await fetch(`${PROJECTS_BASE_API}/${id}/test-runs?${params}`);
+		if (!response.ok) throw new Error('Failed to fetch project test runs');
+		return await response.json();
+	} catch (error) {
+		console.error('Error fetching project test runs:', error);
+		return null;
+	}
+}
+
+export async function getFlakyTests(id: string, limit = 10): Promise<ApiFlakyTest[]> {
+	try {
+		const response = await fetch(`${PROJECTS_BASE_API}/${id}/flaky-tests?limit=${limit}`);
+		if (!response.ok) throw new Error('Failed to fetch flaky tests');
+		return await response.json();
+	} catch (error) {
+		console.error('Error fetching flaky tests:', error);
+		return [];
+	}
+}
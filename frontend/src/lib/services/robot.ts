@@ -1,6 +1,16 @@
 import { API_BASE_URL } from '$lib/config';
-import type { ApiSuite, ApiTest, TestRunResponse } from '$lib/types/generated';
-import type { ApiSuiteKeywords, RobotBaseBody } from '$lib/types/robot';
+import type {
+	ApiFailureGroup,
+	ApiSearchMatch,
+	ApiSuite,
+	ApiSuiteNode,
+	ApiSuiteTestPage,
+	ApiTest,
+	ApiTestHistory,
+	ApiTestRunComparison,
+	TestRunResponse
+} from '$lib/types/generated';
+import type { ApiSuiteKeywords, ApiTestKeywords } from '$lib/types/robot';
 
 
 const ROBOT_BASE_API = `${API_BASE_URL}/robot`;
@@ -16,6 +26,43 @@ export async function getTestRunById(id: number): Promise<TestRunResponse | null
 	}
 }
 
+// The suite tree of large runs, level by level: the root suites of the run
+// when `parentSuiteId` is not given, else the children of that suite.
+export async function getSuiteNodes(
+	testRunId: number,
+	parentSuiteId?: number
+): Promise<ApiSuiteNode[]> {
+	const url =
+		parentSuiteId === undefined
+			? `${ROBOT_BASE_API}/test-runs/${testRunId}/suites`
+			: `${ROBOT_BASE_API}/suites/${parentSuiteId}/suites`;
+	try {
+		const response = await fetch(url);
+		if (!response.ok) throw new Error('Failed to fetch suites');
+		return await response.json();
+	} catch (error) {
+		console.error('Error fetching suites:', error);
+		return [];
+	}
+}
+
+export async function getSuiteTests(
+	suiteId: number,
+	cursor?: string,
+	limit = 100
+): Promise<ApiSuiteTestPage | null> {
+	const query = new URLSearchParams({ limit: `${limit}` });
+	if (cursor) query.set('cursor', cursor);
+	try {
+		const response = await fetch(`${ROBOT_BASE_API}/suites/${suiteId}/tests?${query}`);
+		if (!response.ok) throw new Error('Failed to fetch suite tests');
+		return await response.json();
+	} catch (error) {
+		console.error('Error fetching suite tests:', error);
+		return null;
+	}
+}
+
 export async function getSuiteKeywords(suiteId: number): Promise<ApiSuiteKeywords | null> {
 	try {
 		const response = await fetch(`${ROBOT_BASE_API}/suites/${suiteId}/keywords`);
@@ -27,7 +74,7 @@ export async function getSuiteKeywords(suiteId: number): Promise<ApiSuiteKeyword
 	}
 }
 
-export async function getTestKeywords(testId: number): Promise<RobotBaseBody[] | null> {
+export async function getTestKeywords(testId: number): Promise<ApiTestKeywords | null> {
 	try {
 		const response = await fetch(`${ROBOT_BASE_API}/tests/${testId}/keywords`);
 		if (!response.ok) throw new Error('Failed to fetch test keywords');
@@ -38,6 +85,68 @@ export async function getTestKeywords(testId: number): Promise<RobotBaseBody[] |
 	}
 }
 
+/** Outcomes of the test in the runs of its project, latest first. */
+export async function getTestHistory(testId: number, limit = 20): Promise<ApiTestHistory | null> {
+	try {
+		const response = await fetch(`${ROBOT_BASE_API}/tests/${testId}/history?limit=${limit}`);
+		if (!response.ok) throw new Error('Failed to fetch test history');
+		return await response.json();
+	} catch (error) {
+		console.error('Error fetching test history:', error);
+		return null;
+	}
+}
+
+// `query` is the query string of the search page, passed on as is.
+export async function search(query: string): Promise<ApiSearchMatch[]> {
+	try {
+		const response = await fetch(`${ROBOT_BASE_API}/search${query}`);
+		if (!response.ok) throw new Error('Failed to search');
+		return await response.json();
+	} catch (error) {
+		console.error('Error searching:', error);
+		return [];
+	}
+}
+
+export async function getFailureGroups(testRunId: number): Promise<ApiFailureGroup[]> {
+	try {
+		const response = await fetch(`${ROBOT_BASE_API}/test-runs/${testRunId}/failures`);
+		if (!response.ok) throw new Error('Failed to fetch failure groups');
+		return await response.json();
+	} catch (error) {
+		console.error('Error fetching failure groups:', error);
+		return [];
+	}
+}
+
+export async function compareTestRuns(
+	baseId: number,
+	targetId: number
+): Promise<ApiTestRunComparison | null> {
+	try {
+		const response = await fetch(`${ROBOT_BASE_API}/test-runs/${baseId}/compare/${targetId}`);
+		if (!response.ok) throw new Error('Failed to compare test runs');
+		return await response.json();
+	} catch (error) {
+		console.error('Error comparing test runs:', error);
+		return null;
+	}
+}
+
+// Files uploaded in an archive with the output, linked relatively from messages.
+export function getArtifactUrl(testRunId: number, path: string): string {
+	if (/^([a-z]+:|\/)/i.test(path)) {
+		return path;
+	}
+	return `${ROBOT_BASE_API}/test-runs/${testRunId}/artifacts/${path}`;
+}
+
+// Download of an uploaded output file, as it was uploaded.
+export function getOutputFileUrl(sha1: string): string {
+	return `${ROBOT_BASE_API}/outputs/${sha1}`;
+}
+
 export function getFailedTestIdentifiers(suites: ApiSuite[]): string[] {
 	const failedTestIds: string[] = [];
 	for (const suite of suites) {
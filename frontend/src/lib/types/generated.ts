@@ -7,6 +7,41 @@ export interface ApiError {
 	timestamp: string;
 	level: string;
 	content: string;
+	html: boolean;
+}
+
+export enum ApiImportStatus {
+	Queued = "queued",
+	Parsing = "parsing",
+	Saving = "saving",
+	Done = "done",
+	Failed = "failed",
+}
+
+export interface ApiImportProblem {
+	path: string;
+	reason: string;
+}
+
+export interface ApiImportJob {
+	id: number;
+	projectId: number;
+	fileNames: string[];
+	status: ApiImportStatus;
+	testRunId?: number;
+	alreadyImported: boolean;
+	/** Imports a stored test run again rather than an upload. */
+	reimport: boolean;
+	error?: string;
+	problems: ApiImportProblem[];
+	createDate: string;
+	updateDate: string;
+}
+
+export enum ApiSourceFormat {
+	RobotXml = "robotXml",
+	RobotJson = "robotJson",
+	Junit = "junit",
 }
 
 export enum ApiStatisticType {
@@ -29,14 +64,32 @@ export interface ApiStatistic {
 export interface ApiTest {
 	id: number;
 	name: string;
-	line: number;
+	line?: number;
 	identifier: string;
 	tags: string[];
 	status: string;
+	/** Failure or skip reason, HTML when it starts with `*HTML*`. */
+	message?: string;
 	startTime: string;
 	endTime: string;
 	doc?: string;
 	timeout?: string;
+	attempt: number;
+	passedOnRetry: boolean;
+}
+
+export interface ApiTestAttempt {
+	id: number;
+	attempt: number;
+	status: string;
+	startTime: string;
+	endTime: string;
+}
+
+/** Entry of the `Metadata` setting of a suite. */
+export interface ApiMetadata {
+	name: string;
+	value: string;
 }
 
 export interface ApiSuite {
@@ -44,19 +97,71 @@ export interface ApiSuite {
 	name: string;
 	source?: string;
 	status: string;
+	/** Failure or skip reason, HTML when it starts with `*HTML*`. */
+	message?: string;
 	startTime: string;
 	endTime: string;
 	doc?: string;
 	identifier: string;
+	metadata: ApiMetadata[];
 	suites: ApiSuite[];
 	tests: ApiTest[];
 }
 
+/**
+ * A suite without its suites and tests, to load the tree of a large run
+ * level by level. The counts cover the tests of all the suites under it.
+ */
+export interface ApiSuiteNode {
+	id: number;
+	name: string;
+	source?: string;
+	status: string;
+	/** Failure or skip reason, HTML when it starts with `*HTML*`. */
+	message?: string;
+	startTime: string;
+	endTime: string;
+	doc?: string;
+	identifier: string;
+	metadata: ApiMetadata[];
+	/** Child suites, listed by `/suites/{id}/suites`. */
+	suiteCount: number;
+	/** Tests of the suite itself, listed by `/suites/{id}/tests`. */
+	testCount: number;
+	statistics: ApiStatisticCounts;
+}
+
+/** Tests of a suite in the order they ran, `nextCursor` gives the next ones. */
+export interface ApiSuiteTestPage {
+	tests: ApiTest[];
+	nextCursor?: string;
+}
+
 export interface ApiSuiteKeywords {
 	setupKeyword?: Value;
 	teardownKeyword?: Value;
 }
 
+export interface ApiTestKeywords {
+	setupKeyword?: Value;
+	/** Body of the test, without its setup and teardown. */
+	keywords?: Value;
+	teardownKeyword?: Value;
+}
+
+export interface ApiCiMetadata {
+	system?: string;
+	buildUrl?: string;
+	buildNumber?: string;
+	robotGitHash?: string;
+	appGitHash?: string;
+}
+
+export interface ApiLabel {
+	name: string;
+	value: string;
+}
+
 export interface ApiTestRunSummary {
 	testRunId: number;
 	testRunDate: string;
@@ -67,6 +172,10 @@ export interface ApiTestRunSummary {
 	skippedTests: number;
 	errorCount: number;
 	appVersion: string;
+	environment?: string;
+	branch?: string;
+	ci: ApiCiMetadata;
+	labels: ApiLabel[];
 }
 
 export interface ProjectOverviewResponse {
@@ -75,6 +184,22 @@ export interface ProjectOverviewResponse {
 	createDate: string;
 	testRunCount: number;
 	lastTestRunSummary?: ApiTestRunSummary;
+	contexts: ApiProjectContext[];
+}
+
+/** The runs of a project against one environment and branch. */
+export interface ApiProjectContext {
+	environment?: string;
+	branch?: string;
+	testRunCount: number;
+	lastTestRunSummary: ApiTestRunSummary;
+}
+
+/** A page of the test runs of a project, `nextCursor` gives the next one. */
+export interface ApiTestRunPage {
+	testRunsSummaries: ApiTestRunSummary[];
+	totalCount: number;
+	nextCursor?: string;
 }
 
 export interface ProjectResponse {
@@ -85,6 +210,172 @@ export interface ProjectResponse {
 	testRunsSummaries: ApiTestRunSummary[];
 }
 
+export interface ApiArtifact {
+	path: string;
+	contentType: string;
+	size: number;
+}
+
+/** A test of a project that passes and fails on the same app version. */
+export interface ApiFlakyTest {
+	suite: string;
+	name: string;
+	/**
+	 * Share of consecutive runs of the same app version where the result
+	 * changed, from 0 to 1.
+	 */
+	score: number;
+	runCount: number;
+	transitions: number;
+	flips: number;
+	lastTestRunId: number;
+	lastRunDate: string;
+	lastAppVersion: string;
+	lastStatus: string;
+}
+
+export interface ApiTestHistoryEntry {
+	testId: number;
+	testRunId: number;
+	testRunDate: string;
+	appVersion: string;
+	environment?: string;
+	branch?: string;
+	status: string;
+	/** Failure or skip reason, HTML when it starts with `*HTML*`. */
+	message?: string;
+	startTime: string;
+	endTime: string;
+	durationMs: number;
+	attempt: number;
+}
+
+/** The outcomes of a test across runs, `nextCursor` gives the older ones. */
+export interface ApiTestHistory {
+	projectId: number;
+	suite: string;
+	name: string;
+	entries: ApiTestHistoryEntry[];
+	nextCursor?: string;
+}
+
+/**
+ * A test or keyword whose text matches a search. Keywords are found by
+ * their name, arguments and messages, tests by their name, documentation
+ * and message.
+ */
+export interface ApiSearchMatch {
+	projectId: number;
+	testRunId: number;
+	testRunDate: string;
+	suiteId: number;
+	suiteIdentifier: string;
+	suite: string;
+	/** Missing for the setup and teardown keywords of suites. */
+	testId?: number;
+	testIdentifier?: string;
+	testName?: string;
+	/** `setup`, `body` or `teardown`, missing when the test itself matches. */
+	keywordType?: string;
+	/**
+	 * JSON pointer of the keyword or message in the keywords of this type,
+	 * e.g. `/0/kw/keywords/2/kw`.
+	 */
+	keywordPath?: string;
+	keywordName?: string;
+	/** The part of the text around the matching words. */
+	excerpt: string;
+}
+
+export interface ApiFailedTest {
+	testId: number;
+	suite: string;
+	name: string;
+}
+
+/**
+ * The failed tests of a run whose messages share a signature, the message
+ * without its quoted values, UUIDs, timestamps and numbers.
+ */
+export interface ApiFailureGroup {
+	signature: string;
+	failureCount: number;
+	/** Up to 3 distinct messages of the group. */
+	exampleMessages: string[];
+	tests: ApiFailedTest[];
+	/** Date of the first run of the project with a failure of this signature. */
+	firstSeenDate: string;
+}
+
+/** The failures of the runs of a project that share a signature. */
+export interface ApiProjectFailureGroup {
+	signature: string;
+	failureCount: number;
+	/** Distinct tests, by the path of their suite and their name. */
+	testCount: number;
+	testRunCount: number;
+	/** Up to 3 distinct messages of the group. */
+	exampleMessages: string[];
+	firstSeenDate: string;
+	lastSeenDate: string;
+	lastTestRunId: number;
+}
+
+export interface ApiComparedTest {
+	suite: string;
+	name: string;
+	baseTestId?: number;
+	baseStatus?: string;
+	baseDurationMs?: number;
+	targetTestId?: number;
+	targetStatus?: string;
+	targetDurationMs?: number;
+	/** Only set when the test is in both runs, positive when it got slower. */
+	durationChangeMs?: number;
+}
+
+export interface ApiStatisticCounts {
+	passCount: number;
+	failCount: number;
+	skipCount: number;
+}
+
+export interface ApiTagStatisticChange {
+	tag: string;
+	/** Missing when the run has no test with the tag. */
+	base?: ApiStatisticCounts;
+	target?: ApiStatisticCounts;
+	passChange: number;
+	failChange: number;
+	skipChange: number;
+}
+
+/**
+ * The tests of two runs matched by the path of their suite and their name,
+ * from the base run to the target run.
+ */
+export interface ApiTestRunComparison {
+	baseTestRunId: number;
+	targetTestRunId: number;
+	newlyFailing: ApiComparedTest[];
+	fixed: ApiComparedTest[];
+	stillFailing: ApiComparedTest[];
+	/** Skipped and not run tests count as passing. */
+	stillPassing: ApiComparedTest[];
+	/** Tests of the target run only. */
+	added: ApiComparedTest[];
+	/** Tests of the base run only. */
+	removed: ApiComparedTest[];
+	/** Tags whose counts changed. */
+	tags: ApiTagStatisticChange[];
+}
+
+export interface ApiTestRunPart {
+	fileName?: string;
+	sha1: string;
+	rerun: boolean;
+}
+
 export interface TestRunResponse {
 	id: number;
 	projectId: number;
@@ -92,10 +383,17 @@ export interface TestRunResponse {
 	generator: string;
 	generatedDate: string;
 	schemaVersion: string;
+	sourceFormat: ApiSourceFormat;
 	importedDate: string;
 	suites: ApiSuite[];
 	statistics: ApiStatistic[];
 	errors: ApiError[];
+	parts: ApiTestRunPart[];
+	artifacts: ApiArtifact[];
 	appVersion: string;
+	environment?: string;
+	branch?: string;
+	ci: ApiCiMetadata;
+	labels: ApiLabel[];
 }
 
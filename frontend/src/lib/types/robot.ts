@@ -5,6 +5,12 @@ export interface ApiSuiteKeywords {
     teardownKeyword?: RobotKeyword;
 }
 
+export interface ApiTestKeywords {
+    setupKeyword?: RobotKeyword;
+    keywords?: RobotBaseBody[];
+    teardownKeyword?: RobotKeyword;
+}
+
 export type RobotBaseBody =
     | { kw: RobotKeyword }
     | { for: RobotFor }
@@ -17,18 +23,36 @@ export type RobotBaseBody =
     | { continue: RobotContinue }
     | { break: RobotBreak }
     | { message: RobotMessage }
+    | { error: RobotError }
+    // Element the parser did not know, kept as raw JSON in tolerant mode
+    | { unknown: unknown }
 
 export interface RobotStatus {
     status: string;
-    start_time: string;
-    end_time: string;
+    // Robot Framework 6 and older
+    start_time?: string;
+    end_time?: string;
+    // Robot Framework 7 and newer, elapsed in seconds
+    start?: string;
+    elapsed?: string;
+    // Failure or skip reason, HTML when it starts with *HTML*
+    message?: string;
 }
 
 export interface RobotBreak {
+    msg: RobotMessage[];
     status: RobotStatus;
 }
 
 export interface RobotContinue {
+    msg: RobotMessage[];
+    status: RobotStatus;
+}
+
+// A row of the data Robot Framework could not make sense of
+export interface RobotError {
+    values: string[];
+    msg: RobotMessage[];
     status: RobotStatus;
 }
 
@@ -51,6 +75,7 @@ export interface RobotFor {
     iters: RobotForIter[];
     vars: string[];
     values: string[];
+    msg: RobotMessage[];
     status: RobotStatus;
 }
 
@@ -69,12 +94,17 @@ export interface RobotIfBranch {
 
 export interface RobotIf {
     branches: RobotIfBranch[];
+    msg: RobotMessage[];
     status: RobotStatus;
 }
 
 export interface RobotMessage {
-    timestamp: string;
+    // Robot Framework 6 and older
+    timestamp?: string;
+    // Robot Framework 7 and newer
+    time?: string;
     level: string;
+    html: boolean;
     value: string;
 }
 
@@ -85,6 +115,10 @@ export interface RobotKeyword {
     owner?: string;
     library?: string;
     type_?: RobotKeywordType;
+    // Name as written in the data, for keywords with embedded arguments
+    source_name?: string;
+    source?: string;
+    lineno?: string;
     msg: RobotMessage[];
     keywords: RobotBaseBody[];
     var: string[];
@@ -97,6 +131,7 @@ export interface RobotKeyword {
 
 export interface RobotReturn {
     value: string[];
+    msg: RobotMessage[];
     status: RobotStatus;
 }
 
@@ -104,12 +139,14 @@ export interface RobotTryBranch {
     type: string;
     patternType?: string;
     assign?: string;
+    patterns: string[];
     children: RobotBaseBody[];
     status: RobotStatus;
 }
 
 export interface RobotTry {
     branches: RobotTryBranch[];
+    msg: RobotMessage[];
     status: RobotStatus;
 }
 
@@ -117,7 +154,7 @@ export interface RobotVar {
     name: string;
     scope?: string;
     separator?: string;
-    message: RobotMessage;
+    message?: RobotMessage;
     vars: string[];
     status: RobotStatus;
 }
@@ -133,6 +170,7 @@ export interface RobotWhile {
     onLimit: string[];
     onLimitMessage: string[];
     iters: RobotWhileIter[];
+    msg: RobotMessage[];
     status: RobotStatus;
 }
 
@@ -147,5 +185,7 @@ export type KeywordType =
     | 'RobotReturn'
     | 'RobotContinue'
     | 'RobotBreak'
-    | 'RobotMessage';
+    | 'RobotMessage'
+    | 'RobotError'
+    | 'RobotUnknown';
 
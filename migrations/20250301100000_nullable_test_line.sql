@@ -0,0 +1,3 @@
+-- Robot Framework 4 does not record the line of tests
+ALTER TABLE tests
+ALTER COLUMN line DROP NOT NULL;
\ No newline at end of file
@@ -0,0 +1,7 @@
+CREATE TYPE source_format AS ENUM ('robot_xml', 'robot_json', 'junit');
+ALTER TABLE test_runs
+ADD COLUMN source_format source_format NOT NULL DEFAULT 'robot_xml';
+
+-- JUnit suites and merged Robot suites have no source file
+ALTER TABLE suites
+ALTER COLUMN source DROP NOT NULL;
\ No newline at end of file
@@ -0,0 +1,14 @@
+-- Output files a test run was imported from, several when shards are merged
+CREATE TABLE test_run_parts (
+    test_run_id INTEGER NOT NULL,
+    -- foreign key to test_runs.id
+    part_index INTEGER NOT NULL,
+    file_name TEXT,
+    sha1 TEXT UNIQUE NOT NULL,
+    PRIMARY KEY (test_run_id, part_index)
+);
+INSERT INTO test_run_parts (test_run_id, part_index, sha1)
+SELECT id,
+    0,
+    sha1
+FROM test_runs;
\ No newline at end of file
@@ -0,0 +1,9 @@
+-- Results of a rerun replace the matching tests, earlier attempts are kept
+ALTER TABLE tests
+ADD COLUMN attempt INTEGER NOT NULL DEFAULT 1,
+ADD COLUMN superseded_by INTEGER;
+-- foreign key to tests.id
+CREATE INDEX idx_tests_superseded_by ON tests (superseded_by);
+
+ALTER TABLE test_run_parts
+ADD COLUMN rerun BOOLEAN NOT NULL DEFAULT false;
\ No newline at end of file
@@ -0,0 +1,10 @@
+-- Files uploaded in an archive along the output files, e.g. log.html and screenshots
+CREATE TABLE test_run_artifacts (
+    test_run_id INTEGER NOT NULL,
+    -- foreign key to test_runs.id
+    path TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    size BIGINT NOT NULL,
+    content BYTEA NOT NULL,
+    PRIMARY KEY (test_run_id, path)
+);
\ No newline at end of file
@@ -0,0 +1,6 @@
+-- Gzipped copies of the uploaded output files, to import them again after parser changes
+CREATE TABLE output_files (
+    sha1 TEXT PRIMARY KEY,
+    size BIGINT NOT NULL,
+    content BYTEA NOT NULL
+);
\ No newline at end of file
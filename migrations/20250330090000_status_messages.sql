@@ -0,0 +1,9 @@
+-- Failure or skip reason written in the <status> element
+ALTER TABLE suites
+ADD COLUMN message TEXT;
+
+ALTER TABLE tests
+ADD COLUMN message TEXT;
+
+ALTER TABLE test_run_errors
+ADD COLUMN html BOOLEAN NOT NULL DEFAULT false;
\ No newline at end of file
@@ -0,0 +1,30 @@
+-- Test setup and teardown are stored apart from the body, like the ones of suites
+ALTER TABLE test_keywords
+ADD COLUMN type TEXT NOT NULL DEFAULT 'body';
+
+-- Until now they were the first and last keywords of the body
+INSERT INTO test_keywords (test_id, type, value)
+SELECT test_id, 'teardown', value -> -1 -> 'kw'
+FROM test_keywords
+WHERE type = 'body'
+  AND value -> -1 -> 'kw' ->> 'type_' = 'TEARDOWN';
+
+UPDATE test_keywords
+SET value = value - -1
+WHERE type = 'body'
+  AND value -> -1 -> 'kw' ->> 'type_' = 'TEARDOWN';
+
+INSERT INTO test_keywords (test_id, type, value)
+SELECT test_id, 'setup', value -> 0 -> 'kw'
+FROM test_keywords
+WHERE type = 'body'
+  AND value -> 0 -> 'kw' ->> 'type_' = 'SETUP';
+
+UPDATE test_keywords
+SET value = value - 0
+WHERE type = 'body'
+  AND value -> 0 -> 'kw' ->> 'type_' = 'SETUP';
+
+DELETE FROM test_keywords
+WHERE type = 'body'
+  AND value = '[]'::jsonb;
@@ -0,0 +1,9 @@
+-- Metadata setting of the suites, e.g. Browser or Version
+CREATE TABLE suite_metadata (
+    suite_id INTEGER NOT NULL,
+    position INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    value TEXT NOT NULL,
+    PRIMARY KEY (suite_id, position)
+);
+CREATE INDEX idx_suite_metadata_name_value ON suite_metadata (name, value);
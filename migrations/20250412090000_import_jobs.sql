@@ -0,0 +1,18 @@
+-- Uploads imported in the background, with their progress and outcome
+CREATE TYPE import_status AS ENUM ('queued', 'parsing', 'saving', 'done', 'failed');
+CREATE TABLE import_jobs (
+    id SERIAL PRIMARY KEY,
+    project_id INTEGER NOT NULL,
+    -- foreign key to projects.id
+    file_names TEXT [] NOT NULL,
+    status import_status NOT NULL DEFAULT 'queued',
+    test_run_id INTEGER,
+    -- foreign key to test_runs.id, set once done
+    already_imported BOOLEAN NOT NULL DEFAULT FALSE,
+    error TEXT,
+    problems JSONB,
+    -- values of the output file that cannot be stored, when failed because of them
+    create_date TIMESTAMP NOT NULL DEFAULT NOW(),
+    update_date TIMESTAMP NOT NULL DEFAULT NOW()
+);
+CREATE INDEX idx_import_jobs_project_id ON import_jobs (project_id, id DESC);
\ No newline at end of file
@@ -0,0 +1,18 @@
+-- Context of the CI build a test run comes from
+ALTER TABLE test_runs
+ADD COLUMN environment TEXT,
+    ADD COLUMN ci_system TEXT,
+    ADD COLUMN build_url TEXT,
+    ADD COLUMN build_number TEXT,
+    ADD COLUMN robot_git_hash TEXT,
+    ADD COLUMN app_git_hash TEXT;
+CREATE INDEX idx_test_runs_project_id_environment ON test_runs (project_id, environment);
+-- Free-form labels of a test run, e.g. team=checkout
+CREATE TABLE test_run_labels (
+    test_run_id INTEGER NOT NULL,
+    -- foreign key to test_runs.id
+    name TEXT NOT NULL,
+    value TEXT NOT NULL,
+    PRIMARY KEY (test_run_id, name)
+);
+CREATE INDEX idx_test_run_labels_name_value ON test_run_labels (name, value);
\ No newline at end of file
@@ -0,0 +1,5 @@
+-- Branch of the application a test run was tested against
+ALTER TABLE test_runs
+ADD COLUMN branch TEXT;
+DROP INDEX idx_test_runs_project_id_environment;
+CREATE INDEX idx_test_runs_project_id_environment_branch ON test_runs (project_id, environment, branch, generated_date DESC);
\ No newline at end of file
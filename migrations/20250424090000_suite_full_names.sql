@@ -0,0 +1,43 @@
+-- Path of a suite from the root suite of its output file, e.g. Tests.Login,
+-- which identifies a test across runs with its name. The root suite of
+-- combined outputs is not part of the paths.
+ALTER TABLE suites
+ADD COLUMN full_name TEXT;
+WITH RECURSIVE combined_runs AS (
+    SELECT test_run_id
+    FROM test_run_parts
+    WHERE NOT rerun
+    GROUP BY test_run_id
+    HAVING COUNT(*) > 1
+),
+suite_paths AS (
+    SELECT s.id,
+        s.name AS full_name,
+        s.test_run_id IN (
+            SELECT test_run_id
+            FROM combined_runs
+        ) AS combined
+    FROM suites s
+    WHERE s.parent_suite_id IS NULL
+    UNION ALL
+    SELECT s.id,
+        CASE
+            WHEN suite_paths.combined THEN s.name
+            ELSE suite_paths.full_name || '.' || s.name
+        END,
+        false
+    FROM suites s
+        JOIN suite_paths ON s.parent_suite_id = suite_paths.id
+)
+UPDATE suites
+SET full_name = suite_paths.full_name
+FROM suite_paths
+WHERE suites.id = suite_paths.id;
+UPDATE suites
+SET full_name = name
+WHERE full_name IS NULL;
+ALTER TABLE suites
+ALTER COLUMN full_name
+SET NOT NULL;
+CREATE INDEX idx_suites_full_name ON suites (full_name);
+CREATE INDEX idx_tests_suite_id_name ON tests (suite_id, name);
\ No newline at end of file
@@ -0,0 +1,106 @@
+-- Flakiness of each test of a project, kept up to date as runs are imported:
+-- how often its results flip between PASS and FAIL from one run to the next
+-- while the app version stays the same. Tests are identified by the full
+-- name of their suite and their name.
+CREATE TABLE test_flakiness (
+    project_id INTEGER NOT NULL,
+    -- foreign key to projects.id
+    suite TEXT NOT NULL,
+    name TEXT NOT NULL,
+    -- Runs where the test passed or failed
+    run_count INTEGER NOT NULL,
+    -- Consecutive runs of the same app version
+    transitions INTEGER NOT NULL,
+    -- Of which the result changed
+    flips INTEGER NOT NULL,
+    score DOUBLE PRECISION GENERATED ALWAYS AS (
+        CASE
+            WHEN transitions > 0 THEN flips::DOUBLE PRECISION / transitions
+            ELSE 0
+        END
+    ) STORED,
+    last_test_run_id INTEGER NOT NULL,
+    -- foreign key to test_runs.id
+    last_run_date TIMESTAMP NOT NULL,
+    last_app_version TEXT NOT NULL,
+    last_status TEXT NOT NULL,
+    PRIMARY KEY (project_id, suite, name)
+);
+CREATE INDEX idx_test_flakiness_project_id_score ON test_flakiness (project_id, score DESC);
+WITH results AS (
+    SELECT DISTINCT ON (tr.id, s.full_name, t.name) tr.project_id,
+        s.full_name AS suite,
+        t.name,
+        tr.id AS test_run_id,
+        tr.generated_date,
+        tr.application_version,
+        t.status
+    FROM tests t
+        JOIN suites s ON s.id = t.suite_id
+        JOIN test_runs tr ON tr.id = s.test_run_id
+    WHERE t.superseded_by IS NULL
+        AND t.status IN ('PASS', 'FAIL')
+    ORDER BY tr.id,
+        s.full_name,
+        t.name,
+        t.id DESC
+),
+sequenced AS (
+    SELECT results.*,
+        LAG(status) OVER run_order AS previous_status,
+        LAG(application_version) OVER run_order AS previous_app_version,
+        ROW_NUMBER() OVER (
+            PARTITION BY project_id,
+            suite,
+            name
+            ORDER BY generated_date DESC,
+                test_run_id DESC
+        ) AS recency
+    FROM results
+    WINDOW run_order AS (
+            PARTITION BY project_id,
+            suite,
+            name
+            ORDER BY generated_date,
+                test_run_id
+        )
+)
+INSERT INTO test_flakiness (
+        project_id,
+        suite,
+        name,
+        run_count,
+        transitions,
+        flips,
+        last_test_run_id,
+        last_run_date,
+        last_app_version,
+        last_status
+    )
+SELECT project_id,
+    suite,
+    name,
+    COUNT(*),
+    COUNT(*) FILTER (
+        WHERE previous_app_version = application_version
+    ),
+    COUNT(*) FILTER (
+        WHERE previous_app_version = application_version
+            AND previous_status <> status
+    ),
+    MAX(test_run_id) FILTER (
+        WHERE recency = 1
+    ),
+    MAX(generated_date) FILTER (
+        WHERE recency = 1
+    ),
+    MAX(application_version) FILTER (
+        WHERE recency = 1
+    ),
+    MAX(status) FILTER (
+        WHERE recency = 1
+    )
+FROM sequenced
+GROUP BY project_id,
+    suite,
+    name;
\ No newline at end of file
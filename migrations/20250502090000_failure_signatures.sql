@@ -0,0 +1,46 @@
+-- A failure message without the parts that vary from one failure to the next,
+-- so that the tests failing for the same cause share it: quoted values,
+-- UUIDs, timestamps and numbers are replaced, in that order
+CREATE FUNCTION failure_signature(message TEXT) RETURNS TEXT LANGUAGE sql IMMUTABLE PARALLEL SAFE AS $$
+SELECT left(
+        btrim(
+            regexp_replace(
+                regexp_replace(
+                    regexp_replace(
+                        regexp_replace(
+                            regexp_replace(
+                                message,
+                                '''[^'']*''|"[^"]*"',
+                                '<value>',
+                                'g'
+                            ),
+                            '[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}',
+                            '<uuid>',
+                            'g'
+                        ),
+                        '\d{4}-\d{2}-\d{2}([T ]\d{2}:\d{2}(:\d{2}(\.\d+)?)?(Z|[+-]\d{2}:?\d{2})?)?|\d{2}:\d{2}:\d{2}(\.\d+)?',
+                        '<timestamp>',
+                        'g'
+                    ),
+                    '0x[0-9a-fA-F]+|\d+(\.\d+)?',
+                    '<n>',
+                    'g'
+                ),
+                '\s+',
+                ' ',
+                'g'
+            )
+        ),
+        1000
+    ) $$;
+
+-- Only failed tests have one, an empty one when they have no message
+ALTER TABLE tests
+ADD COLUMN failure_signature TEXT GENERATED ALWAYS AS (
+        CASE
+            WHEN status = 'FAIL' THEN failure_signature(COALESCE(message, ''))
+        END
+    ) STORED;
+
+CREATE INDEX idx_tests_failure_signature ON tests (failure_signature)
+WHERE failure_signature IS NOT NULL;
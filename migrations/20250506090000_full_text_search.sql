@@ -0,0 +1,14 @@
+-- Full-text indexes of the search, with the simple configuration since log
+-- messages are not prose: words are lowercased but not stemmed
+CREATE INDEX idx_tests_search ON tests USING GIN (
+    to_tsvector(
+        'simple',
+        name || ' ' || COALESCE(doc, '') || ' ' || COALESCE(message, '')
+    )
+);
+
+-- Every string of the keyword trees, the keywords that match are then found
+-- by walking the trees of the rows found
+CREATE INDEX idx_test_keywords_search ON test_keywords USING GIN (jsonb_to_tsvector('simple', value, '["string"]'));
+
+CREATE INDEX idx_suite_keywords_search ON suite_keywords USING GIN (jsonb_to_tsvector('simple', value, '["string"]'));
@@ -0,0 +1,7 @@
+-- The suite tree of a run is loaded level by level, and the tests of a suite
+-- page by page in the order they ran
+CREATE INDEX idx_suites_test_run_id_parent_suite_id ON suites (test_run_id, parent_suite_id);
+
+CREATE INDEX idx_suites_parent_suite_id ON suites (parent_suite_id);
+
+CREATE INDEX idx_tests_suite_id_start_time ON tests (suite_id, start_time, id);
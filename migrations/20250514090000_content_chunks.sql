@@ -0,0 +1,26 @@
+-- Large contents are stored in chunks, so that they are never held whole in
+-- memory and are not bound by the 1 GB limit of a BYTEA value
+CREATE SEQUENCE content_ids AS BIGINT;
+
+CREATE TABLE content_chunks (
+    content_id BIGINT NOT NULL,
+    position INTEGER NOT NULL,
+    content BYTEA NOT NULL,
+    PRIMARY KEY (content_id, position)
+);
+
+ALTER TABLE test_run_artifacts
+ADD COLUMN content_id BIGINT;
+
+UPDATE test_run_artifacts
+SET content_id = nextval('content_ids');
+
+INSERT INTO content_chunks (content_id, position, content)
+SELECT content_id,
+    0,
+    content
+FROM test_run_artifacts;
+
+ALTER TABLE test_run_artifacts
+DROP COLUMN content,
+ALTER COLUMN content_id SET NOT NULL;
\ No newline at end of file
@@ -0,0 +1,16 @@
+-- Output files are stored in chunks too, see content_chunks
+ALTER TABLE output_files
+ADD COLUMN content_id BIGINT;
+
+UPDATE output_files
+SET content_id = nextval('content_ids');
+
+INSERT INTO content_chunks (content_id, position, content)
+SELECT content_id,
+    0,
+    content
+FROM output_files;
+
+ALTER TABLE output_files
+DROP COLUMN content,
+ALTER COLUMN content_id SET NOT NULL;
\ No newline at end of file
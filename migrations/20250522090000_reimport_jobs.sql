@@ -0,0 +1,4 @@
+-- Test runs imported again from their stored output files are followed as
+-- import jobs too
+ALTER TABLE import_jobs
+ADD COLUMN reimport BOOLEAN NOT NULL DEFAULT FALSE;
\ No newline at end of file
@@ -0,0 +1,35 @@
+-- Indexing every string of a keyword tree failed past the 1 MB a tsvector
+-- holds, so only the names, arguments and messages of its keywords are
+-- indexed now, as much of them as fits: at most 4 bytes a character, 200000
+-- characters stay under it
+DROP INDEX idx_test_keywords_search;
+
+DROP INDEX idx_suite_keywords_search;
+
+CREATE FUNCTION keywords_search_vector(tree JSONB) RETURNS TSVECTOR LANGUAGE sql IMMUTABLE PARALLEL SAFE AS $$
+SELECT to_tsvector('simple', left(string_agg(text, ' '), 200000))
+FROM jsonb_array_elements_text(
+        jsonb_path_query_array(
+            tree,
+            'strict $.** ? (@.args.type() == "array").name'
+        ) || jsonb_path_query_array(
+            tree,
+            'strict $.** ? (@.args.type() == "array").args[*]'
+        ) || jsonb_path_query_array(
+            tree,
+            'strict $.** ? (@.msg.type() == "array").msg[*].value'
+        ) || jsonb_path_query_array(
+            tree,
+            'strict $.** ? (@.message.value.type() == "string").message.value'
+        )
+    ) text $$;
+
+ALTER TABLE test_keywords
+ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS (keywords_search_vector(value)) STORED;
+
+ALTER TABLE suite_keywords
+ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS (keywords_search_vector(value)) STORED;
+
+CREATE INDEX idx_test_keywords_search ON test_keywords USING GIN (search_vector);
+
+CREATE INDEX idx_suite_keywords_search ON suite_keywords USING GIN (search_vector);
\ No newline at end of file
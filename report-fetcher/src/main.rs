@@ -1,9 +1,10 @@
 // src/main.rs
 use anyhow::{Context, Result};
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 use futures_util::StreamExt;
 use reqwest::multipart::{Form, Part};
 use serde::Serialize;
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use tokio::fs::{self, File};
 use tokio::io::AsyncWriteExt;
@@ -49,17 +50,105 @@ enum Commands {
         #[arg(long)]
         app_version: String,
 
+        #[command(flatten)]
+        context: Box<RunContext>,
+
+        #[arg(long, default_value = "http://localhost:5325/api/robot/upload")]
+        api_url: String,
+    },
+    /// Time the import of a synthetic output file
+    Bench {
+        /// Number of tests of the output file
+        #[arg(long, default_value_t = 20_000)]
+        tests: usize,
+
+        #[arg(long, default_value = "Bench")]
+        app_name: String,
+
         #[arg(long, default_value = "http://localhost:5325/api/robot/upload")]
         api_url: String,
     },
 }
 
+/// Where the tests ran, shown with the test run and used to filter runs.
+#[derive(Args, Default)]
+struct RunContext {
+    /// Test environment (e.g., staging)
+    #[arg(long)]
+    environment: Option<String>,
+
+    /// Branch of the tested app (e.g., main)
+    #[arg(long)]
+    branch: Option<String>,
+
+    /// CI system that ran the tests (e.g., jenkins)
+    #[arg(long)]
+    ci_system: Option<String>,
+
+    #[arg(long)]
+    build_url: Option<String>,
+
+    #[arg(long)]
+    build_number: Option<String>,
+
+    /// Commit of the robot tests
+    #[arg(long)]
+    robot_git_hash: Option<String>,
+
+    /// Commit of the tested app
+    #[arg(long)]
+    app_git_hash: Option<String>,
+
+    /// Free-form label, repeated for each one (e.g., team=checkout)
+    #[arg(long = "label", value_parser = parse_label)]
+    labels: Vec<(String, String)>,
+}
+
 #[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
 struct Metadata {
-    #[serde(rename = "appName")]
     app_name: String,
-    #[serde(rename = "appVersion")]
     app_version: String,
+    environment: Option<String>,
+    branch: Option<String>,
+    ci: CiMetadata,
+    labels: BTreeMap<String, String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CiMetadata {
+    system: Option<String>,
+    build_url: Option<String>,
+    build_number: Option<String>,
+    robot_git_hash: Option<String>,
+    app_git_hash: Option<String>,
+}
+
+impl Metadata {
+    fn new(app_name: &str, app_version: &str, context: RunContext) -> Self {
+        Metadata {
+            app_name: app_name.to_string(),
+            app_version: app_version.to_string(),
+            environment: context.environment,
+            branch: context.branch,
+            ci: CiMetadata {
+                system: context.ci_system,
+                build_url: context.build_url,
+                build_number: context.build_number,
+                robot_git_hash: context.robot_git_hash,
+                app_git_hash: context.app_git_hash,
+            },
+            labels: context.labels.into_iter().collect(),
+        }
+    }
+}
+
+fn parse_label(label: &str) -> Result<(String, String), String> {
+    label
+        .split_once('=')
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .ok_or_else(|| format!("invalid label {}, expected name=value", label))
 }
 
 #[tokio::main]
@@ -81,9 +170,18 @@ async fn main() -> Result<()> {
             folder_path,
             app_name,
             app_version,
+            context,
+            api_url,
+        } => {
+            let metadata = Metadata::new(&app_name, &app_version, *context);
+            upload_robot_data(&folder_path, &metadata, &api_url).await?;
+        }
+        Commands::Bench {
+            tests,
+            app_name,
             api_url,
         } => {
-            upload_robot_data(&folder_path, &app_name, &app_version, &api_url).await?;
+            bench_import(tests, &app_name, &api_url).await?;
         }
     }
 
@@ -198,12 +296,7 @@ async fn fetch_robot_data(
     Ok(())
 }
 
-async fn upload_robot_data(
-    folder_path: &Path,
-    app_name: &str,
-    app_version: &str,
-    api_url: &str,
-) -> Result<()> {
+async fn upload_robot_data(folder_path: &Path, metadata: &Metadata, api_url: &str) -> Result<()> {
     let xml_path = folder_path.join("output.xml");
 
     if !xml_path.exists() {
@@ -212,13 +305,12 @@ async fn upload_robot_data(
 
     info!("Uploading file: {}", xml_path.display());
     info!("API URL: {}", api_url);
-    info!("App Name: {}, App Version: {}", app_name, app_version);
+    info!(
+        "App Name: {}, App Version: {}",
+        metadata.app_name, metadata.app_version
+    );
 
-    let metadata = Metadata {
-        app_name: app_name.to_string(),
-        app_version: app_version.to_string(),
-    };
-    let metadata_json = serde_json::to_string(&metadata).context("Failed to serialize metadata")?;
+    let metadata_json = serde_json::to_string(metadata).context("Failed to serialize metadata")?;
 
     let file_contents = fs::read(&xml_path)
         .await
@@ -242,10 +334,15 @@ async fn upload_robot_data(
 
     if response.status().is_success() {
         info!("Upload successful: {}", response.status());
-        if let Ok(text) = response.text().await {
-            debug!("Response: {}", text);
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .context("Failed to read upload response")?;
+        debug!("Response: {}", body);
+        match body["jobId"].as_i64() {
+            Some(job_id) => wait_for_import(&client, api_url, job_id).await,
+            None => Ok(()),
         }
-        Ok(())
     } else {
         let status = response.status();
         let body = response.text().await.unwrap_or_default();
@@ -253,3 +350,163 @@ async fn upload_robot_data(
         anyhow::bail!("Upload failed: HTTP {}", status)
     }
 }
+
+/// Uploads are imported in the background, polls the import job until it is
+/// over.
+async fn wait_for_import(client: &reqwest::Client, api_url: &str, job_id: i64) -> Result<()> {
+    let job_url = format!("{}/imports/{}", api_url.trim_end_matches("/upload"), job_id);
+    let mut last_status = String::new();
+    loop {
+        let job: serde_json::Value = client
+            .get(&job_url)
+            .send()
+            .await
+            .context("Failed to get import job")?
+            .error_for_status()
+            .context("Failed to get import job")?
+            .json()
+            .await
+            .context("Failed to read import job")?;
+        let status = job["status"].as_str().unwrap_or_default();
+        if status != last_status {
+            info!("Import job {}: {}", job_id, status);
+            last_status = status.to_string();
+        }
+        match status {
+            "done" => {
+                info!("Imported test run {}", job["testRunId"]);
+                return Ok(());
+            }
+            "failed" => {
+                error!("Import failed: {} {}", job["error"], job["problems"]);
+                anyhow::bail!("Import job {} failed", job_id)
+            }
+            _ => tokio::time::sleep(std::time::Duration::from_millis(200)).await,
+        }
+    }
+}
+
+/// Writes an output file with `tests` tests spread over nested suites, each
+/// with tags and a few keywords, uploads it and reports how long the import
+/// took. The generation date makes every file a new test run.
+async fn bench_import(tests: usize, app_name: &str, api_url: &str) -> Result<()> {
+    let folder_path = std::env::temp_dir().join(format!("robot-bench-{}", std::process::id()));
+    fs::create_dir_all(&folder_path)
+        .await
+        .context("Failed to create benchmark directory")?;
+    let xml_path = folder_path.join("output.xml");
+    let content = synthetic_output(tests);
+    info!(
+        "Generated {} tests, {:.2} MB",
+        tests,
+        content.len() as f64 / 1_048_576.0
+    );
+    fs::write(&xml_path, content)
+        .await
+        .context("Failed to write output.xml")?;
+
+    let start = std::time::Instant::now();
+    let metadata = Metadata::new(app_name, "bench", RunContext::default());
+    let result = upload_robot_data(&folder_path, &metadata, api_url).await;
+    let elapsed = start.elapsed();
+    fs::remove_dir_all(&folder_path).await.ok();
+    result?;
+
+    info!(
+        "Imported {} tests in {:.2?} ({:.0} tests/s)",
+        tests,
+        elapsed,
+        tests as f64 / elapsed.as_secs_f64()
+    );
+    Ok(())
+}
+
+fn synthetic_output(tests: usize) -> String {
+    const TESTS_PER_SUITE: usize = 50;
+    const SUITES_PER_PARENT: usize = 20;
+
+    // Each run newer than the last, the way CI uploads them.
+    let generated = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%S%.6f");
+    let mut xml = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <robot generator=\"Robot 7.1.1 (bench)\" generated=\"{}\" rpa=\"false\" schemaversion=\"5\">\n\
+         <suite id=\"s1\" name=\"Bench\" source=\"/bench\">\n",
+        generated
+    );
+    let suite_count = tests.div_ceil(TESTS_PER_SUITE);
+    let parent_count = suite_count.div_ceil(SUITES_PER_PARENT);
+    let mut test_index = 0;
+    for parent in 0..parent_count {
+        let parent_id = format!("s1-s{}", parent + 1);
+        xml.push_str(&format!(
+            "<suite id=\"{}\" name=\"Area {}\" source=\"/bench/area_{}\">\n",
+            parent_id,
+            parent + 1,
+            parent + 1
+        ));
+        for suite in 0..SUITES_PER_PARENT {
+            if test_index >= tests {
+                break;
+            }
+            let suite_id = format!("{}-s{}", parent_id, suite + 1);
+            xml.push_str(&format!(
+                "<suite id=\"{}\" name=\"Feature {}\" source=\"/bench/area_{}/feature_{}.robot\">\n",
+                suite_id,
+                suite + 1,
+                parent + 1,
+                suite + 1
+            ));
+            for test in 0..TESTS_PER_SUITE {
+                if test_index >= tests {
+                    break;
+                }
+                let status = if test_index % 10 == 0 { "FAIL" } else { "PASS" };
+                xml.push_str(&format!(
+                    "<test id=\"{}-t{}\" name=\"Scenario {}\" line=\"{}\">\n\
+                     <kw name=\"Log\" owner=\"BuiltIn\">\n\
+                     <msg time=\"2025-01-01T00:00:01.000000\" level=\"INFO\">Step {}</msg>\n\
+                     <arg>Step {}</arg>\n\
+                     <status status=\"PASS\" start=\"2025-01-01T00:00:01.000000\" elapsed=\"0.001\"/>\n\
+                     </kw>\n\
+                     <kw name=\"Should Be Equal\" owner=\"BuiltIn\">\n\
+                     <arg>${{value}}</arg>\n\
+                     <arg>expected</arg>\n\
+                     <status status=\"{}\" start=\"2025-01-01T00:00:01.001000\" elapsed=\"0.001\"/>\n\
+                     </kw>\n\
+                     <tag>area-{}</tag>\n\
+                     <tag>bench</tag>\n\
+                     <status status=\"{}\" start=\"2025-01-01T00:00:01.000000\" elapsed=\"0.002\"/>\n\
+                     </test>\n",
+                    suite_id,
+                    test + 1,
+                    test_index + 1,
+                    test * 5 + 3,
+                    test_index + 1,
+                    test_index + 1,
+                    status,
+                    parent + 1,
+                    status
+                ));
+                test_index += 1;
+            }
+            xml.push_str(
+                "<status status=\"PASS\" start=\"2025-01-01T00:00:01.000000\" elapsed=\"1.0\"/>\n</suite>\n",
+            );
+        }
+        xml.push_str(
+            "<status status=\"PASS\" start=\"2025-01-01T00:00:01.000000\" elapsed=\"1.0\"/>\n</suite>\n",
+        );
+    }
+    let failed = tests.div_ceil(10);
+    xml.push_str(&format!(
+        "<status status=\"FAIL\" start=\"2025-01-01T00:00:00.500000\" elapsed=\"2.0\"/>\n\
+         </suite>\n\
+         <statistics>\n<total>\n<stat pass=\"{}\" fail=\"{}\" skip=\"0\">All Tests</stat>\n</total>\n\
+         <tag>\n</tag>\n<suite>\n</suite>\n</statistics>\n\
+         <errors>\n</errors>\n\
+         </robot>\n",
+        tests - failed,
+        failed
+    ));
+    xml
+}
@@ -0,0 +1,31 @@
+use std::{env, str::FromStr};
+
+pub struct ImportsConfig {
+    /// `IMPORT_WORKERS`, the uploads imported at the same time, each worker on
+    /// a thread of its own.
+    pub workers: usize,
+    /// `IMPORT_QUEUE_SIZE`, the uploads waiting for a worker before new ones
+    /// are refused.
+    pub queue_size: usize,
+    /// `ARCHIVE_MAX_SIZE`, the bytes an uploaded archive may expand to.
+    pub archive_max_size: u64,
+    /// `ARCHIVE_MAX_ENTRIES`, the files and directories an uploaded archive
+    /// may hold.
+    pub archive_max_entries: usize,
+}
+
+pub fn load() -> ImportsConfig {
+    ImportsConfig {
+        workers: env_number("IMPORT_WORKERS").unwrap_or(2),
+        queue_size: env_number("IMPORT_QUEUE_SIZE").unwrap_or(32),
+        archive_max_size: env_number("ARCHIVE_MAX_SIZE").unwrap_or(4 * 1024 * 1024 * 1024), // 4 GB
+        archive_max_entries: env_number("ARCHIVE_MAX_ENTRIES").unwrap_or(10_000),
+    }
+}
+
+fn env_number<T: FromStr + PartialOrd + Default>(name: &str) -> Option<T> {
+    env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .filter(|value| *value > T::default())
+}
@@ -1,2 +1,4 @@
 pub mod database;
+pub mod imports;
+pub mod parser;
 pub mod server;
@@ -0,0 +1,13 @@
+use std::env;
+
+pub struct ParserConfig {
+    /// `TOLERANT_PARSING=true` keeps the elements the parser does not know
+    /// instead of rejecting the upload.
+    pub tolerant: bool,
+}
+
+pub fn load() -> ParserConfig {
+    ParserConfig {
+        tolerant: env::var("TOLERANT_PARSING").is_ok_and(|value| value == "true"),
+    }
+}
@@ -1,7 +1,6 @@
 use actix_cors::Cors;
 use actix_multipart::{form::MultipartFormConfig, MultipartError};
 use actix_web::{App, Error, HttpRequest, HttpServer};
-use dotenv;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tracing::error;
@@ -22,14 +21,29 @@ async fn main() -> std::io::Result<()> {
     let pool = config::database::setup_database().await;
 
     let robot_repository = repositories::robot::RobotRepository::new(pool.clone());
-    let projects_repository = repositories::projects::ProjectsRepository::new(pool);
+    let projects_repository = repositories::projects::ProjectsRepository::new(pool.clone());
+    let imports_repository = repositories::imports::ImportsRepository::new(pool);
 
-    let robot_output_parser_service = Arc::new(services::parser::RobotOutputParserService::new());
+    let parser_config = config::parser::load();
+    let robot_output_parser_service = Arc::new(services::parser::RobotOutputParserService::new(
+        parser_config.tolerant,
+    ));
     let robot_service = Arc::new(services::robot::RobotService::new(robot_repository));
     let projects_service = Arc::new(services::projects::ProjectsService::new(
         projects_repository,
         Arc::clone(&robot_service),
     ));
+    let imports_config = config::imports::load();
+    let imports_service = Arc::new(
+        services::imports::ImportService::start(
+            imports_repository,
+            Arc::clone(&robot_service),
+            Arc::clone(&robot_output_parser_service),
+            &imports_config,
+        )
+        .await
+        .expect("Failed to start the import workers"),
+    );
 
     let server_config = config::server::load();
     let addr = format!("127.0.0.1:{}", server_config.port);
@@ -57,15 +71,17 @@ async fn main() -> std::io::Result<()> {
                     Arc::clone(&robot_service),
                     Arc::clone(&projects_service),
                     Arc::clone(&robot_output_parser_service),
+                    Arc::clone(&imports_service),
                 )
             })
             .configure(|cfg| {
                 routes::projects::ProjectsHandler::init(cfg, Arc::clone(&projects_service))
             })
+            .configure(|cfg| routes::admin::AdminHandler::init(cfg, Arc::clone(&imports_service)))
             .configure(|cfg| {
                 routes::shutdown::ShutdownHandler::init(cfg, Arc::clone(&shutdown_flag))
             })
-            .configure(|cfg| routes::frontend::FrontendHandler::init(cfg))
+            .configure(routes::frontend::FrontendHandler::init)
     })
     .bind(addr)?
     .run();
@@ -86,5 +102,5 @@ async fn main() -> std::io::Result<()> {
 
 fn handle_multipart_error(err: MultipartError, _req: &HttpRequest) -> Error {
     error!("Multipart error: {}", err);
-    return Error::from(err);
+    Error::from(err)
 }
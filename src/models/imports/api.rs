@@ -0,0 +1,53 @@
+use serde::Serialize;
+use typeshare::typeshare;
+
+use super::domain::ImportStatus;
+
+#[typeshare]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiImportJob {
+    pub id: i32,
+    pub project_id: i32,
+    pub file_names: Vec<String>,
+    pub status: ApiImportStatus,
+    pub test_run_id: Option<i32>,
+    pub already_imported: bool,
+    /// Imports a stored test run again rather than an upload.
+    pub reimport: bool,
+    pub error: Option<String>,
+    pub problems: Vec<ApiImportProblem>,
+    pub create_date: String,
+    pub update_date: String,
+}
+
+#[typeshare]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ApiImportStatus {
+    Queued,
+    Parsing,
+    Saving,
+    Done,
+    Failed,
+}
+
+impl From<ImportStatus> for ApiImportStatus {
+    fn from(status: ImportStatus) -> Self {
+        match status {
+            ImportStatus::Queued => ApiImportStatus::Queued,
+            ImportStatus::Parsing => ApiImportStatus::Parsing,
+            ImportStatus::Saving => ApiImportStatus::Saving,
+            ImportStatus::Done => ApiImportStatus::Done,
+            ImportStatus::Failed => ApiImportStatus::Failed,
+        }
+    }
+}
+
+#[typeshare]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiImportProblem {
+    pub path: String,
+    pub reason: String,
+}
@@ -0,0 +1,63 @@
+use chrono::NaiveDateTime;
+use serde_json::Value;
+
+use super::domain::{ImportJob, ImportProblem, ImportStatus};
+
+#[derive(sqlx::FromRow)]
+pub struct ImportJobDB {
+    pub id: i32,
+    pub project_id: i32,
+    pub file_names: Vec<String>,
+    pub status: ImportStatusDB,
+    pub test_run_id: Option<i32>,
+    pub already_imported: bool,
+    pub reimport: bool,
+    pub error: Option<String>,
+    pub problems: Option<Value>,
+    pub create_date: NaiveDateTime,
+    pub update_date: NaiveDateTime,
+}
+
+impl ImportJobDB {
+    pub fn into_job(self) -> ImportJob {
+        let problems: Vec<ImportProblem> = self
+            .problems
+            .and_then(|problems| serde_json::from_value(problems).ok())
+            .unwrap_or_default();
+        ImportJob {
+            id: self.id,
+            project_id: self.project_id,
+            file_names: self.file_names,
+            status: self.status.into(),
+            test_run_id: self.test_run_id,
+            already_imported: self.already_imported,
+            reimport: self.reimport,
+            error: self.error,
+            problems,
+            create_date: self.create_date,
+            update_date: self.update_date,
+        }
+    }
+}
+
+#[derive(sqlx::Type, Debug, Clone, Copy)]
+#[sqlx(type_name = "import_status", rename_all = "snake_case")]
+pub enum ImportStatusDB {
+    Queued,
+    Parsing,
+    Saving,
+    Done,
+    Failed,
+}
+
+impl From<ImportStatus> for ImportStatusDB {
+    fn from(status: ImportStatus) -> Self {
+        match status {
+            ImportStatus::Queued => ImportStatusDB::Queued,
+            ImportStatus::Parsing => ImportStatusDB::Parsing,
+            ImportStatus::Saving => ImportStatusDB::Saving,
+            ImportStatus::Done => ImportStatusDB::Done,
+            ImportStatus::Failed => ImportStatusDB::Failed,
+        }
+    }
+}
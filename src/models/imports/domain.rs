@@ -0,0 +1,83 @@
+use chrono::NaiveDateTime;
+use serde::Deserialize;
+
+use crate::utils;
+
+use super::{
+    api::{ApiImportJob, ApiImportProblem},
+    db::ImportStatusDB,
+};
+
+/// An upload, or a test run imported again, processed in the background
+/// from the time it is queued.
+pub struct ImportJob {
+    pub id: i32,
+    pub project_id: i32,
+    pub file_names: Vec<String>,
+    pub status: ImportStatus,
+    pub test_run_id: Option<i32>,
+    pub already_imported: bool,
+    /// Imports a stored test run again rather than an upload.
+    pub reimport: bool,
+    pub error: Option<String>,
+    pub problems: Vec<ImportProblem>,
+    pub create_date: NaiveDateTime,
+    pub update_date: NaiveDateTime,
+}
+
+impl ImportJob {
+    pub fn to_api(&self) -> ApiImportJob {
+        ApiImportJob {
+            id: self.id,
+            project_id: self.project_id,
+            file_names: self.file_names.clone(),
+            status: self.status.into(),
+            test_run_id: self.test_run_id,
+            already_imported: self.already_imported,
+            reimport: self.reimport,
+            error: self.error.clone(),
+            problems: self.problems.iter().map(ImportProblem::to_api).collect(),
+            create_date: utils::date::format_datetime(self.create_date),
+            update_date: utils::date::format_datetime(self.update_date),
+        }
+    }
+}
+
+/// `Parsing` covers writing the suites and tests as they are parsed,
+/// `Saving` the test run itself once the output files are read.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImportStatus {
+    Queued,
+    Parsing,
+    Saving,
+    Done,
+    Failed,
+}
+
+impl From<ImportStatusDB> for ImportStatus {
+    fn from(db_status: ImportStatusDB) -> Self {
+        match db_status {
+            ImportStatusDB::Queued => ImportStatus::Queued,
+            ImportStatusDB::Parsing => ImportStatus::Parsing,
+            ImportStatusDB::Saving => ImportStatus::Saving,
+            ImportStatusDB::Done => ImportStatus::Done,
+            ImportStatusDB::Failed => ImportStatus::Failed,
+        }
+    }
+}
+
+/// A value of the output file that cannot be stored.
+#[derive(Deserialize)]
+pub struct ImportProblem {
+    pub path: String,
+    pub reason: String,
+}
+
+impl ImportProblem {
+    fn to_api(&self) -> ApiImportProblem {
+        ApiImportProblem {
+            path: self.path.clone(),
+            reason: self.reason.clone(),
+        }
+    }
+}
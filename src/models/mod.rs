@@ -1,3 +1,4 @@
+pub mod imports;
 pub mod projects;
 pub mod robot;
 
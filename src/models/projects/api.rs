@@ -1,6 +1,8 @@
 use serde::Serialize;
 use typeshare::typeshare;
 
+use crate::models::robot::api::{ApiCiMetadata, ApiLabel};
+
 #[typeshare]
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -10,6 +12,18 @@ pub struct ProjectOverviewResponse {
     pub create_date: String,
     pub test_run_count: i32,
     pub last_test_run_summary: Option<ApiTestRunSummary>,
+    pub contexts: Vec<ApiProjectContext>,
+}
+
+/// The runs of a project against one environment and branch.
+#[typeshare]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiProjectContext {
+    pub environment: Option<String>,
+    pub branch: Option<String>,
+    pub test_run_count: i32,
+    pub last_test_run_summary: ApiTestRunSummary,
 }
 
 #[typeshare]
@@ -25,6 +39,21 @@ pub struct ApiTestRunSummary {
     pub skipped_tests: i32,
     pub error_count: i32,
     pub app_version: String,
+    pub environment: Option<String>,
+    pub branch: Option<String>,
+    pub ci: ApiCiMetadata,
+    pub labels: Vec<ApiLabel>,
+}
+
+/// A page of the test runs of a project, `nextCursor` gives the next one.
+#[typeshare]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiTestRunPage {
+    pub test_runs_summaries: Vec<ApiTestRunSummary>,
+    #[typeshare(serialized_as = "number")]
+    pub total_count: i64,
+    pub next_cursor: Option<String>,
 }
 
 #[typeshare]
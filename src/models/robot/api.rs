@@ -2,7 +2,7 @@ use serde::Serialize;
 use serde_json::Value;
 use typeshare::typeshare;
 
-use super::domain::StatisticType;
+use super::domain::{SourceFormat, StatisticType};
 
 #[typeshare]
 #[derive(Serialize)]
@@ -14,11 +14,37 @@ pub struct TestRunResponse {
     pub generator: String,
     pub generated_date: String,
     pub schema_version: String,
+    pub source_format: ApiSourceFormat,
     pub imported_date: String,
     pub suites: Vec<ApiSuite>,
     pub statistics: Vec<ApiStatistic>,
     pub errors: Vec<ApiError>,
+    pub parts: Vec<ApiTestRunPart>,
+    pub artifacts: Vec<ApiArtifact>,
     pub app_version: String,
+    pub environment: Option<String>,
+    pub branch: Option<String>,
+    pub ci: ApiCiMetadata,
+    pub labels: Vec<ApiLabel>,
+}
+
+#[typeshare]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiCiMetadata {
+    pub system: Option<String>,
+    pub build_url: Option<String>,
+    pub build_number: Option<String>,
+    pub robot_git_hash: Option<String>,
+    pub app_git_hash: Option<String>,
+}
+
+#[typeshare]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiLabel {
+    pub name: String,
+    pub value: String,
 }
 
 #[typeshare]
@@ -29,28 +55,307 @@ pub struct ApiSuite {
     pub name: String,
     pub source: Option<String>,
     pub status: String,
+    /// Failure or skip reason, HTML when it starts with `*HTML*`.
+    pub message: Option<String>,
     pub start_time: String,
     pub end_time: String,
     pub doc: Option<String>,
     pub identifier: String,
+    pub metadata: Vec<ApiMetadata>,
     pub suites: Vec<ApiSuite>,
     pub tests: Vec<ApiTest>,
 }
 
+/// A suite without its suites and tests, to load the tree of a large run
+/// level by level. The counts cover the tests of all the suites under it.
+#[typeshare]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiSuiteNode {
+    pub id: i32,
+    pub name: String,
+    pub source: Option<String>,
+    pub status: String,
+    /// Failure or skip reason, HTML when it starts with `*HTML*`.
+    pub message: Option<String>,
+    pub start_time: String,
+    pub end_time: String,
+    pub doc: Option<String>,
+    pub identifier: String,
+    pub metadata: Vec<ApiMetadata>,
+    /// Child suites, listed by `/suites/{id}/suites`.
+    pub suite_count: i32,
+    /// Tests of the suite itself, listed by `/suites/{id}/tests`.
+    pub test_count: i32,
+    pub statistics: ApiStatisticCounts,
+}
+
+/// Tests of a suite in the order they ran, `nextCursor` gives the next ones.
+#[typeshare]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiSuiteTestPage {
+    pub tests: Vec<ApiTest>,
+    pub next_cursor: Option<String>,
+}
+
+/// Entry of the `Metadata` setting of a suite.
+#[typeshare]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiMetadata {
+    pub name: String,
+    pub value: String,
+}
+
 #[typeshare]
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ApiTest {
     pub id: i32,
     pub name: String,
-    pub line: i32,
+    pub line: Option<i32>,
     pub identifier: String,
     pub tags: Vec<String>,
     pub status: String,
+    /// Failure or skip reason, HTML when it starts with `*HTML*`.
+    pub message: Option<String>,
     pub start_time: String,
     pub end_time: String,
     pub doc: Option<String>,
     pub timeout: Option<String>,
+    pub attempt: i32,
+    pub passed_on_retry: bool,
+}
+
+#[typeshare]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiTestAttempt {
+    pub id: i32,
+    pub attempt: i32,
+    pub status: String,
+    pub start_time: String,
+    pub end_time: String,
+}
+
+/// A test of a project that passes and fails on the same app version.
+#[typeshare]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiFlakyTest {
+    pub suite: String,
+    pub name: String,
+    /// Share of consecutive runs of the same app version where the result
+    /// changed, from 0 to 1.
+    pub score: f64,
+    pub run_count: i32,
+    pub transitions: i32,
+    pub flips: i32,
+    pub last_test_run_id: i32,
+    pub last_run_date: String,
+    pub last_app_version: String,
+    pub last_status: String,
+}
+
+/// The outcomes of a test across runs, `nextCursor` gives the older ones.
+#[typeshare]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiTestHistory {
+    pub project_id: i32,
+    pub suite: String,
+    pub name: String,
+    pub entries: Vec<ApiTestHistoryEntry>,
+    pub next_cursor: Option<String>,
+}
+
+#[typeshare]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiTestHistoryEntry {
+    pub test_id: i32,
+    pub test_run_id: i32,
+    pub test_run_date: String,
+    pub app_version: String,
+    pub environment: Option<String>,
+    pub branch: Option<String>,
+    pub status: String,
+    /// Failure or skip reason, HTML when it starts with `*HTML*`.
+    pub message: Option<String>,
+    pub start_time: String,
+    pub end_time: String,
+    #[typeshare(serialized_as = "number")]
+    pub duration_ms: i64,
+    pub attempt: i32,
+}
+
+/// A test or keyword whose text matches a search. Keywords are found by
+/// their name, arguments and messages, tests by their name, documentation
+/// and message.
+#[typeshare]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiSearchMatch {
+    pub project_id: i32,
+    pub test_run_id: i32,
+    pub test_run_date: String,
+    pub suite_id: i32,
+    pub suite_identifier: String,
+    pub suite: String,
+    /// Missing for the setup and teardown keywords of suites.
+    pub test_id: Option<i32>,
+    pub test_identifier: Option<String>,
+    pub test_name: Option<String>,
+    /// `setup`, `body` or `teardown`, missing when the test itself matches.
+    pub keyword_type: Option<String>,
+    /// JSON pointer of the keyword or message in the keywords of this type,
+    /// e.g. `/0/kw/keywords/2/kw`.
+    pub keyword_path: Option<String>,
+    pub keyword_name: Option<String>,
+    /// The part of the text around the matching words.
+    pub excerpt: String,
+}
+
+/// The failed tests of a run whose messages share a signature, the message
+/// without its quoted values, UUIDs, timestamps and numbers.
+#[typeshare]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiFailureGroup {
+    pub signature: String,
+    pub failure_count: i32,
+    /// Up to 3 distinct messages of the group.
+    pub example_messages: Vec<String>,
+    pub tests: Vec<ApiFailedTest>,
+    /// Date of the first run of the project with a failure of this signature.
+    pub first_seen_date: String,
+}
+
+#[typeshare]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiFailedTest {
+    pub test_id: i32,
+    pub suite: String,
+    pub name: String,
+}
+
+/// The failures of the runs of a project that share a signature.
+#[typeshare]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiProjectFailureGroup {
+    pub signature: String,
+    pub failure_count: i32,
+    /// Distinct tests, by the path of their suite and their name.
+    pub test_count: i32,
+    pub test_run_count: i32,
+    /// Up to 3 distinct messages of the group.
+    pub example_messages: Vec<String>,
+    pub first_seen_date: String,
+    pub last_seen_date: String,
+    pub last_test_run_id: i32,
+}
+
+/// The tests of two runs matched by the path of their suite and their name,
+/// from the base run to the target run.
+#[typeshare]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiTestRunComparison {
+    pub base_test_run_id: i32,
+    pub target_test_run_id: i32,
+    pub newly_failing: Vec<ApiComparedTest>,
+    pub fixed: Vec<ApiComparedTest>,
+    pub still_failing: Vec<ApiComparedTest>,
+    /// Skipped and not run tests count as passing.
+    pub still_passing: Vec<ApiComparedTest>,
+    /// Tests of the target run only.
+    pub added: Vec<ApiComparedTest>,
+    /// Tests of the base run only.
+    pub removed: Vec<ApiComparedTest>,
+    /// Tags whose counts changed.
+    pub tags: Vec<ApiTagStatisticChange>,
+}
+
+#[typeshare]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiComparedTest {
+    pub suite: String,
+    pub name: String,
+    pub base_test_id: Option<i32>,
+    pub base_status: Option<String>,
+    #[typeshare(serialized_as = "number")]
+    pub base_duration_ms: Option<i64>,
+    pub target_test_id: Option<i32>,
+    pub target_status: Option<String>,
+    #[typeshare(serialized_as = "number")]
+    pub target_duration_ms: Option<i64>,
+    /// Only set when the test is in both runs, positive when it got slower.
+    #[typeshare(serialized_as = "number")]
+    pub duration_change_ms: Option<i64>,
+}
+
+#[typeshare]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiTagStatisticChange {
+    pub tag: String,
+    /// Missing when the run has no test with the tag.
+    pub base: Option<ApiStatisticCounts>,
+    pub target: Option<ApiStatisticCounts>,
+    pub pass_change: i32,
+    pub fail_change: i32,
+    pub skip_change: i32,
+}
+
+#[typeshare]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiStatisticCounts {
+    pub pass_count: i32,
+    pub fail_count: i32,
+    pub skip_count: i32,
+}
+
+#[typeshare]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiTestRunPart {
+    pub file_name: Option<String>,
+    pub sha1: String,
+    pub rerun: bool,
+}
+
+#[typeshare]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiArtifact {
+    pub path: String,
+    pub content_type: String,
+    #[typeshare(serialized_as = "number")]
+    pub size: i64,
+}
+
+#[typeshare]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ApiSourceFormat {
+    RobotXml,
+    RobotJson,
+    Junit,
+}
+
+impl From<SourceFormat> for ApiSourceFormat {
+    fn from(domain_format: SourceFormat) -> Self {
+        match domain_format {
+            SourceFormat::RobotXml => ApiSourceFormat::RobotXml,
+            SourceFormat::RobotJson => ApiSourceFormat::RobotJson,
+            SourceFormat::Junit => ApiSourceFormat::Junit,
+        }
+    }
 }
 
 #[typeshare]
@@ -94,6 +399,7 @@ pub struct ApiError {
     pub timestamp: String,
     pub level: String,
     pub content: String,
+    pub html: bool,
 }
 
 #[typeshare]
@@ -103,3 +409,13 @@ pub struct ApiSuiteKeywords<'a> {
     pub setup_keyword: Option<&'a Value>,
     pub teardown_keyword: Option<&'a Value>,
 }
+
+#[typeshare]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiTestKeywords<'a> {
+    pub setup_keyword: Option<&'a Value>,
+    /// Body of the test, without its setup and teardown.
+    pub keywords: Option<&'a Value>,
+    pub teardown_keyword: Option<&'a Value>,
+}
@@ -5,7 +5,10 @@ use sqlx::postgres::types::PgInterval;
 use crate::utils;
 
 use super::domain::{
-    ProjectTestRunSummary, SavedTestRun, TestRunError, TestRunStatistic, TestRunSuite, TestRunTest,
+    ArtifactContent, CiMetadata, ComparedResult, ContentChunks, FailedTest, FailureGroup,
+    FlakyTest, OutputFile, ProjectFailureGroup, ProjectTestRunSummary, SavedTestRun, SearchMatch,
+    SuiteNode, TestAttempt, TestComparison, TestHistoryEntry, TestRunArtifact, TestRunError,
+    TestRunPart, TestRunStatistic, TestRunSuite, TestRunTest,
 };
 
 #[derive(sqlx::FromRow)]
@@ -20,6 +23,17 @@ pub struct ProjectTestSummaryDB {
     pub failed_tests: i32,
     pub skipped_tests: i32,
     pub error_count: Option<i32>,
+    pub environment: Option<String>,
+    pub branch: Option<String>,
+    pub ci_system: Option<String>,
+    pub build_url: Option<String>,
+    pub build_number: Option<String>,
+    pub robot_git_hash: Option<String>,
+    pub app_git_hash: Option<String>,
+    pub label_names: Vec<String>,
+    pub label_values: Vec<String>,
+    /// Only set when listing a page of runs.
+    pub sort_key: Option<i64>,
 }
 
 impl ProjectTestSummaryDB {
@@ -39,6 +53,20 @@ impl ProjectTestSummaryDB {
             failed_tests: self.failed_tests,
             skipped_tests: self.skipped_tests,
             error_count: self.error_count.unwrap_or(0),
+            environment: self.environment,
+            branch: self.branch,
+            ci: CiMetadata {
+                system: self.ci_system,
+                build_url: self.build_url,
+                build_number: self.build_number,
+                robot_git_hash: self.robot_git_hash,
+                app_git_hash: self.app_git_hash,
+            },
+            labels: self
+                .label_names
+                .into_iter()
+                .zip(self.label_values)
+                .collect(),
         }
     }
 }
@@ -51,8 +79,18 @@ pub struct TestRunDB {
     pub generator: String,
     pub generated_date: NaiveDateTime,
     pub schema_version: String,
+    pub source_format: SourceFormatDB,
     pub application_version: String,
     pub imported_date: NaiveDateTime,
+    pub environment: Option<String>,
+    pub branch: Option<String>,
+    pub ci_system: Option<String>,
+    pub build_url: Option<String>,
+    pub build_number: Option<String>,
+    pub robot_git_hash: Option<String>,
+    pub app_git_hash: Option<String>,
+    pub label_names: Vec<String>,
+    pub label_values: Vec<String>,
 }
 
 impl TestRunDB {
@@ -61,6 +99,8 @@ impl TestRunDB {
         suites: Vec<TestRunSuite>,
         statistics: Vec<TestRunStatistic>,
         errors: Vec<TestRunError>,
+        parts: Vec<TestRunPart>,
+        artifacts: Vec<TestRunArtifact>,
     ) -> SavedTestRun {
         SavedTestRun {
             id: self.id,
@@ -69,11 +109,29 @@ impl TestRunDB {
             generator: self.generator.clone(),
             generated_date: self.generated_date,
             schema_version: self.schema_version.clone(),
+            source_format: self.source_format.clone().into(),
             app_version: self.application_version.clone(),
             imported_date: self.imported_date,
-            suites: suites,
-            statistics: statistics,
-            errors: errors,
+            suites,
+            statistics,
+            errors,
+            parts,
+            artifacts,
+            environment: self.environment.clone(),
+            branch: self.branch.clone(),
+            ci: CiMetadata {
+                system: self.ci_system.clone(),
+                build_url: self.build_url.clone(),
+                build_number: self.build_number.clone(),
+                robot_git_hash: self.robot_git_hash.clone(),
+                app_git_hash: self.app_git_hash.clone(),
+            },
+            labels: self
+                .label_names
+                .iter()
+                .cloned()
+                .zip(self.label_values.iter().cloned())
+                .collect(),
         }
     }
 }
@@ -81,13 +139,17 @@ impl TestRunDB {
 #[derive(sqlx::FromRow)]
 pub struct SuiteDB {
     pub id: i32,
+    pub parent_suite_id: Option<i32>,
     pub name: String,
     pub source: Option<String>,
     pub status: String,
+    pub message: Option<String>,
     pub start_time: NaiveDateTime,
     pub end_time: NaiveDateTime,
     pub doc: Option<String>,
     pub identifier: String,
+    pub metadata_names: Vec<String>,
+    pub metadata_values: Vec<String>,
 }
 
 impl SuiteDB {
@@ -101,12 +163,19 @@ impl SuiteDB {
             name: self.name.clone(),
             source: self.source.clone(),
             status: self.status.clone(),
+            message: self.message.clone(),
             start_time: self.start_time,
             end_time: self.end_time,
             doc: self.doc.clone(),
             identifier: self.identifier.clone(),
+            metadata: self
+                .metadata_names
+                .iter()
+                .cloned()
+                .zip(self.metadata_values.iter().cloned())
+                .collect(),
             suites: sub_suites,
-            tests: tests,
+            tests,
         }
     }
 }
@@ -114,34 +183,335 @@ impl SuiteDB {
 #[derive(sqlx::FromRow)]
 pub struct TestDB {
     pub id: i32,
+    pub suite_id: i32,
     pub identifier: String,
     pub name: String,
     pub status: String,
+    pub message: Option<String>,
     pub start_time: NaiveDateTime,
     pub end_time: NaiveDateTime,
-    pub line: i32,
+    pub line: Option<i32>,
     pub doc: Option<String>,
     pub timeout: Option<String>,
-    pub tag: Option<String>,
+    pub attempt: i32,
+    pub passed_on_retry: bool,
+    pub tags: Vec<String>,
+    /// Only set when listing a page of the tests of a suite.
+    pub sort_key: Option<i64>,
 }
 
 impl TestDB {
-    pub fn to_(&self, tags: Vec<String>) -> TestRunTest {
+    pub fn into_test(self) -> TestRunTest {
         TestRunTest {
             id: self.id,
-            name: self.name.clone(),
+            name: self.name,
             line: self.line,
-            identifier: self.identifier.clone(),
-            tags: tags,
+            identifier: self.identifier,
+            tags: self.tags,
+            status: self.status,
+            message: self.message,
+            start_time: self.start_time,
+            end_time: self.end_time,
+            doc: self.doc,
+            timeout: self.timeout,
+            attempt: self.attempt,
+            passed_on_retry: self.passed_on_retry,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+pub struct SuiteNodeDB {
+    pub id: i32,
+    pub name: String,
+    pub source: Option<String>,
+    pub status: String,
+    pub message: Option<String>,
+    pub start_time: NaiveDateTime,
+    pub end_time: NaiveDateTime,
+    pub doc: Option<String>,
+    pub identifier: String,
+    pub metadata_names: Vec<String>,
+    pub metadata_values: Vec<String>,
+    pub suite_count: i32,
+    pub test_count: i32,
+    pub pass_count: i32,
+    pub fail_count: i32,
+    pub skip_count: i32,
+}
+
+impl SuiteNodeDB {
+    pub fn into_node(self) -> SuiteNode {
+        SuiteNode {
+            id: self.id,
+            name: self.name,
+            source: self.source,
+            status: self.status,
+            message: self.message,
+            start_time: self.start_time,
+            end_time: self.end_time,
+            doc: self.doc,
+            identifier: self.identifier,
+            metadata: self
+                .metadata_names
+                .into_iter()
+                .zip(self.metadata_values)
+                .collect(),
+            suite_count: self.suite_count,
+            test_count: self.test_count,
+            pass_count: self.pass_count,
+            fail_count: self.fail_count,
+            skip_count: self.skip_count,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+pub struct TestAttemptDB {
+    pub id: i32,
+    pub attempt: i32,
+    pub status: String,
+    pub start_time: NaiveDateTime,
+    pub end_time: NaiveDateTime,
+}
+
+impl TestAttemptDB {
+    pub fn to_test_attempt(&self) -> TestAttempt {
+        TestAttempt {
+            id: self.id,
+            attempt: self.attempt,
             status: self.status.clone(),
             start_time: self.start_time,
             end_time: self.end_time,
-            doc: self.doc.clone(),
-            timeout: self.timeout.clone(),
         }
     }
 }
 
+#[derive(sqlx::FromRow)]
+pub struct TestHistoryEntryDB {
+    pub test_id: i32,
+    pub test_run_id: i32,
+    pub test_run_date: NaiveDateTime,
+    pub app_version: String,
+    pub environment: Option<String>,
+    pub branch: Option<String>,
+    pub status: String,
+    pub message: Option<String>,
+    pub start_time: NaiveDateTime,
+    pub end_time: NaiveDateTime,
+    pub attempt: i32,
+    pub sort_key: i64,
+}
+
+impl TestHistoryEntryDB {
+    pub fn into_entry(self) -> TestHistoryEntry {
+        TestHistoryEntry {
+            test_id: self.test_id,
+            test_run_id: self.test_run_id,
+            test_run_date: self.test_run_date,
+            app_version: self.app_version,
+            environment: self.environment,
+            branch: self.branch,
+            status: self.status,
+            message: self.message,
+            start_time: self.start_time,
+            end_time: self.end_time,
+            attempt: self.attempt,
+        }
+    }
+}
+
+/// A test of either run, the columns of the run without it are null.
+#[derive(sqlx::FromRow)]
+pub struct TestComparisonDB {
+    pub suite: String,
+    pub name: String,
+    pub base_test_id: Option<i32>,
+    pub base_status: Option<String>,
+    pub base_duration_ms: Option<i64>,
+    pub target_test_id: Option<i32>,
+    pub target_status: Option<String>,
+    pub target_duration_ms: Option<i64>,
+}
+
+impl TestComparisonDB {
+    pub fn into_comparison(self) -> TestComparison {
+        TestComparison {
+            suite: self.suite,
+            name: self.name,
+            base: compared_result(self.base_test_id, self.base_status, self.base_duration_ms),
+            target: compared_result(
+                self.target_test_id,
+                self.target_status,
+                self.target_duration_ms,
+            ),
+        }
+    }
+}
+
+fn compared_result(
+    test_id: Option<i32>,
+    status: Option<String>,
+    duration_ms: Option<i64>,
+) -> Option<ComparedResult> {
+    Some(ComparedResult {
+        test_id: test_id?,
+        status: status?,
+        duration_ms: duration_ms.unwrap_or_default(),
+    })
+}
+
+/// The tests come as one array per column, ordered by suite and name.
+#[derive(sqlx::FromRow)]
+pub struct FailureGroupDB {
+    pub signature: String,
+    pub failure_count: i32,
+    pub example_messages: Vec<String>,
+    pub test_ids: Vec<i32>,
+    pub test_suites: Vec<String>,
+    pub test_names: Vec<String>,
+    pub first_seen_date: NaiveDateTime,
+}
+
+impl FailureGroupDB {
+    pub fn into_failure_group(self) -> FailureGroup {
+        FailureGroup {
+            signature: self.signature,
+            failure_count: self.failure_count,
+            example_messages: self.example_messages,
+            tests: self
+                .test_ids
+                .into_iter()
+                .zip(self.test_suites)
+                .zip(self.test_names)
+                .map(|((test_id, suite), name)| FailedTest {
+                    test_id,
+                    suite,
+                    name,
+                })
+                .collect(),
+            first_seen_date: self.first_seen_date,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+pub struct ProjectFailureGroupDB {
+    pub signature: String,
+    pub failure_count: i32,
+    pub test_count: i32,
+    pub test_run_count: i32,
+    pub example_messages: Vec<String>,
+    pub first_seen_date: NaiveDateTime,
+    pub last_seen_date: NaiveDateTime,
+    pub last_test_run_id: i32,
+}
+
+impl ProjectFailureGroupDB {
+    pub fn into_failure_group(self) -> ProjectFailureGroup {
+        ProjectFailureGroup {
+            signature: self.signature,
+            failure_count: self.failure_count,
+            test_count: self.test_count,
+            test_run_count: self.test_run_count,
+            example_messages: self.example_messages,
+            first_seen_date: self.first_seen_date,
+            last_seen_date: self.last_seen_date,
+            last_test_run_id: self.last_test_run_id,
+        }
+    }
+}
+
+/// The test columns are null for the keywords of suites, the keyword ones
+/// for the matches of the test itself.
+#[derive(sqlx::FromRow)]
+pub struct SearchMatchDB {
+    pub project_id: i32,
+    pub test_run_id: i32,
+    pub test_run_date: NaiveDateTime,
+    pub suite_id: i32,
+    pub suite_identifier: String,
+    pub suite: String,
+    pub test_id: Option<i32>,
+    pub test_identifier: Option<String>,
+    pub test_name: Option<String>,
+    pub keyword_type: Option<String>,
+    pub keyword_path: Option<Vec<String>>,
+    pub keyword_name: Option<String>,
+    pub excerpt: String,
+}
+
+impl SearchMatchDB {
+    pub fn into_match(self) -> SearchMatch {
+        SearchMatch {
+            project_id: self.project_id,
+            test_run_id: self.test_run_id,
+            test_run_date: self.test_run_date,
+            suite_id: self.suite_id,
+            suite_identifier: self.suite_identifier,
+            suite: self.suite,
+            test_id: self.test_id,
+            test_identifier: self.test_identifier,
+            test_name: self.test_name,
+            keyword_type: self.keyword_type,
+            keyword_path: self.keyword_path,
+            keyword_name: self.keyword_name,
+            excerpt: self.excerpt,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+pub struct FlakyTestDB {
+    pub suite: String,
+    pub name: String,
+    pub score: f64,
+    pub run_count: i32,
+    pub transitions: i32,
+    pub flips: i32,
+    pub last_test_run_id: i32,
+    pub last_run_date: NaiveDateTime,
+    pub last_app_version: String,
+    pub last_status: String,
+}
+
+impl FlakyTestDB {
+    pub fn into_flaky_test(self) -> FlakyTest {
+        FlakyTest {
+            suite: self.suite,
+            name: self.name,
+            score: self.score,
+            run_count: self.run_count,
+            transitions: self.transitions,
+            flips: self.flips,
+            last_test_run_id: self.last_test_run_id,
+            last_run_date: self.last_run_date,
+            last_app_version: self.last_app_version,
+            last_status: self.last_status,
+        }
+    }
+}
+
+/// A suite of a test run with its current tests, one row per test.
+#[derive(sqlx::FromRow, Debug)]
+pub struct RerunTargetDB {
+    pub id: i32,
+    pub identifier: String,
+    pub path: String,
+    pub test_id: Option<i32>,
+    pub test_identifier: Option<String>,
+    pub test_name: Option<String>,
+    pub test_attempt: Option<i32>,
+}
+
+#[derive(sqlx::Type, Debug, Clone)]
+#[sqlx(type_name = "source_format", rename_all = "snake_case")]
+pub enum SourceFormatDB {
+    RobotXml,
+    RobotJson,
+    Junit,
+}
+
 #[derive(sqlx::Type, Clone)]
 #[sqlx(type_name = "stat_type", rename_all = "snake_case")]
 pub enum StatisticTypeDB {
@@ -183,6 +553,7 @@ pub struct ErrorDB {
     pub timestamp: NaiveDateTime,
     pub level: String,
     pub content: String,
+    pub html: bool,
 }
 
 impl ErrorDB {
@@ -192,17 +563,81 @@ impl ErrorDB {
             timestamp: self.timestamp,
             level: self.level.clone(),
             content: self.content.clone(),
+            html: self.html,
         }
     }
 }
 
-#[derive(sqlx::FromRow, Debug)]
-pub struct SuiteKeywordRecord {
-    pub keyword_type: String,
-    pub value: Value,
+#[derive(sqlx::FromRow)]
+pub struct TestRunPartDB {
+    pub file_name: Option<String>,
+    pub sha1: String,
+    pub rerun: bool,
+}
+
+impl TestRunPartDB {
+    pub fn to_test_run_part(&self) -> TestRunPart {
+        TestRunPart {
+            file_name: self.file_name.clone(),
+            sha1: self.sha1.clone(),
+            rerun: self.rerun,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+pub struct ArtifactDB {
+    pub path: String,
+    pub content_type: String,
+    pub size: i64,
+}
+
+impl ArtifactDB {
+    pub fn to_test_run_artifact(&self) -> TestRunArtifact {
+        TestRunArtifact {
+            path: self.path.clone(),
+            content_type: self.content_type.clone(),
+            size: self.size,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+pub struct ArtifactContentDB {
+    pub content_type: String,
+    pub size: i64,
+    pub content_id: i64,
+}
+
+impl ArtifactContentDB {
+    pub fn into_artifact_content(self, content: ContentChunks) -> ArtifactContent {
+        ArtifactContent {
+            content_type: self.content_type,
+            size: self.size,
+            content,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+pub struct OutputFileDB {
+    pub file_name: Option<String>,
+    pub size: i64,
+    pub content_id: i64,
+}
+
+impl OutputFileDB {
+    pub fn into_output_file(self, content: ContentChunks) -> OutputFile {
+        OutputFile {
+            file_name: self.file_name,
+            size: self.size,
+            content,
+        }
+    }
 }
 
 #[derive(sqlx::FromRow, Debug)]
-pub struct RawJsonRecord {
+pub struct KeywordRecord {
+    pub keyword_type: String,
     pub value: Value,
 }
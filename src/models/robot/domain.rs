@@ -1,13 +1,23 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, io, sync::Arc};
 
 use chrono::{Duration, NaiveDateTime};
+use futures_util::stream::BoxStream;
 use serde_json::Value;
 
-use crate::{models::projects::api::ApiTestRunSummary, utils};
+use crate::{
+    models::projects::api::{ApiProjectContext, ApiTestRunPage, ApiTestRunSummary},
+    utils,
+};
 
 use super::{
-    api::{ApiError, ApiStatistic, ApiSuite, ApiSuiteKeywords, ApiTest, TestRunResponse},
-    db::StatisticTypeDB,
+    api::{
+        ApiArtifact, ApiCiMetadata, ApiComparedTest, ApiError, ApiFailedTest, ApiFailureGroup,
+        ApiFlakyTest, ApiLabel, ApiMetadata, ApiProjectFailureGroup, ApiSearchMatch, ApiStatistic,
+        ApiStatisticCounts, ApiSuite, ApiSuiteKeywords, ApiSuiteNode, ApiSuiteTestPage,
+        ApiTagStatisticChange, ApiTest, ApiTestAttempt, ApiTestHistory, ApiTestHistoryEntry,
+        ApiTestKeywords, ApiTestRunComparison, ApiTestRunPart, TestRunResponse,
+    },
+    db::{SourceFormatDB, StatisticTypeDB},
 };
 
 pub struct ProjectTestRunSummary {
@@ -22,6 +32,10 @@ pub struct ProjectTestRunSummary {
     pub failed_tests: i32,
     pub skipped_tests: i32,
     pub error_count: i32,
+    pub environment: Option<String>,
+    pub branch: Option<String>,
+    pub ci: CiMetadata,
+    pub labels: Vec<(String, String)>,
 }
 
 impl ProjectTestRunSummary {
@@ -36,6 +50,19 @@ impl ProjectTestRunSummary {
             skipped_tests: self.skipped_tests,
             error_count: self.error_count,
             app_version: self.app_version.clone(),
+            environment: self.environment.clone(),
+            branch: self.branch.clone(),
+            ci: self.ci.to_api(),
+            labels: labels_to_api(&self.labels),
+        }
+    }
+
+    pub fn to_context_api(&self) -> ApiProjectContext {
+        ApiProjectContext {
+            environment: self.environment.clone(),
+            branch: self.branch.clone(),
+            test_run_count: self.test_run_count,
+            last_test_run_summary: self.to_api(),
         }
     }
 }
@@ -47,11 +74,18 @@ pub struct SavedTestRun {
     pub generator: String,
     pub generated_date: NaiveDateTime,
     pub schema_version: String,
+    pub source_format: SourceFormat,
     pub imported_date: NaiveDateTime,
     pub suites: Vec<TestRunSuite>,
     pub statistics: Vec<TestRunStatistic>,
     pub errors: Vec<TestRunError>,
+    pub parts: Vec<TestRunPart>,
+    pub artifacts: Vec<TestRunArtifact>,
     pub app_version: String,
+    pub environment: Option<String>,
+    pub branch: Option<String>,
+    pub ci: CiMetadata,
+    pub labels: Vec<(String, String)>,
 }
 
 impl SavedTestRun {
@@ -63,6 +97,7 @@ impl SavedTestRun {
             generator: self.generator.clone(),
             generated_date: utils::date::format_datetime(self.generated_date),
             schema_version: self.schema_version.clone(),
+            source_format: self.source_format.clone().into(),
             imported_date: utils::date::format_datetime(self.imported_date),
             suites: self.suites.iter().map(TestRunSuite::to_api).collect(),
             statistics: self
@@ -71,7 +106,202 @@ impl SavedTestRun {
                 .map(TestRunStatistic::to_api)
                 .collect(),
             errors: self.errors.iter().map(TestRunError::to_api).collect(),
+            parts: self.parts.iter().map(TestRunPart::to_api).collect(),
+            artifacts: self.artifacts.iter().map(TestRunArtifact::to_api).collect(),
             app_version: self.app_version.clone(),
+            environment: self.environment.clone(),
+            branch: self.branch.clone(),
+            ci: self.ci.to_api(),
+            labels: labels_to_api(&self.labels),
+        }
+    }
+}
+
+/// The CI build a test run comes from, as told by the upload.
+#[derive(Debug, Clone, Default)]
+pub struct CiMetadata {
+    pub system: Option<String>,
+    pub build_url: Option<String>,
+    pub build_number: Option<String>,
+    pub robot_git_hash: Option<String>,
+    pub app_git_hash: Option<String>,
+}
+
+impl CiMetadata {
+    fn to_api(&self) -> ApiCiMetadata {
+        ApiCiMetadata {
+            system: self.system.clone(),
+            build_url: self.build_url.clone(),
+            build_number: self.build_number.clone(),
+            robot_git_hash: self.robot_git_hash.clone(),
+            app_git_hash: self.app_git_hash.clone(),
+        }
+    }
+}
+
+fn labels_to_api(labels: &[(String, String)]) -> Vec<ApiLabel> {
+    labels
+        .iter()
+        .map(|(name, value)| ApiLabel {
+            name: name.clone(),
+            value: value.clone(),
+        })
+        .collect()
+}
+
+/// Filters of the test runs of a project, which must all match. Git hashes
+/// match by prefix, so that short hashes can be given. An empty environment
+/// or branch matches the runs without one.
+#[derive(Debug, Default)]
+pub struct TestRunFilter {
+    pub metadata: Vec<(String, String)>,
+    pub environment: Option<String>,
+    pub branch: Option<String>,
+    pub ci_system: Option<String>,
+    pub build_number: Option<String>,
+    pub robot_git_hash: Option<String>,
+    pub app_git_hash: Option<String>,
+    pub labels: Vec<(String, String)>,
+    /// Runs generated from then on.
+    pub from: Option<NaiveDateTime>,
+    /// Runs generated until then.
+    pub to: Option<NaiveDateTime>,
+    pub app_version: Option<String>,
+    pub has_failures: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum TestRunSort {
+    #[default]
+    Date,
+    Duration,
+    FailedTests,
+}
+
+impl TestRunSort {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TestRunSort::Date => "date",
+            TestRunSort::Duration => "duration",
+            TestRunSort::FailedTests => "failed_tests",
+        }
+    }
+}
+
+/// Position after the last row of a page: the value it is sorted by, in
+/// microseconds for dates and durations, and its id to break ties.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PageCursor {
+    pub sort_key: i64,
+    pub id: i32,
+}
+
+impl PageCursor {
+    pub fn parse(cursor: &str) -> Option<Self> {
+        let (sort_key, id) = cursor.split_once('_')?;
+        Some(PageCursor {
+            sort_key: sort_key.parse().ok()?,
+            id: id.parse().ok()?,
+        })
+    }
+}
+
+impl std::fmt::Display for PageCursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}_{}", self.sort_key, self.id)
+    }
+}
+
+/// A cursor is only valid with the sort of the page it comes from.
+#[derive(Debug)]
+pub struct TestRunPageRequest {
+    pub sort: TestRunSort,
+    pub descending: bool,
+    pub cursor: Option<PageCursor>,
+    pub limit: i64,
+}
+
+pub struct TestRunPage {
+    pub summaries: Vec<ProjectTestRunSummary>,
+    /// Runs matching the filter, on every page.
+    pub total_count: i64,
+    pub next_cursor: Option<PageCursor>,
+}
+
+impl TestRunPage {
+    pub fn to_api(&self) -> ApiTestRunPage {
+        ApiTestRunPage {
+            test_runs_summaries: self.summaries.iter().map(|tr| tr.to_api()).collect(),
+            total_count: self.total_count,
+            next_cursor: self.next_cursor.map(|cursor| cursor.to_string()),
+        }
+    }
+}
+
+/// An output file the test run was imported from, or a rerun merged into it.
+pub struct TestRunPart {
+    pub file_name: Option<String>,
+    pub sha1: String,
+    pub rerun: bool,
+}
+
+impl TestRunPart {
+    fn to_api(&self) -> ApiTestRunPart {
+        ApiTestRunPart {
+            file_name: self.file_name.clone(),
+            sha1: self.sha1.clone(),
+            rerun: self.rerun,
+        }
+    }
+}
+
+/// A file uploaded along the output files, served as is.
+pub struct TestRunArtifact {
+    pub path: String,
+    pub content_type: String,
+    pub size: i64,
+}
+
+impl TestRunArtifact {
+    fn to_api(&self) -> ApiArtifact {
+        ApiArtifact {
+            path: self.path.clone(),
+            content_type: self.content_type.clone(),
+            size: self.size,
+        }
+    }
+}
+
+pub struct ArtifactContent {
+    pub content_type: String,
+    pub size: i64,
+    pub content: ContentChunks,
+}
+
+/// Content read from the database chunk by chunk, as it is sent.
+pub type ContentChunks = BoxStream<'static, io::Result<Vec<u8>>>;
+
+/// Copy of an uploaded output file.
+pub struct OutputFile {
+    pub file_name: Option<String>,
+    /// Size of the file as it was uploaded.
+    pub size: i64,
+    pub content: ContentChunks,
+}
+
+#[derive(Clone)]
+pub enum SourceFormat {
+    RobotXml,
+    RobotJson,
+    Junit,
+}
+
+impl From<SourceFormatDB> for SourceFormat {
+    fn from(db_format: SourceFormatDB) -> Self {
+        match db_format {
+            SourceFormatDB::RobotXml => SourceFormat::RobotXml,
+            SourceFormatDB::RobotJson => SourceFormat::RobotJson,
+            SourceFormatDB::Junit => SourceFormat::Junit,
         }
     }
 }
@@ -81,10 +311,12 @@ pub struct TestRunSuite {
     pub name: String,
     pub source: Option<String>,
     pub status: String,
+    pub message: Option<String>,
     pub start_time: NaiveDateTime,
     pub end_time: NaiveDateTime,
     pub doc: Option<String>,
     pub identifier: String,
+    pub metadata: Vec<(String, String)>,
     pub suites: Vec<TestRunSuite>,
     pub tests: Vec<TestRunTest>,
 }
@@ -96,27 +328,113 @@ impl TestRunSuite {
             name: self.name.clone(),
             source: self.source.clone(),
             status: self.status.clone(),
+            message: self.message.clone(),
             start_time: utils::date::format_datetime(self.start_time),
             end_time: utils::date::format_datetime(self.end_time),
             doc: self.doc.clone(),
             identifier: self.identifier.clone(),
+            metadata: self
+                .metadata
+                .iter()
+                .map(|(name, value)| ApiMetadata {
+                    name: name.clone(),
+                    value: value.clone(),
+                })
+                .collect(),
             suites: self.suites.iter().map(|suite| suite.to_api()).collect(),
             tests: self.tests.iter().map(|test| test.to_api()).collect(),
         }
     }
 }
 
+/// A suite of a run without its children, see [`TestRunSuite`] for the
+/// whole tree.
+pub struct SuiteNode {
+    pub id: i32,
+    pub name: String,
+    pub source: Option<String>,
+    pub status: String,
+    pub message: Option<String>,
+    pub start_time: NaiveDateTime,
+    pub end_time: NaiveDateTime,
+    pub doc: Option<String>,
+    pub identifier: String,
+    pub metadata: Vec<(String, String)>,
+    pub suite_count: i32,
+    pub test_count: i32,
+    /// Last attempts of the tests of the suite and of the suites under it.
+    pub pass_count: i32,
+    pub fail_count: i32,
+    pub skip_count: i32,
+}
+
+impl SuiteNode {
+    pub fn to_api(&self) -> ApiSuiteNode {
+        ApiSuiteNode {
+            id: self.id,
+            name: self.name.clone(),
+            source: self.source.clone(),
+            status: self.status.clone(),
+            message: self.message.clone(),
+            start_time: utils::date::format_datetime(self.start_time),
+            end_time: utils::date::format_datetime(self.end_time),
+            doc: self.doc.clone(),
+            identifier: self.identifier.clone(),
+            metadata: self
+                .metadata
+                .iter()
+                .map(|(name, value)| ApiMetadata {
+                    name: name.clone(),
+                    value: value.clone(),
+                })
+                .collect(),
+            suite_count: self.suite_count,
+            test_count: self.test_count,
+            statistics: ApiStatisticCounts {
+                pass_count: self.pass_count,
+                fail_count: self.fail_count,
+                skip_count: self.skip_count,
+            },
+        }
+    }
+}
+
+/// Tests of a suite in the order they ran.
+#[derive(Debug)]
+pub struct SuiteTestsRequest {
+    pub cursor: Option<PageCursor>,
+    pub limit: i64,
+}
+
+pub struct SuiteTestPage {
+    pub tests: Vec<TestRunTest>,
+    pub next_cursor: Option<PageCursor>,
+}
+
+impl SuiteTestPage {
+    pub fn to_api(&self) -> ApiSuiteTestPage {
+        ApiSuiteTestPage {
+            tests: self.tests.iter().map(TestRunTest::to_api).collect(),
+            next_cursor: self.next_cursor.map(|cursor| cursor.to_string()),
+        }
+    }
+}
+
 pub struct TestRunTest {
     pub id: i32,
     pub name: String,
-    pub line: i32,
+    pub line: Option<i32>,
     pub identifier: String,
     pub tags: Vec<String>,
     pub status: String,
+    pub message: Option<String>,
     pub start_time: NaiveDateTime,
     pub end_time: NaiveDateTime,
     pub doc: Option<String>,
     pub timeout: Option<String>,
+    pub attempt: i32,
+    /// Failed at first and passed when rerun.
+    pub passed_on_retry: bool,
 }
 
 impl TestRunTest {
@@ -128,14 +446,396 @@ impl TestRunTest {
             identifier: self.identifier.clone(),
             tags: self.tags.clone(),
             status: self.status.clone(),
+            message: self.message.clone(),
             start_time: utils::date::format_datetime(self.start_time),
             end_time: utils::date::format_datetime(self.end_time),
             doc: self.doc.clone(),
             timeout: self.timeout.clone(),
+            attempt: self.attempt,
+            passed_on_retry: self.passed_on_retry,
         }
     }
 }
 
+/// One run of a test, the last one is the result shown in the test run.
+pub struct TestAttempt {
+    pub id: i32,
+    pub attempt: i32,
+    pub status: String,
+    pub start_time: NaiveDateTime,
+    pub end_time: NaiveDateTime,
+}
+
+impl TestAttempt {
+    pub fn to_api(&self) -> ApiTestAttempt {
+        ApiTestAttempt {
+            id: self.id,
+            attempt: self.attempt,
+            status: self.status.clone(),
+            start_time: utils::date::format_datetime(self.start_time),
+            end_time: utils::date::format_datetime(self.end_time),
+        }
+    }
+}
+
+/// What identifies a test across the runs of a project.
+#[derive(Debug, Clone)]
+pub struct TestIdentity {
+    pub project_id: i32,
+    /// Path of its suite from the root suite of the output file.
+    pub suite: String,
+    pub name: String,
+}
+
+/// The outcome of a test in one run, its last attempt when rerun.
+pub struct TestHistoryEntry {
+    pub test_id: i32,
+    pub test_run_id: i32,
+    pub test_run_date: NaiveDateTime,
+    pub app_version: String,
+    pub environment: Option<String>,
+    pub branch: Option<String>,
+    pub status: String,
+    pub message: Option<String>,
+    pub start_time: NaiveDateTime,
+    pub end_time: NaiveDateTime,
+    pub attempt: i32,
+}
+
+impl TestHistoryEntry {
+    fn to_api(&self) -> ApiTestHistoryEntry {
+        ApiTestHistoryEntry {
+            test_id: self.test_id,
+            test_run_id: self.test_run_id,
+            test_run_date: utils::date::format_datetime(self.test_run_date),
+            app_version: self.app_version.clone(),
+            environment: self.environment.clone(),
+            branch: self.branch.clone(),
+            status: self.status.clone(),
+            message: self.message.clone(),
+            start_time: utils::date::format_datetime(self.start_time),
+            end_time: utils::date::format_datetime(self.end_time),
+            duration_ms: (self.end_time - self.start_time).num_milliseconds(),
+            attempt: self.attempt,
+        }
+    }
+}
+
+/// Latest runs first. `status` keeps the runs where the test ended with it.
+#[derive(Debug)]
+pub struct TestHistoryRequest {
+    pub status: Option<String>,
+    pub cursor: Option<PageCursor>,
+    pub limit: i64,
+}
+
+pub struct TestHistory {
+    pub test: TestIdentity,
+    pub entries: Vec<TestHistoryEntry>,
+    pub next_cursor: Option<PageCursor>,
+}
+
+impl TestHistory {
+    pub fn to_api(&self) -> ApiTestHistory {
+        ApiTestHistory {
+            project_id: self.test.project_id,
+            suite: self.test.suite.clone(),
+            name: self.test.name.clone(),
+            entries: self.entries.iter().map(TestHistoryEntry::to_api).collect(),
+            next_cursor: self.next_cursor.map(|cursor| cursor.to_string()),
+        }
+    }
+}
+
+/// How often a test flips between PASS and FAIL from one run to the next
+/// of the same app version. `score` is the share of these consecutive runs
+/// where it did.
+pub struct FlakyTest {
+    pub suite: String,
+    pub name: String,
+    pub score: f64,
+    pub run_count: i32,
+    pub transitions: i32,
+    pub flips: i32,
+    pub last_test_run_id: i32,
+    pub last_run_date: NaiveDateTime,
+    pub last_app_version: String,
+    pub last_status: String,
+}
+
+impl FlakyTest {
+    pub fn to_api(&self) -> ApiFlakyTest {
+        ApiFlakyTest {
+            suite: self.suite.clone(),
+            name: self.name.clone(),
+            score: self.score,
+            run_count: self.run_count,
+            transitions: self.transitions,
+            flips: self.flips,
+            last_test_run_id: self.last_test_run_id,
+            last_run_date: utils::date::format_datetime(self.last_run_date),
+            last_app_version: self.last_app_version.clone(),
+            last_status: self.last_status.clone(),
+        }
+    }
+}
+
+/// Words to look for, as typed in a search engine: `"HTTP 502"` for a
+/// phrase, `or` between alternatives and `-` before the words to exclude.
+#[derive(Debug)]
+pub struct SearchRequest {
+    pub text: String,
+    pub project_id: Option<i32>,
+    pub test_run_id: Option<i32>,
+    pub from: Option<NaiveDateTime>,
+    pub to: Option<NaiveDateTime>,
+    pub limit: i64,
+}
+
+pub struct SearchMatch {
+    pub project_id: i32,
+    pub test_run_id: i32,
+    pub test_run_date: NaiveDateTime,
+    pub suite_id: i32,
+    pub suite_identifier: String,
+    pub suite: String,
+    pub test_id: Option<i32>,
+    pub test_identifier: Option<String>,
+    pub test_name: Option<String>,
+    pub keyword_type: Option<String>,
+    /// Keys from the root of the keywords of this type.
+    pub keyword_path: Option<Vec<String>>,
+    pub keyword_name: Option<String>,
+    pub excerpt: String,
+}
+
+impl SearchMatch {
+    pub fn to_api(&self) -> ApiSearchMatch {
+        ApiSearchMatch {
+            project_id: self.project_id,
+            test_run_id: self.test_run_id,
+            test_run_date: utils::date::format_datetime(self.test_run_date),
+            suite_id: self.suite_id,
+            suite_identifier: self.suite_identifier.clone(),
+            suite: self.suite.clone(),
+            test_id: self.test_id,
+            test_identifier: self.test_identifier.clone(),
+            test_name: self.test_name.clone(),
+            keyword_type: self.keyword_type.clone(),
+            keyword_path: self
+                .keyword_path
+                .as_ref()
+                .map(|path| path.iter().map(|key| format!("/{}", key)).collect()),
+            keyword_name: self.keyword_name.clone(),
+            excerpt: self.excerpt.clone(),
+        }
+    }
+}
+
+/// The failed tests of a run that share a signature, their last attempts
+/// when rerun.
+pub struct FailureGroup {
+    pub signature: String,
+    pub failure_count: i32,
+    pub example_messages: Vec<String>,
+    pub tests: Vec<FailedTest>,
+    pub first_seen_date: NaiveDateTime,
+}
+
+impl FailureGroup {
+    pub fn to_api(&self) -> ApiFailureGroup {
+        ApiFailureGroup {
+            signature: self.signature.clone(),
+            failure_count: self.failure_count,
+            example_messages: self.example_messages.clone(),
+            tests: self.tests.iter().map(FailedTest::to_api).collect(),
+            first_seen_date: utils::date::format_datetime(self.first_seen_date),
+        }
+    }
+}
+
+pub struct FailedTest {
+    pub test_id: i32,
+    pub suite: String,
+    pub name: String,
+}
+
+impl FailedTest {
+    fn to_api(&self) -> ApiFailedTest {
+        ApiFailedTest {
+            test_id: self.test_id,
+            suite: self.suite.clone(),
+            name: self.name.clone(),
+        }
+    }
+}
+
+/// The failures of the runs of a project that share a signature. The dates
+/// are the ones of the first and last of these runs.
+pub struct ProjectFailureGroup {
+    pub signature: String,
+    pub failure_count: i32,
+    pub test_count: i32,
+    pub test_run_count: i32,
+    pub example_messages: Vec<String>,
+    pub first_seen_date: NaiveDateTime,
+    pub last_seen_date: NaiveDateTime,
+    pub last_test_run_id: i32,
+}
+
+impl ProjectFailureGroup {
+    pub fn to_api(&self) -> ApiProjectFailureGroup {
+        ApiProjectFailureGroup {
+            signature: self.signature.clone(),
+            failure_count: self.failure_count,
+            test_count: self.test_count,
+            test_run_count: self.test_run_count,
+            example_messages: self.example_messages.clone(),
+            first_seen_date: utils::date::format_datetime(self.first_seen_date),
+            last_seen_date: utils::date::format_datetime(self.last_seen_date),
+            last_test_run_id: self.last_test_run_id,
+        }
+    }
+}
+
+/// The result of a test in one of two compared runs.
+pub struct ComparedResult {
+    pub test_id: i32,
+    pub status: String,
+    pub duration_ms: i64,
+}
+
+impl ComparedResult {
+    fn is_failing(&self) -> bool {
+        self.status == "FAIL"
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TestChange {
+    NewlyFailing,
+    Fixed,
+    StillFailing,
+    StillPassing,
+    Added,
+    Removed,
+}
+
+/// A test matched by the path of its suite and its name, missing from the
+/// run it is not part of.
+pub struct TestComparison {
+    pub suite: String,
+    pub name: String,
+    pub base: Option<ComparedResult>,
+    pub target: Option<ComparedResult>,
+}
+
+impl TestComparison {
+    /// Only FAIL counts as failing.
+    pub fn change(&self) -> TestChange {
+        match (&self.base, &self.target) {
+            (Some(base), Some(target)) => match (base.is_failing(), target.is_failing()) {
+                (false, true) => TestChange::NewlyFailing,
+                (true, false) => TestChange::Fixed,
+                (true, true) => TestChange::StillFailing,
+                (false, false) => TestChange::StillPassing,
+            },
+            (None, _) => TestChange::Added,
+            (Some(_), None) => TestChange::Removed,
+        }
+    }
+
+    fn to_api(&self) -> ApiComparedTest {
+        ApiComparedTest {
+            suite: self.suite.clone(),
+            name: self.name.clone(),
+            base_test_id: self.base.as_ref().map(|base| base.test_id),
+            base_status: self.base.as_ref().map(|base| base.status.clone()),
+            base_duration_ms: self.base.as_ref().map(|base| base.duration_ms),
+            target_test_id: self.target.as_ref().map(|target| target.test_id),
+            target_status: self.target.as_ref().map(|target| target.status.clone()),
+            target_duration_ms: self.target.as_ref().map(|target| target.duration_ms),
+            duration_change_ms: self
+                .base
+                .as_ref()
+                .zip(self.target.as_ref())
+                .map(|(base, target)| target.duration_ms - base.duration_ms),
+        }
+    }
+}
+
+/// The counts of a tag in two compared runs.
+pub struct TagStatisticChange {
+    pub tag: String,
+    pub base: Option<TestRunStatistic>,
+    pub target: Option<TestRunStatistic>,
+}
+
+impl TagStatisticChange {
+    fn counts(statistic: &Option<TestRunStatistic>) -> (i32, i32, i32) {
+        statistic.as_ref().map_or((0, 0, 0), |statistic| {
+            (
+                statistic.pass_count,
+                statistic.fail_count,
+                statistic.skip_count,
+            )
+        })
+    }
+
+    pub fn is_changed(&self) -> bool {
+        Self::counts(&self.base) != Self::counts(&self.target)
+    }
+
+    fn to_api(&self) -> ApiTagStatisticChange {
+        let (base_pass, base_fail, base_skip) = Self::counts(&self.base);
+        let (target_pass, target_fail, target_skip) = Self::counts(&self.target);
+        ApiTagStatisticChange {
+            tag: self.tag.clone(),
+            base: self.base.as_ref().map(TestRunStatistic::to_counts_api),
+            target: self.target.as_ref().map(TestRunStatistic::to_counts_api),
+            pass_change: target_pass - base_pass,
+            fail_change: target_fail - base_fail,
+            skip_change: target_skip - base_skip,
+        }
+    }
+}
+
+pub struct TestRunComparison {
+    pub base_test_run_id: i32,
+    pub target_test_run_id: i32,
+    /// Ordered by suite and name.
+    pub tests: Vec<TestComparison>,
+    pub tags: Vec<TagStatisticChange>,
+}
+
+impl TestRunComparison {
+    pub fn to_api(&self) -> ApiTestRunComparison {
+        let mut comparison = ApiTestRunComparison {
+            base_test_run_id: self.base_test_run_id,
+            target_test_run_id: self.target_test_run_id,
+            newly_failing: vec![],
+            fixed: vec![],
+            still_failing: vec![],
+            still_passing: vec![],
+            added: vec![],
+            removed: vec![],
+            tags: self.tags.iter().map(TagStatisticChange::to_api).collect(),
+        };
+        for test in &self.tests {
+            let tests = match test.change() {
+                TestChange::NewlyFailing => &mut comparison.newly_failing,
+                TestChange::Fixed => &mut comparison.fixed,
+                TestChange::StillFailing => &mut comparison.still_failing,
+                TestChange::StillPassing => &mut comparison.still_passing,
+                TestChange::Added => &mut comparison.added,
+                TestChange::Removed => &mut comparison.removed,
+            };
+            tests.push(test.to_api());
+        }
+        comparison
+    }
+}
+
 #[derive(Clone)]
 pub enum StatisticType {
     Total,
@@ -177,12 +877,21 @@ impl TestRunStatistic {
             text: self.text.clone(),
         }
     }
+
+    fn to_counts_api(&self) -> ApiStatisticCounts {
+        ApiStatisticCounts {
+            pass_count: self.pass_count,
+            fail_count: self.fail_count,
+            skip_count: self.skip_count,
+        }
+    }
 }
 pub struct TestRunError {
     pub id: i32,
     pub timestamp: NaiveDateTime,
     pub level: String,
     pub content: String,
+    pub html: bool,
 }
 
 impl TestRunError {
@@ -192,6 +901,7 @@ impl TestRunError {
             timestamp: utils::date::format_datetime(self.timestamp),
             level: self.level.clone(),
             content: self.content.clone(),
+            html: self.html,
         }
     }
 }
@@ -216,3 +926,17 @@ impl SuiteKeywords {
         }
     }
 }
+
+pub struct TestKeywords {
+    pub keywords: Arc<HashMap<String, Value>>,
+}
+
+impl TestKeywords {
+    pub fn to_api(&self) -> ApiTestKeywords<'_> {
+        ApiTestKeywords {
+            setup_keyword: self.keywords.get("setup"),
+            keywords: self.keywords.get("body"),
+            teardown_keyword: self.keywords.get("teardown"),
+        }
+    }
+}
@@ -2,7 +2,10 @@ use chrono::NaiveDateTime;
 use serde::Serialize;
 use sqlx::FromRow;
 
-use crate::services::parser::{self};
+use crate::{
+    models::robot::{db::SourceFormatDB, domain::CiMetadata},
+    services::parser::{self},
+};
 
 #[derive(Debug, Serialize, FromRow)]
 pub struct TestRunDBLegacy {
@@ -11,29 +14,35 @@ pub struct TestRunDBLegacy {
     pub generator: String,
     pub generated_date: NaiveDateTime,
     pub schema_version: String,
+    #[serde(skip)]
+    pub source_format: SourceFormatDB,
     pub imported_date: Option<NaiveDateTime>,
-    pub suites: Vec<SuiteDBLegacy>,
-    pub statistics: Vec<StatDBLegacy>,
-    pub errors: Vec<ErrorDBLegacy>,
     pub sha1: String,
     // Metadata
     pub app_name: String,
     pub app_version: String,
+    pub environment: Option<String>,
+    pub branch: Option<String>,
+    #[serde(skip)]
+    #[sqlx(skip)]
+    pub ci: CiMetadata,
+    #[sqlx(skip)]
+    pub labels: Vec<(String, String)>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct SuiteDBLegacy {
     pub id: Option<i32>,
     pub name: String,
     pub source: Option<String>,
     pub status: String,
+    pub message: Option<String>,
     pub start_time: NaiveDateTime,
     pub end_time: NaiveDateTime,
     pub doc: Option<String>,
     pub identifier: String,
+    pub metadata: Vec<(String, String)>,
     pub setup_keyword: Option<parser::Keyword>,
-    pub suites: Vec<SuiteDBLegacy>,
-    pub tests: Vec<TestDBLegacy>,
     pub teardown_keyword: Option<parser::Keyword>,
 }
 
@@ -41,15 +50,19 @@ pub struct SuiteDBLegacy {
 pub struct TestDBLegacy {
     pub id: Option<i32>,
     pub name: String,
-    pub line: i32,
+    pub line: Option<i32>,
     pub identifier: String,
     pub tags: Vec<String>,
     pub status: String,
+    pub message: Option<String>,
     pub start_time: NaiveDateTime,
     pub end_time: NaiveDateTime,
     pub doc: Option<String>,
     pub timeout: Option<String>,
+    pub setup_keyword: Option<parser::Keyword>,
     pub keywords: Vec<parser::BaseBody>,
+    pub teardown_keyword: Option<parser::Keyword>,
+    pub attempt: i32,
 }
 
 #[derive(Debug, Serialize)]
@@ -78,4 +91,5 @@ pub struct ErrorDBLegacy {
     pub timestamp: NaiveDateTime,
     pub level: String,
     pub content: String,
+    pub html: bool,
 }
@@ -0,0 +1,223 @@
+use serde_json::Value;
+use sqlx::PgPool;
+
+use crate::models::imports::{
+    db::{ImportJobDB, ImportStatusDB},
+    domain::{ImportJob, ImportStatus},
+};
+
+pub struct ImportsRepository {
+    pool: PgPool,
+}
+
+impl ImportsRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn insert_import_job(
+        &self,
+        project_id: i32,
+        file_names: &[String],
+    ) -> Result<i32, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"--sql
+            INSERT INTO import_jobs (project_id, file_names)
+            VALUES ($1, $2)
+            RETURNING id
+            "#,
+            project_id,
+            file_names
+        )
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(|e| tracing::error!("Query insert_import_job failed: {:?}", e))
+    }
+
+    /// One job for each test run of the project, or of all projects, or for
+    /// the one test run, named after the output files of the run. Returns the
+    /// ids of the jobs with those of their test runs.
+    pub async fn insert_reimport_jobs(
+        &self,
+        project_id: Option<i32>,
+        test_run_id: Option<i32>,
+    ) -> Result<Vec<(i32, i32)>, sqlx::Error> {
+        sqlx::query!(
+            r#"--sql
+            INSERT INTO import_jobs (project_id, file_names, test_run_id, reimport)
+            SELECT tr.project_id,
+                ARRAY(
+                    SELECT COALESCE(parts.file_name, parts.sha1)
+                    FROM test_run_parts parts
+                    WHERE parts.test_run_id = tr.id
+                    ORDER BY parts.part_index
+                ),
+                tr.id,
+                TRUE
+            FROM test_runs tr
+            WHERE (
+                    $1::INTEGER IS NULL
+                    OR tr.project_id = $1
+                )
+                AND (
+                    $2::INTEGER IS NULL
+                    OR tr.id = $2
+                )
+            ORDER BY tr.id
+            RETURNING id, test_run_id AS "test_run_id!"
+            "#,
+            project_id,
+            test_run_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .inspect_err(|e| tracing::error!("Query insert_reimport_jobs failed: {:?}", e))
+        .map(|jobs| {
+            jobs.into_iter()
+                .map(|job| (job.id, job.test_run_id))
+                .collect()
+        })
+    }
+
+    pub async fn update_import_job_status(
+        &self,
+        job_id: i32,
+        status: ImportStatus,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"--sql
+            UPDATE import_jobs
+            SET status = $2, update_date = NOW()
+            WHERE id = $1
+            "#,
+            job_id,
+            ImportStatusDB::from(status) as ImportStatusDB
+        )
+        .execute(&self.pool)
+        .await
+        .inspect_err(|e| tracing::error!("Query update_import_job_status failed: {:?}", e))?;
+        Ok(())
+    }
+
+    pub async fn finish_import_job(
+        &self,
+        job_id: i32,
+        test_run_id: i32,
+        already_imported: bool,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"--sql
+            UPDATE import_jobs
+            SET status = 'done', test_run_id = $2, already_imported = $3, update_date = NOW()
+            WHERE id = $1
+            "#,
+            job_id,
+            test_run_id,
+            already_imported
+        )
+        .execute(&self.pool)
+        .await
+        .inspect_err(|e| tracing::error!("Query finish_import_job failed: {:?}", e))?;
+        Ok(())
+    }
+
+    pub async fn fail_import_job(
+        &self,
+        job_id: i32,
+        error: &str,
+        problems: Option<Value>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"--sql
+            UPDATE import_jobs
+            SET status = 'failed', error = $2, problems = $3, update_date = NOW()
+            WHERE id = $1
+            "#,
+            job_id,
+            error,
+            problems
+        )
+        .execute(&self.pool)
+        .await
+        .inspect_err(|e| tracing::error!("Query fail_import_job failed: {:?}", e))?;
+        Ok(())
+    }
+
+    /// Jobs left unfinished by a stopped server, whose uploaded files are gone.
+    pub async fn fail_unfinished_import_jobs(&self, error: &str) -> Result<u64, sqlx::Error> {
+        sqlx::query!(
+            r#"--sql
+            UPDATE import_jobs
+            SET status = 'failed', error = $1, update_date = NOW()
+            WHERE status NOT IN ('done', 'failed')
+            "#,
+            error
+        )
+        .execute(&self.pool)
+        .await
+        .inspect_err(|e| tracing::error!("Query fail_unfinished_import_jobs failed: {:?}", e))
+        .map(|result| result.rows_affected())
+    }
+
+    pub async fn get_import_job_by_id(
+        &self,
+        job_id: i32,
+    ) -> Result<Option<ImportJob>, sqlx::Error> {
+        sqlx::query_as!(
+            ImportJobDB,
+            r#"--sql
+            SELECT id,
+                project_id,
+                file_names,
+                status AS "status: ImportStatusDB",
+                test_run_id,
+                already_imported,
+                reimport,
+                error,
+                problems,
+                create_date,
+                update_date
+            FROM import_jobs
+            WHERE id = $1
+            "#,
+            job_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .inspect_err(|e| tracing::error!("Query get_import_job_by_id failed: {:?}", e))
+        .map(|job| job.map(ImportJobDB::into_job))
+    }
+
+    pub async fn get_import_jobs_by_project_id(
+        &self,
+        project_id: i32,
+        limit: i64,
+    ) -> Result<Vec<ImportJob>, sqlx::Error> {
+        sqlx::query_as!(
+            ImportJobDB,
+            r#"--sql
+            SELECT id,
+                project_id,
+                file_names,
+                status AS "status: ImportStatusDB",
+                test_run_id,
+                already_imported,
+                reimport,
+                error,
+                problems,
+                create_date,
+                update_date
+            FROM import_jobs
+            WHERE project_id = $1
+            ORDER BY id DESC
+            LIMIT $2
+            "#,
+            project_id,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+        .inspect_err(|e| tracing::error!("Query get_import_jobs_by_project_id failed: {:?}", e))
+        .map(|jobs| jobs.into_iter().map(ImportJobDB::into_job).collect())
+    }
+}
@@ -1,2 +1,3 @@
+pub mod imports;
 pub mod projects;
 pub mod robot;
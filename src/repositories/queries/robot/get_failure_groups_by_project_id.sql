@@ -0,0 +1,111 @@
+WITH runs AS (
+    SELECT tr.id,
+        tr.generated_date
+    FROM test_runs tr
+        JOIN test_run_statistics stats ON stats.test_run_id = tr.id
+        AND stats.stat_type = 'total'
+    WHERE tr.project_id = $1
+        AND (
+            $4::TEXT IS NULL
+            OR tr.environment IS NOT DISTINCT FROM NULLIF($4, '')
+        )
+        AND (
+            $11::TEXT IS NULL
+            OR tr.branch IS NOT DISTINCT FROM NULLIF($11, '')
+        )
+        AND (
+            $5::TEXT IS NULL
+            OR tr.ci_system = $5
+        )
+        AND (
+            $6::TEXT IS NULL
+            OR tr.build_number = $6
+        )
+        AND (
+            $7::TEXT IS NULL
+            OR starts_with(tr.robot_git_hash, $7)
+        )
+        AND (
+            $8::TEXT IS NULL
+            OR starts_with(tr.app_git_hash, $8)
+        )
+        AND (
+            $12::TIMESTAMP IS NULL
+            OR tr.generated_date >= $12
+        )
+        AND (
+            $13::TIMESTAMP IS NULL
+            OR tr.generated_date <= $13
+        )
+        AND (
+            $14::TEXT IS NULL
+            OR tr.application_version = $14
+        )
+        AND (
+            $15::BOOLEAN IS NULL
+            OR (stats.fail_count > 0) = $15
+        )
+        AND NOT EXISTS (
+            SELECT 1
+            FROM unnest($9::TEXT [], $10::TEXT []) AS filter(name, value)
+            WHERE NOT EXISTS (
+                    SELECT 1
+                    FROM test_run_labels l
+                    WHERE l.test_run_id = tr.id
+                        AND l.name = filter.name
+                        AND l.value = filter.value
+                )
+        )
+        AND NOT EXISTS (
+            SELECT 1
+            FROM unnest($2::TEXT [], $3::TEXT []) AS filter(name, value)
+            WHERE NOT EXISTS (
+                    SELECT 1
+                    FROM suites s
+                        JOIN suite_metadata m ON m.suite_id = s.id
+                    WHERE s.test_run_id = tr.id
+                        AND s.parent_suite_id IS NULL
+                        AND m.name = filter.name
+                        AND m.value = filter.value
+                )
+        )
+),
+failures AS (
+    SELECT t.failure_signature,
+        t.message,
+        s.full_name AS suite,
+        t.name,
+        r.id AS test_run_id,
+        r.generated_date
+    FROM runs r
+        JOIN suites s ON s.test_run_id = r.id
+        JOIN tests t ON t.suite_id = s.id
+    WHERE t.superseded_by IS NULL
+        AND t.failure_signature IS NOT NULL
+)
+SELECT failure_signature AS "signature!",
+    COUNT(*)::INTEGER AS "failure_count!",
+    COUNT(DISTINCT (suite, name))::INTEGER AS "test_count!",
+    COUNT(DISTINCT test_run_id)::INTEGER AS "test_run_count!",
+    COALESCE(
+        (
+            ARRAY_AGG(DISTINCT message) FILTER (
+                WHERE message IS NOT NULL
+            )
+        ) [1:3],
+        ARRAY []::TEXT []
+    ) AS "example_messages!",
+    MIN(generated_date) AS "first_seen_date!",
+    MAX(generated_date) AS "last_seen_date!",
+    (
+        ARRAY_AGG(
+            test_run_id
+            ORDER BY generated_date DESC,
+                test_run_id DESC
+        )
+    ) [1] AS "last_test_run_id!"
+FROM failures
+GROUP BY failure_signature
+ORDER BY 2 DESC,
+    1
+LIMIT $16;
\ No newline at end of file
@@ -0,0 +1,64 @@
+WITH failures AS (
+    SELECT t.id,
+        s.full_name AS suite,
+        t.name,
+        t.message,
+        t.failure_signature
+    FROM tests t
+        JOIN suites s ON s.id = t.suite_id
+    WHERE s.test_run_id = $1
+        AND t.superseded_by IS NULL
+        AND t.failure_signature IS NOT NULL
+),
+first_seen AS (
+    SELECT t.failure_signature,
+        MIN(tr.generated_date) AS first_seen_date
+    FROM tests t
+        JOIN suites s ON s.id = t.suite_id
+        JOIN test_runs tr ON tr.id = s.test_run_id
+    WHERE tr.project_id = (
+            SELECT project_id
+            FROM test_runs
+            WHERE id = $1
+        )
+        AND t.superseded_by IS NULL
+        AND t.failure_signature IN (
+            SELECT failure_signature
+            FROM failures
+        )
+    GROUP BY t.failure_signature
+)
+SELECT f.failure_signature AS "signature!",
+    COUNT(*)::INTEGER AS "failure_count!",
+    COALESCE(
+        (
+            ARRAY_AGG(DISTINCT f.message) FILTER (
+                WHERE f.message IS NOT NULL
+            )
+        ) [1:3],
+        ARRAY []::TEXT []
+    ) AS "example_messages!",
+    ARRAY_AGG(
+        f.id
+        ORDER BY f.suite,
+            f.name,
+            f.id
+    ) AS "test_ids!",
+    ARRAY_AGG(
+        f.suite
+        ORDER BY f.suite,
+            f.name,
+            f.id
+    ) AS "test_suites!",
+    ARRAY_AGG(
+        f.name
+        ORDER BY f.suite,
+            f.name,
+            f.id
+    ) AS "test_names!",
+    MIN(fs.first_seen_date) AS "first_seen_date!"
+FROM failures f
+    JOIN first_seen fs ON fs.failure_signature = f.failure_signature
+GROUP BY f.failure_signature
+ORDER BY 2 DESC,
+    1;
\ No newline at end of file
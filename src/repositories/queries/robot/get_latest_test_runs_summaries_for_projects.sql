@@ -1,13 +1,17 @@
 WITH total_count AS (
     SELECT project_id,
+        environment,
+        branch,
         COUNT(*)::INTEGER as total_rows
     FROM test_runs tr
     WHERE tr.project_id IN (
             SELECT unnest($1::integer [])
         )
-    GROUP BY project_id
+    GROUP BY project_id,
+        environment,
+        branch
 )
-SELECT DISTINCT ON (tr.project_id) tr.project_id,
+SELECT DISTINCT ON (tr.project_id, tr.environment, tr.branch) tr.project_id,
     total_count.total_rows as test_run_count,
     tr.id as test_run_id,
     tr.application_version as application_version,
@@ -16,10 +20,22 @@ SELECT DISTINCT ON (tr.project_id) tr.project_id,
     stats.fail_count as failed_tests,
     stats.skip_count as skipped_tests,
     errors.error_count as error_count,
-    timing.elapsed_time as elapsed_time
+    timing.elapsed_time as elapsed_time,
+    tr.environment,
+    tr.branch,
+    tr.ci_system,
+    tr.build_url,
+    tr.build_number,
+    tr.robot_git_hash,
+    tr.app_git_hash,
+    COALESCE(labels.names, '{}') AS "label_names!",
+    COALESCE(labels.values, '{}') AS "label_values!",
+    NULL::BIGINT AS sort_key
 FROM test_runs tr
     JOIN test_run_statistics stats ON stats.test_run_id = tr.id
     JOIN total_count ON total_count.project_id = tr.project_id
+    AND total_count.environment IS NOT DISTINCT FROM tr.environment
+    AND total_count.branch IS NOT DISTINCT FROM tr.branch
     LEFT JOIN (
         SELECT test_run_id,
             COUNT(*)::INTEGER as error_count
@@ -32,9 +48,18 @@ FROM test_runs tr
         FROM suites s
         WHERE s.parent_suite_id IS NULL
     ) timing ON timing.test_run_id = tr.id
+    LEFT JOIN (
+        SELECT test_run_id,
+            array_agg(name ORDER BY name) AS names,
+            array_agg(value ORDER BY name) AS values
+        FROM test_run_labels
+        GROUP BY test_run_id
+    ) labels ON labels.test_run_id = tr.id
 WHERE tr.project_id IN (
         SELECT unnest($1::integer [])
     )
     AND stats.stat_type = 'total'
 ORDER BY tr.project_id,
+    tr.environment,
+    tr.branch,
     tr.generated_date DESC;
\ No newline at end of file
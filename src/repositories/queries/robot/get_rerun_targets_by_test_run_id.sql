@@ -0,0 +1,26 @@
+WITH RECURSIVE suite_paths AS (
+    SELECT s.id,
+        s.identifier,
+        s.name AS path
+    FROM suites s
+    WHERE s.test_run_id = $1
+        AND s.parent_suite_id IS NULL
+    UNION ALL
+    SELECT s.id,
+        s.identifier,
+        suite_paths.path || '.' || s.name
+    FROM suites s
+        JOIN suite_paths ON s.parent_suite_id = suite_paths.id
+)
+SELECT suite_paths.id AS "id!",
+    suite_paths.identifier AS "identifier!",
+    suite_paths.path AS "path!",
+    t.id AS "test_id?",
+    t.identifier AS "test_identifier?",
+    t.name AS "test_name?",
+    t.attempt AS "test_attempt?"
+FROM suite_paths
+    LEFT JOIN tests t ON t.suite_id = suite_paths.id
+    AND t.superseded_by IS NULL
+ORDER BY suite_paths.id,
+    t.id;
\ No newline at end of file
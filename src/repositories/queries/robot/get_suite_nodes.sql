@@ -0,0 +1,74 @@
+WITH RECURSIVE nodes AS (
+    SELECT s.*
+    FROM suites s
+    WHERE CASE
+            WHEN $2::INTEGER IS NULL THEN s.test_run_id = $1
+            AND s.parent_suite_id IS NULL
+            ELSE s.parent_suite_id = $2
+        END
+),
+subtree (node_id, suite_id) AS (
+    SELECT n.id,
+        n.id
+    FROM nodes n
+    UNION ALL
+    SELECT st.node_id,
+        child.id
+    FROM subtree st
+        JOIN suites child ON child.parent_suite_id = st.suite_id
+),
+counts AS (
+    SELECT st.node_id,
+        COUNT(*) FILTER (
+            WHERE t.status = 'PASS'
+        ) AS pass_count,
+        COUNT(*) FILTER (
+            WHERE t.status = 'FAIL'
+        ) AS fail_count,
+        COUNT(*) FILTER (
+            WHERE t.status = 'SKIP'
+        ) AS skip_count
+    FROM subtree st
+        JOIN tests t ON t.suite_id = st.suite_id
+    WHERE t.superseded_by IS NULL
+    GROUP BY st.node_id
+)
+SELECT n.id,
+    n.name,
+    n.source,
+    n.status,
+    n.message,
+    n.start_time,
+    n.end_time,
+    n.identifier,
+    n.doc,
+    ARRAY(
+        SELECT m.name
+        FROM suite_metadata m
+        WHERE m.suite_id = n.id
+        ORDER BY m.position
+    ) AS "metadata_names!",
+    ARRAY(
+        SELECT m.value
+        FROM suite_metadata m
+        WHERE m.suite_id = n.id
+        ORDER BY m.position
+    ) AS "metadata_values!",
+    (
+        SELECT COUNT(*)::INTEGER
+        FROM suites child
+        WHERE child.parent_suite_id = n.id
+    ) AS "suite_count!",
+    (
+        SELECT COUNT(*)::INTEGER
+        FROM tests t
+        WHERE t.suite_id = n.id
+            AND t.superseded_by IS NULL
+    ) AS "test_count!",
+    COALESCE(c.pass_count, 0)::INTEGER AS "pass_count!",
+    COALESCE(c.fail_count, 0)::INTEGER AS "fail_count!",
+    COALESCE(c.skip_count, 0)::INTEGER AS "skip_count!"
+FROM nodes n
+    LEFT JOIN counts c ON c.node_id = n.id
+ORDER BY n.start_time,
+    n.id;
\ No newline at end of file
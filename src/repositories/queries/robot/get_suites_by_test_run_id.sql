@@ -0,0 +1,26 @@
+SELECT s.id,
+    s.parent_suite_id,
+    s.name,
+    s.source,
+    s.status,
+    s.start_time,
+    s.end_time,
+    s.identifier,
+    s.doc,
+    s.message,
+    ARRAY(
+        SELECT m.name
+        FROM suite_metadata m
+        WHERE m.suite_id = s.id
+        ORDER BY m.position
+    ) AS "metadata_names!",
+    ARRAY(
+        SELECT m.value
+        FROM suite_metadata m
+        WHERE m.suite_id = s.id
+        ORDER BY m.position
+    ) AS "metadata_values!"
+FROM suites s
+WHERE s.test_run_id = $1
+ORDER BY s.start_time,
+    s.id;
\ No newline at end of file
@@ -0,0 +1,33 @@
+SELECT t.id AS test_id,
+    tr.id AS test_run_id,
+    tr.generated_date AS test_run_date,
+    tr.application_version AS app_version,
+    tr.environment,
+    tr.branch,
+    t.status,
+    t.message,
+    t.start_time,
+    t.end_time,
+    t.attempt,
+    sort.sort_key AS "sort_key!"
+FROM tests t
+    JOIN suites s ON s.id = t.suite_id
+    JOIN test_runs tr ON tr.id = s.test_run_id
+    CROSS JOIN LATERAL (
+        SELECT (EXTRACT(EPOCH FROM tr.generated_date) * 1000000)::BIGINT AS sort_key
+    ) sort
+WHERE tr.project_id = $1
+    AND s.full_name = $2
+    AND t.name = $3
+    AND t.superseded_by IS NULL
+    AND (
+        $4::TEXT IS NULL
+        OR t.status = $4
+    )
+    AND (
+        $5::BIGINT IS NULL
+        OR (sort.sort_key, t.id) < ($5, $6::INTEGER)
+    )
+ORDER BY sort.sort_key DESC,
+    t.id DESC
+LIMIT $7;
\ No newline at end of file
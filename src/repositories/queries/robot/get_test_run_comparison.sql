@@ -0,0 +1,43 @@
+WITH base AS (
+    SELECT DISTINCT ON (s.full_name, t.name) s.full_name AS suite,
+        t.name,
+        t.id,
+        t.status,
+        t.start_time,
+        t.end_time
+    FROM tests t
+        JOIN suites s ON s.id = t.suite_id
+    WHERE s.test_run_id = $1
+        AND t.superseded_by IS NULL
+    ORDER BY s.full_name,
+        t.name,
+        t.id
+),
+target AS (
+    SELECT DISTINCT ON (s.full_name, t.name) s.full_name AS suite,
+        t.name,
+        t.id,
+        t.status,
+        t.start_time,
+        t.end_time
+    FROM tests t
+        JOIN suites s ON s.id = t.suite_id
+    WHERE s.test_run_id = $2
+        AND t.superseded_by IS NULL
+    ORDER BY s.full_name,
+        t.name,
+        t.id
+)
+SELECT COALESCE(b.suite, tg.suite) AS "suite!",
+    COALESCE(b.name, tg.name) AS "name!",
+    b.id AS "base_test_id?",
+    b.status AS "base_status?",
+    (EXTRACT(EPOCH FROM b.end_time - b.start_time) * 1000)::BIGINT AS "base_duration_ms?",
+    tg.id AS "target_test_id?",
+    tg.status AS "target_status?",
+    (EXTRACT(EPOCH FROM tg.end_time - tg.start_time) * 1000)::BIGINT AS "target_duration_ms?"
+FROM base b
+    FULL OUTER JOIN target tg ON tg.suite = b.suite
+    AND tg.name = b.name
+ORDER BY 1,
+    2;
\ No newline at end of file
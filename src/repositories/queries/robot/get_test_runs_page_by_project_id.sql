@@ -0,0 +1,132 @@
+SELECT tr.project_id AS project_id,
+    0 AS test_run_count,
+    tr.id AS test_run_id,
+    tr.application_version AS application_version,
+    tr.generated_date AS test_run_date,
+    timing.elapsed_time AS elapsed_time,
+    stats.pass_count AS passed_tests,
+    stats.fail_count AS failed_tests,
+    stats.skip_count AS skipped_tests,
+    errors.error_count AS error_count,
+    tr.environment,
+    tr.branch,
+    tr.ci_system,
+    tr.build_url,
+    tr.build_number,
+    tr.robot_git_hash,
+    tr.app_git_hash,
+    COALESCE(labels.names, '{}') AS "label_names!",
+    COALESCE(labels.values, '{}') AS "label_values!",
+    sort.sort_key
+FROM test_runs tr
+    JOIN test_run_statistics stats ON stats.test_run_id = tr.id
+    AND stats.stat_type = 'total'
+    LEFT JOIN (
+        SELECT test_run_id,
+            COUNT(*)::INTEGER AS error_count
+        FROM test_run_errors
+        GROUP BY test_run_id
+    ) errors ON errors.test_run_id = tr.id
+    LEFT JOIN (
+        SELECT test_run_id,
+            s.end_time - s.start_time AS elapsed_time
+        FROM suites s
+        WHERE s.parent_suite_id IS NULL
+    ) timing ON timing.test_run_id = tr.id
+    LEFT JOIN (
+        SELECT test_run_id,
+            array_agg(name ORDER BY name) AS names,
+            array_agg(value ORDER BY name) AS values
+        FROM test_run_labels
+        GROUP BY test_run_id
+    ) labels ON labels.test_run_id = tr.id
+    CROSS JOIN LATERAL (
+        SELECT CASE
+                $16::TEXT
+                WHEN 'duration' THEN COALESCE(
+                    (EXTRACT(EPOCH FROM timing.elapsed_time) * 1000000)::BIGINT,
+                    0
+                )
+                WHEN 'failed_tests' THEN stats.fail_count::BIGINT
+                ELSE (EXTRACT(EPOCH FROM tr.generated_date) * 1000000)::BIGINT
+            END AS sort_key,
+            CASE
+                WHEN $17::BOOLEAN THEN -1
+                ELSE 1
+            END AS direction
+    ) sort
+WHERE tr.project_id = $1
+    AND (
+        $4::TEXT IS NULL
+        OR tr.environment IS NOT DISTINCT FROM NULLIF($4, '')
+    )
+    AND (
+        $11::TEXT IS NULL
+        OR tr.branch IS NOT DISTINCT FROM NULLIF($11, '')
+    )
+    AND (
+        $5::TEXT IS NULL
+        OR tr.ci_system = $5
+    )
+    AND (
+        $6::TEXT IS NULL
+        OR tr.build_number = $6
+    )
+    AND (
+        $7::TEXT IS NULL
+        OR starts_with(tr.robot_git_hash, $7)
+    )
+    AND (
+        $8::TEXT IS NULL
+        OR starts_with(tr.app_git_hash, $8)
+    )
+    AND (
+        $12::TIMESTAMP IS NULL
+        OR tr.generated_date >= $12
+    )
+    AND (
+        $13::TIMESTAMP IS NULL
+        OR tr.generated_date <= $13
+    )
+    AND (
+        $14::TEXT IS NULL
+        OR tr.application_version = $14
+    )
+    AND (
+        $15::BOOLEAN IS NULL
+        OR (stats.fail_count > 0) = $15
+    )
+    AND NOT EXISTS (
+        SELECT 1
+        FROM unnest($9::TEXT [], $10::TEXT []) AS filter(name, value)
+        WHERE NOT EXISTS (
+                SELECT 1
+                FROM test_run_labels l
+                WHERE l.test_run_id = tr.id
+                    AND l.name = filter.name
+                    AND l.value = filter.value
+            )
+    )
+    AND NOT EXISTS (
+        SELECT 1
+        FROM unnest($2::TEXT [], $3::TEXT []) AS filter(name, value)
+        WHERE NOT EXISTS (
+                SELECT 1
+                FROM suites s
+                    JOIN suite_metadata m ON m.suite_id = s.id
+                WHERE s.test_run_id = tr.id
+                    AND s.parent_suite_id IS NULL
+                    AND m.name = filter.name
+                    AND m.value = filter.value
+            )
+    )
+    AND (
+        $18::BIGINT IS NULL
+        OR (
+            sort.sort_key * sort.direction,
+            tr.id * sort.direction
+        ) > ($18 * sort.direction, $19::INTEGER * sort.direction)
+    )
+ORDER BY sort.sort_key * sort.direction,
+    tr.id * sort.direction
+LIMIT $20;
\ No newline at end of file
@@ -0,0 +1,32 @@
+SELECT t.id,
+    t.suite_id,
+    t.identifier,
+    t.name,
+    t.status,
+    t.message,
+    t.start_time,
+    t.end_time,
+    t.line,
+    t.doc,
+    t.timeout,
+    t.attempt,
+    EXISTS(
+        SELECT 1
+        FROM tests previous
+        WHERE previous.superseded_by = t.id
+            AND previous.status = 'FAIL'
+    )
+    AND t.status = 'PASS' AS "passed_on_retry!",
+    ARRAY(
+        SELECT tt.value
+        FROM test_tags tt
+        WHERE tt.test_id = t.id
+        ORDER BY tt.value
+    ) AS "tags!",
+    NULL::BIGINT AS sort_key
+FROM tests t
+    JOIN suites s ON s.id = t.suite_id
+WHERE s.test_run_id = $1
+    AND t.superseded_by IS NULL
+ORDER BY t.start_time,
+    t.id;
\ No newline at end of file
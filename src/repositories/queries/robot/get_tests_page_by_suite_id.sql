@@ -0,0 +1,39 @@
+SELECT t.id,
+    t.suite_id,
+    t.identifier,
+    t.name,
+    t.status,
+    t.message,
+    t.start_time,
+    t.end_time,
+    t.line,
+    t.doc,
+    t.timeout,
+    t.attempt,
+    EXISTS(
+        SELECT 1
+        FROM tests previous
+        WHERE previous.superseded_by = t.id
+            AND previous.status = 'FAIL'
+    )
+    AND t.status = 'PASS' AS "passed_on_retry!",
+    ARRAY(
+        SELECT tt.value
+        FROM test_tags tt
+        WHERE tt.test_id = t.id
+        ORDER BY tt.value
+    ) AS "tags!",
+    sort.sort_key
+FROM tests t
+    CROSS JOIN LATERAL (
+        SELECT (EXTRACT(EPOCH FROM t.start_time) * 1000000)::BIGINT AS sort_key
+    ) sort
+WHERE t.suite_id = $1
+    AND t.superseded_by IS NULL
+    AND (
+        $2::BIGINT IS NULL
+        OR (sort.sort_key, t.id) > ($2, $3::INTEGER)
+    )
+ORDER BY t.start_time,
+    t.id
+LIMIT $4;
\ No newline at end of file
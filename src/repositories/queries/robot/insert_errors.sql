@@ -0,0 +1,9 @@
+INSERT INTO test_run_errors (test_run_id, timestamp, level, content, html)
+SELECT $1,
+    *
+FROM unnest(
+        $2::TIMESTAMP [],
+        $3::TEXT [],
+        $4::TEXT [],
+        $5::BOOLEAN []
+    );
\ No newline at end of file
@@ -0,0 +1,21 @@
+INSERT INTO test_run_statistics (
+        test_run_id,
+        stat_type,
+        pass_count,
+        fail_count,
+        skip_count,
+        identifier,
+        name,
+        text
+    )
+SELECT $1,
+    *
+FROM unnest(
+        $2::stat_type [],
+        $3::INTEGER [],
+        $4::INTEGER [],
+        $5::INTEGER [],
+        $6::TEXT [],
+        $7::TEXT [],
+        $8::TEXT []
+    );
\ No newline at end of file
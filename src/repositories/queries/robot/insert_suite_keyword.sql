@@ -1,2 +0,0 @@
-INSERT INTO suite_keywords (suite_id, type, value)
-VALUES ($1, $2, $3);
\ No newline at end of file
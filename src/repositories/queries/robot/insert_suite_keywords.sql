@@ -0,0 +1,3 @@
+INSERT INTO suite_keywords (suite_id, type, value)
+SELECT *
+FROM unnest($1::INTEGER [], $2::TEXT [], $3::JSONB []);
\ No newline at end of file
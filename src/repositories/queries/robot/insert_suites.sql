@@ -0,0 +1,51 @@
+INSERT INTO suites (
+        id,
+        test_run_id,
+        name,
+        source,
+        status,
+        start_time,
+        end_time,
+        identifier,
+        parent_suite_id,
+        doc,
+        message,
+        full_name
+    )
+SELECT suite.id,
+    $1,
+    suite.name,
+    suite.source,
+    suite.status,
+    suite.start_time,
+    suite.end_time,
+    suite.identifier,
+    suite.parent_suite_id,
+    suite.doc,
+    suite.message,
+    suite.full_name
+FROM unnest(
+        $2::INTEGER [],
+        $3::TEXT [],
+        $4::TEXT [],
+        $5::TEXT [],
+        $6::TIMESTAMP [],
+        $7::TIMESTAMP [],
+        $8::TEXT [],
+        $9::INTEGER [],
+        $10::TEXT [],
+        $11::TEXT [],
+        $12::TEXT []
+    ) AS suite(
+        id,
+        name,
+        source,
+        status,
+        start_time,
+        end_time,
+        identifier,
+        parent_suite_id,
+        doc,
+        message,
+        full_name
+    );
\ No newline at end of file
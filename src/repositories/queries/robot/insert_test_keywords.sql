@@ -1,2 +1,3 @@
-INSERT INTO test_keywords (test_id, value)
-VALUES ($1, $2);
\ No newline at end of file
+INSERT INTO test_keywords (test_id, type, value)
+SELECT *
+FROM unnest($1::INTEGER [], $2::TEXT [], $3::JSONB []);
\ No newline at end of file
@@ -1,11 +1,36 @@
 INSERT INTO test_runs (
+        id,
         project_id,
         rpa,
         generator,
         generated_date,
         schema_version,
         application_version,
-        sha1
+        sha1,
+        source_format,
+        environment,
+        ci_system,
+        build_url,
+        build_number,
+        robot_git_hash,
+        app_git_hash,
+        branch
     )
-VALUES ($1, $2, $3, $4, $5, $6, $7)
-RETURNING id;
\ No newline at end of file
+VALUES (
+        $1,
+        $2,
+        $3,
+        $4,
+        $5,
+        $6,
+        $7,
+        $8,
+        $9,
+        $10,
+        $11,
+        $12,
+        $13,
+        $14,
+        $15,
+        $16
+    );
\ No newline at end of file
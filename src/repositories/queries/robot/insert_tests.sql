@@ -0,0 +1,29 @@
+INSERT INTO tests (
+        id,
+        suite_id,
+        identifier,
+        name,
+        status,
+        start_time,
+        end_time,
+        line,
+        doc,
+        timeout,
+        attempt,
+        message
+    )
+SELECT *
+FROM unnest(
+        $1::INTEGER [],
+        $2::INTEGER [],
+        $3::TEXT [],
+        $4::TEXT [],
+        $5::TEXT [],
+        $6::TIMESTAMP [],
+        $7::TIMESTAMP [],
+        $8::INTEGER [],
+        $9::TEXT [],
+        $10::TEXT [],
+        $11::INTEGER [],
+        $12::TEXT []
+    );
\ No newline at end of file
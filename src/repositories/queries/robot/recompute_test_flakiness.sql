@@ -0,0 +1,113 @@
+WITH targets AS (
+    SELECT DISTINCT tr.project_id,
+        s.full_name AS suite,
+        t.name
+    FROM tests t
+        JOIN suites s ON s.id = t.suite_id
+        JOIN test_runs tr ON tr.id = s.test_run_id
+    WHERE tr.id = $1
+        AND t.superseded_by IS NULL
+        AND (
+            NOT $2::BOOLEAN
+            OR t.status IN ('PASS', 'FAIL')
+            AND NOT EXISTS (
+                SELECT 1
+                FROM test_flakiness f
+                WHERE f.project_id = tr.project_id
+                    AND f.suite = s.full_name
+                    AND f.name = t.name
+                    AND f.last_test_run_id = tr.id
+            )
+        )
+),
+results AS (
+    SELECT DISTINCT ON (tr.id, s.full_name, t.name) tr.project_id,
+        s.full_name AS suite,
+        t.name,
+        tr.id AS test_run_id,
+        tr.generated_date,
+        tr.application_version,
+        t.status
+    FROM targets
+        JOIN suites s ON s.full_name = targets.suite
+        JOIN test_runs tr ON tr.id = s.test_run_id
+        AND tr.project_id = targets.project_id
+        JOIN tests t ON t.suite_id = s.id
+        AND t.name = targets.name
+    WHERE t.superseded_by IS NULL
+        AND t.status IN ('PASS', 'FAIL')
+    ORDER BY tr.id,
+        s.full_name,
+        t.name,
+        t.id DESC
+),
+sequenced AS (
+    SELECT results.*,
+        LAG(status) OVER run_order AS previous_status,
+        LAG(application_version) OVER run_order AS previous_app_version,
+        ROW_NUMBER() OVER (
+            PARTITION BY project_id,
+            suite,
+            name
+            ORDER BY generated_date DESC,
+                test_run_id DESC
+        ) AS recency
+    FROM results
+    WINDOW run_order AS (
+            PARTITION BY project_id,
+            suite,
+            name
+            ORDER BY generated_date,
+                test_run_id
+        )
+)
+INSERT INTO test_flakiness (
+        project_id,
+        suite,
+        name,
+        run_count,
+        transitions,
+        flips,
+        last_test_run_id,
+        last_run_date,
+        last_app_version,
+        last_status
+    )
+SELECT project_id,
+    suite,
+    name,
+    COUNT(*),
+    COUNT(*) FILTER (
+        WHERE previous_app_version = application_version
+    ),
+    COUNT(*) FILTER (
+        WHERE previous_app_version = application_version
+            AND previous_status <> status
+    ),
+    MAX(test_run_id) FILTER (
+        WHERE recency = 1
+    ),
+    MAX(generated_date) FILTER (
+        WHERE recency = 1
+    ),
+    MAX(application_version) FILTER (
+        WHERE recency = 1
+    ),
+    MAX(status) FILTER (
+        WHERE recency = 1
+    )
+FROM sequenced
+GROUP BY project_id,
+    suite,
+    name
+ORDER BY project_id,
+    suite,
+    name ON CONFLICT (project_id, suite, name) DO
+UPDATE
+SET run_count = EXCLUDED.run_count,
+    transitions = EXCLUDED.transitions,
+    flips = EXCLUDED.flips,
+    last_test_run_id = EXCLUDED.last_test_run_id,
+    last_run_date = EXCLUDED.last_run_date,
+    last_app_version = EXCLUDED.last_app_version,
+    last_status = EXCLUDED.last_status;
\ No newline at end of file
@@ -0,0 +1,82 @@
+WITH run_suites AS (
+    SELECT id
+    FROM suites
+    WHERE test_run_id = $1
+),
+run_tests AS (
+    SELECT id
+    FROM tests
+    WHERE suite_id IN (
+            SELECT id
+            FROM run_suites
+        )
+),
+deleted_test_keywords AS (
+    DELETE FROM test_keywords
+    WHERE test_id IN (
+            SELECT id
+            FROM run_tests
+        )
+),
+deleted_test_tags AS (
+    DELETE FROM test_tags
+    WHERE test_id IN (
+            SELECT id
+            FROM run_tests
+        )
+),
+deleted_tests AS (
+    DELETE FROM tests
+    WHERE id IN (
+            SELECT id
+            FROM run_tests
+        )
+),
+deleted_suite_keywords AS (
+    DELETE FROM suite_keywords
+    WHERE suite_id IN (
+            SELECT id
+            FROM run_suites
+        )
+),
+deleted_suite_metadata AS (
+    DELETE FROM suite_metadata
+    WHERE suite_id IN (
+            SELECT id
+            FROM run_suites
+        )
+),
+deleted_suites AS (
+    DELETE FROM suites
+    WHERE test_run_id = $1
+),
+deleted_statistics AS (
+    DELETE FROM test_run_statistics
+    WHERE test_run_id = $1
+),
+deleted_errors AS (
+    DELETE FROM test_run_errors
+    WHERE test_run_id = $1
+),
+moved_suites AS (
+    UPDATE suites
+    SET test_run_id = $1
+    WHERE test_run_id = $2
+),
+moved_statistics AS (
+    UPDATE test_run_statistics
+    SET test_run_id = $1
+    WHERE test_run_id = $2
+),
+moved_errors AS (
+    UPDATE test_run_errors
+    SET test_run_id = $1
+    WHERE test_run_id = $2
+)
+UPDATE test_runs
+SET rpa = $3,
+    generator = $4,
+    generated_date = $5,
+    schema_version = $6,
+    source_format = $7
+WHERE id = $1;
\ No newline at end of file
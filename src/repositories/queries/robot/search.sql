@@ -0,0 +1,197 @@
+WITH RECURSIVE search AS (
+    SELECT websearch_to_tsquery('simple', $1) AS query
+),
+runs AS (
+    SELECT tr.id,
+        tr.project_id,
+        tr.generated_date
+    FROM test_runs tr
+    WHERE (
+            $2::INTEGER IS NULL
+            OR tr.project_id = $2
+        )
+        AND (
+            $3::INTEGER IS NULL
+            OR tr.id = $3
+        )
+        AND (
+            $4::TIMESTAMP IS NULL
+            OR tr.generated_date >= $4
+        )
+        AND (
+            $5::TIMESTAMP IS NULL
+            OR tr.generated_date <= $5
+        )
+),
+found_tests AS (
+    SELECT t.id AS test_id,
+        t.suite_id,
+        NULL::TEXT AS keyword_type,
+        NULL::TEXT [] AS keyword_path,
+        NULL::TEXT AS keyword_name,
+        concat_ws(' ', t.name, t.doc, t.message) AS text
+    FROM tests t
+        JOIN suites s ON s.id = t.suite_id
+        JOIN runs r ON r.id = s.test_run_id,
+        search
+    WHERE to_tsvector(
+            'simple',
+            t.name || ' ' || COALESCE(t.doc, '') || ' ' || COALESCE(t.message, '')
+        ) @@ search.query
+        AND t.superseded_by IS NULL
+    ORDER BY r.generated_date DESC,
+        r.id DESC,
+        s.full_name,
+        t.name
+    LIMIT $6
+),
+trees AS (
+    -- Only the first trees, in the order of the matches, are walked: each
+    -- gives at least one match unless its words are in different keywords
+    SELECT k.test_id,
+        t.suite_id,
+        k.type,
+        k.value,
+        r.generated_date,
+        r.id AS test_run_id,
+        s.full_name,
+        t.name AS test_name
+    FROM test_keywords k
+        JOIN tests t ON t.id = k.test_id
+        JOIN suites s ON s.id = t.suite_id
+        JOIN runs r ON r.id = s.test_run_id,
+        search
+    WHERE k.search_vector @@ search.query
+        AND t.superseded_by IS NULL
+    UNION ALL
+    SELECT NULL,
+        k.suite_id,
+        k.type,
+        k.value,
+        r.generated_date,
+        r.id,
+        s.full_name,
+        NULL
+    FROM suite_keywords k
+        JOIN suites s ON s.id = k.suite_id
+        JOIN runs r ON r.id = s.test_run_id,
+        search
+    WHERE k.search_vector @@ search.query
+    ORDER BY generated_date DESC,
+        test_run_id DESC,
+        full_name,
+        test_name NULLS FIRST,
+        type
+    LIMIT $6
+),
+nodes AS (
+    SELECT tree.test_id,
+        tree.suite_id,
+        tree.type,
+        ARRAY []::TEXT [] AS path,
+        tree.value
+    FROM trees tree
+    UNION ALL
+    SELECT n.test_id,
+        n.suite_id,
+        n.type,
+        n.path || child.key,
+        child.value
+    FROM nodes n
+        CROSS JOIN LATERAL (
+            SELECT entry.key,
+                entry.value
+            FROM jsonb_each(
+                    CASE
+                        WHEN jsonb_typeof(n.value) = 'object' THEN n.value
+                    END
+                ) entry
+            UNION ALL
+            SELECT (element.index - 1)::TEXT,
+                element.value
+            FROM jsonb_array_elements(
+                    CASE
+                        WHEN jsonb_typeof(n.value) = 'array' THEN n.value
+                    END
+                ) WITH ORDINALITY AS element(value, index)
+        ) child
+    WHERE jsonb_typeof(child.value) IN ('object', 'array')
+        AND child.key NOT IN ('msg', 'status')
+),
+found_keywords AS (
+    SELECT n.test_id,
+        n.suite_id,
+        n.type AS keyword_type,
+        n.path AS keyword_path,
+        n.value->>'name' AS keyword_name,
+        -- As much as the index of the trees keeps, see keywords_search_vector
+        left(
+            CASE
+                WHEN n.value ? 'args' THEN concat_ws(
+                    ' ',
+                    n.value->>'name',
+                    (
+                        SELECT string_agg(arg, ' ')
+                        FROM jsonb_array_elements_text(n.value->'args') arg
+                    ),
+                    (
+                        SELECT string_agg(message->>'value', ' ')
+                        FROM jsonb_array_elements(n.value->'msg') message
+                    )
+                )
+                ELSE n.value->>'value'
+            END,
+            200000
+        ) AS text
+    FROM nodes n
+    WHERE (
+            n.value ? 'name'
+            AND jsonb_typeof(n.value->'args') = 'array'
+            AND jsonb_typeof(n.value->'msg') = 'array'
+        )
+        OR (
+            n.path [cardinality(n.path)] = 'message'
+            AND jsonb_typeof(n.value->'value') = 'string'
+        )
+),
+found AS (
+    SELECT *
+    FROM found_tests
+    UNION ALL
+    SELECT k.*
+    FROM found_keywords k,
+        search
+    WHERE to_tsvector('simple', k.text) @@ search.query
+)
+SELECT r.project_id,
+    r.id AS test_run_id,
+    r.generated_date AS test_run_date,
+    s.id AS suite_id,
+    s.identifier AS suite_identifier,
+    s.full_name AS suite,
+    t.id AS "test_id?",
+    t.identifier AS "test_identifier?",
+    t.name AS "test_name?",
+    f.keyword_type,
+    f.keyword_path,
+    f.keyword_name,
+    btrim(
+        ts_headline(
+            'simple',
+            f.text,
+            search.query,
+            'StartSel="", StopSel=""'
+        )
+    ) AS "excerpt!"
+FROM found f
+    JOIN suites s ON s.id = f.suite_id
+    JOIN runs r ON r.id = s.test_run_id
+    LEFT JOIN tests t ON t.id = f.test_id,
+    search
+ORDER BY r.generated_date DESC,
+    r.id DESC,
+    s.full_name,
+    t.name NULLS FIRST,
+    f.keyword_type NULLS FIRST,
+    f.keyword_path NULLS FIRST
+LIMIT $6;
\ No newline at end of file
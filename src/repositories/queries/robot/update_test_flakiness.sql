@@ -0,0 +1,48 @@
+INSERT INTO test_flakiness (
+        project_id,
+        suite,
+        name,
+        run_count,
+        transitions,
+        flips,
+        last_test_run_id,
+        last_run_date,
+        last_app_version,
+        last_status
+    )
+SELECT DISTINCT ON (s.full_name, t.name) tr.project_id,
+    s.full_name,
+    t.name,
+    1,
+    0,
+    0,
+    tr.id,
+    tr.generated_date,
+    tr.application_version,
+    t.status
+FROM tests t
+    JOIN suites s ON s.id = t.suite_id
+    JOIN test_runs tr ON tr.id = s.test_run_id
+WHERE tr.id = $1
+    AND t.superseded_by IS NULL
+    AND t.status IN ('PASS', 'FAIL')
+ORDER BY s.full_name,
+    t.name,
+    t.id DESC ON CONFLICT (project_id, suite, name) DO
+UPDATE
+SET run_count = test_flakiness.run_count + 1,
+    transitions = test_flakiness.transitions + (
+        test_flakiness.last_app_version = EXCLUDED.last_app_version
+    )::INTEGER,
+    flips = test_flakiness.flips + (
+        test_flakiness.last_app_version = EXCLUDED.last_app_version
+        AND test_flakiness.last_status <> EXCLUDED.last_status
+    )::INTEGER,
+    last_test_run_id = EXCLUDED.last_test_run_id,
+    last_run_date = EXCLUDED.last_run_date,
+    last_app_version = EXCLUDED.last_app_version,
+    last_status = EXCLUDED.last_status
+WHERE (
+        test_flakiness.last_run_date,
+        test_flakiness.last_test_run_id
+    ) < (EXCLUDED.last_run_date, EXCLUDED.last_test_run_id);
\ No newline at end of file
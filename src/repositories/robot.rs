@@ -1,26 +1,69 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, io, sync::Arc};
+
+use futures_util::{stream, StreamExt};
 
 use crate::{
-    models::{self, robot::{db::{ErrorDB, ProjectTestSummaryDB, RawJsonRecord, StatisticDB, SuiteDB, SuiteKeywordRecord, TestDB}, domain::{ProjectTestRunSummary, SavedTestRun, SuiteKeywords, TestRunError, TestRunStatistic, TestRunSuite, TestRunTest}}, robot_legacy::{ErrorDBLegacy, StatDBLegacy, SuiteDBLegacy, TestDBLegacy, TestRunDBLegacy}},
-    services::parser::{self}};
-use serde_json::Value;
-use sqlx::{query_as, query_file, query_file_as, query_scalar, PgPool};
-use crate::models::robot::db::StatisticTypeDB;
+    models::{self, robot::{db::{ArtifactContentDB, ArtifactDB, ErrorDB, FailureGroupDB, FlakyTestDB, OutputFileDB, ProjectFailureGroupDB, ProjectTestSummaryDB, SearchMatchDB, StatisticDB, SuiteDB, SuiteNodeDB, KeywordRecord, RerunTargetDB, TestAttemptDB, TestComparisonDB, TestDB, TestHistoryEntryDB, TestRunPartDB}, domain::{ArtifactContent, CiMetadata, ContentChunks, FailureGroup, FlakyTest, OutputFile, ProjectFailureGroup, ProjectTestRunSummary, SavedTestRun, SearchMatch, SearchRequest, SuiteKeywords, SuiteNode, SuiteTestsRequest, TestAttempt, TestComparison, TestHistoryEntry, TestHistoryRequest, TestIdentity, TestKeywords, TestRunArtifact, TestRunError, PageCursor, TestRunFilter, TestRunPageRequest, TestRunStatistic, TestRunPart, TestRunSuite, TestRunTest}}, robot_legacy::{ErrorDBLegacy, StatDBLegacy, StatTypeDB, SuiteDBLegacy, TestDBLegacy, TestRunDBLegacy}},
+    services::robot::TestRunMetadata};
+use sqlx::{query, query_as, query_file, query_file_as, query_file_scalar, query_scalar, PgConnection, PgPool, Postgres, Transaction};
+use crate::models::robot::db::{SourceFormatDB, StatisticTypeDB};
 
-enum SuiteKeywordType {
+enum KeywordType {
     Setup,
+    Body,
     Teardown,
 }
 
-impl SuiteKeywordType {
+impl KeywordType {
     fn as_str(&self) -> &'static str {
         match self {
-            SuiteKeywordType::Setup => "setup",
-            SuiteKeywordType::Teardown => "teardown",
+            KeywordType::Setup => "setup",
+            KeywordType::Body => "body",
+            KeywordType::Teardown => "teardown",
         }
     }
 }
 
+/// A suite to write, with the ids reserved for it and its parent.
+pub struct SuiteRow {
+    pub id: i32,
+    pub parent_suite_id: Option<i32>,
+    /// Path from the root suite of its output file, e.g. `Tests.Login`.
+    pub full_name: String,
+    pub suite: SuiteDBLegacy,
+}
+
+/// A test to write, with the id reserved for it.
+pub struct TestRow {
+    pub id: i32,
+    pub suite_id: i32,
+    pub test: TestDBLegacy,
+}
+
+/// Keyword rows as the columns of a multi-row insert.
+#[derive(Default)]
+struct KeywordRows {
+    ids: Vec<i32>,
+    types: Vec<String>,
+    values: Vec<serde_json::Value>,
+}
+
+impl KeywordRows {
+    fn push(&mut self, id: i32, keyword_type: KeywordType, keyword: &impl serde::Serialize) -> Result<(), sqlx::Error> {
+        let json_keyword = match serde_json::to_value(keyword) {
+            Ok(json) => json,
+            Err(e) => {
+                tracing::error!("Failed to serialize keyword: {:?}", e);
+                return Err(sqlx::Error::Protocol("Failed to serialize keyword".into()));
+            }
+        };
+        self.ids.push(id);
+        self.types.push(keyword_type.as_str().to_string());
+        self.values.push(json_keyword);
+        Ok(())
+    }
+}
+
 pub struct RobotRepository {
     pool: PgPool,
 }
@@ -30,6 +73,15 @@ impl RobotRepository {
         Self { pool }
     }
 
+    /// Imports write through the connection of a transaction, so that a failed
+    /// import leaves nothing behind.
+    pub async fn begin(&self) -> Result<Transaction<'static, Postgres>, sqlx::Error> {
+        self.pool
+            .begin()
+            .await
+            .inspect_err(|e| tracing::error!("Failed to begin transaction: {:?}", e))
+    }
+
     pub async fn get_latest_test_runs_summaries_for_projects(&self, project_ids: &Vec<i32>) -> Result<Vec<ProjectTestRunSummary>, sqlx::Error> {
         query_file_as!(
             ProjectTestSummaryDB,
@@ -47,11 +99,28 @@ impl RobotRepository {
         })
     }
 
-    pub async fn get_test_runs_summaries_by_project_id(&self, project_id: i32) -> Result<Vec<ProjectTestRunSummary>, sqlx::Error> {
+    /// Metadata filters match the root suite of the runs.
+    pub async fn get_test_runs_summaries_by_project_id(&self, project_id: i32, filter: &TestRunFilter) -> Result<Vec<ProjectTestRunSummary>, sqlx::Error> {
+        let (names, values): (Vec<String>, Vec<String>) = filter.metadata.iter().cloned().unzip();
+        let (label_names, label_values): (Vec<String>, Vec<String>) = filter.labels.iter().cloned().unzip();
         query_file_as!(
             ProjectTestSummaryDB,
             "./src/repositories/queries/robot/get_test_runs_summaries_by_project_id.sql",
-            project_id
+            project_id,
+            &names,
+            &values,
+            filter.environment,
+            filter.ci_system,
+            filter.build_number,
+            filter.robot_git_hash,
+            filter.app_git_hash,
+            &label_names,
+            &label_values,
+            filter.branch,
+            filter.from,
+            filter.to,
+            filter.app_version,
+            filter.has_failures
         )
         .fetch_all(&self.pool)
         .await
@@ -64,6 +133,76 @@ impl RobotRepository {
         })
     }
 
+    /// Gives the cursor of the next page when there is one.
+    pub async fn get_test_runs_page_by_project_id(&self, project_id: i32, filter: &TestRunFilter, page: &TestRunPageRequest) -> Result<(Vec<ProjectTestRunSummary>, Option<PageCursor>), sqlx::Error> {
+        let (names, values): (Vec<String>, Vec<String>) = filter.metadata.iter().cloned().unzip();
+        let (label_names, label_values): (Vec<String>, Vec<String>) = filter.labels.iter().cloned().unzip();
+        // One more run tells whether there is a next page.
+        let mut rows = query_file_as!(
+            ProjectTestSummaryDB,
+            "./src/repositories/queries/robot/get_test_runs_page_by_project_id.sql",
+            project_id,
+            &names,
+            &values,
+            filter.environment,
+            filter.ci_system,
+            filter.build_number,
+            filter.robot_git_hash,
+            filter.app_git_hash,
+            &label_names,
+            &label_values,
+            filter.branch,
+            filter.from,
+            filter.to,
+            filter.app_version,
+            filter.has_failures,
+            page.sort.as_str(),
+            page.descending,
+            page.cursor.map(|cursor| cursor.sort_key),
+            page.cursor.map(|cursor| cursor.id),
+            page.limit + 1
+        )
+        .fetch_all(&self.pool)
+        .await
+        .inspect_err(|e| tracing::error!("Query get_test_runs_page_by_project_id failed: {:?}", e))?;
+
+        let mut next_cursor = None;
+        if rows.len() as i64 > page.limit {
+            rows.truncate(page.limit as usize);
+            next_cursor = rows.last().and_then(|row| {
+                row.sort_key.map(|sort_key| PageCursor { sort_key, id: row.test_run_id })
+            });
+        }
+        let summaries = rows.into_iter().map(|row| row.into_summary()).collect();
+        Ok((summaries, next_cursor))
+    }
+
+    pub async fn count_test_runs_by_project_id(&self, project_id: i32, filter: &TestRunFilter) -> Result<i64, sqlx::Error> {
+        let (names, values): (Vec<String>, Vec<String>) = filter.metadata.iter().cloned().unzip();
+        let (label_names, label_values): (Vec<String>, Vec<String>) = filter.labels.iter().cloned().unzip();
+        query_file_scalar!(
+            "./src/repositories/queries/robot/count_test_runs_by_project_id.sql",
+            project_id,
+            &names,
+            &values,
+            filter.environment,
+            filter.ci_system,
+            filter.build_number,
+            filter.robot_git_hash,
+            filter.app_git_hash,
+            &label_names,
+            &label_values,
+            filter.branch,
+            filter.from,
+            filter.to,
+            filter.app_version,
+            filter.has_failures
+        )
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(|e| tracing::error!("Query count_test_runs_by_project_id failed: {:?}", e))
+    }
+
     pub async fn get_test_run_by_id(
         &self,
         id: i32,
@@ -77,8 +216,18 @@ impl RobotRepository {
                 tr.generator,
                 tr.generated_date,
                 tr.schema_version,
+                tr.source_format AS "source_format: SourceFormatDB",
                 tr.application_version,
-                tr.imported_date
+                tr.imported_date,
+                tr.environment,
+                tr.branch,
+                tr.ci_system,
+                tr.build_url,
+                tr.build_number,
+                tr.robot_git_hash,
+                tr.app_git_hash,
+                ARRAY(SELECT l.name FROM test_run_labels l WHERE l.test_run_id = tr.id ORDER BY l.name) AS "label_names!",
+                ARRAY(SELECT l.value FROM test_run_labels l WHERE l.test_run_id = tr.id ORDER BY l.name) AS "label_values!"
             FROM test_runs tr
             WHERE tr.id = $1
             "#,
@@ -90,10 +239,13 @@ impl RobotRepository {
         
         let test_run = match result {
             Some(test_run_db) => {
-                let suites = self.get_suites_by_test_run_id_and_parent_suite_id(id, None).await?;
+                let mut conn = self.pool.acquire().await?;
+                let suites = self.get_suites_by_test_run_id(&mut conn, id).await?;
                 let statistics = self.get_test_run_statistics_by_test_run_id(id).await?;
                 let errors = self.get_test_run_errors_by_test_run_id(id).await?;
-                Some(test_run_db.to_test_run(suites, statistics, errors))
+                let parts = self.get_test_run_parts_by_test_run_id(id).await?;
+                let artifacts = self.get_test_run_artifacts_by_test_run_id(id).await?;
+                Some(test_run_db.to_test_run(suites, statistics, errors, parts, artifacts))
             }
             None => None,
         };
@@ -101,9 +253,27 @@ impl RobotRepository {
         Ok(test_run)
     }
 
+    pub async fn get_test_run_id_by_sha1(&self, sha1: &str) -> Result<Option<i32>, sqlx::Error> {
+        query_scalar!("SELECT id FROM test_runs WHERE sha1 = $1", sha1)
+            .fetch_optional(&self.pool)
+            .await
+            .inspect_err(|e| tracing::error!("Query get_test_run_id_by_sha1 failed: {:?}", e))
+    }
+
+    pub async fn is_test_run_existing(&self, test_run_id: i32) -> Result<bool, sqlx::Error> {
+        let is_existing: Option<bool> = query_scalar!("SELECT EXISTS(SELECT 1 FROM test_runs WHERE id = $1)", test_run_id)
+            .fetch_one(&self.pool)
+            .await
+            .inspect_err(|e| tracing::error!("Query is_test_run_existing failed: {:?}", e))?;
+
+        Ok(is_existing.unwrap_or(false))
+    }
+
+    /// Looks for the SHA-1 among imported runs and the parts of merged runs.
     pub async fn is_sha1_already_inserted(&self, sha1: &str) -> Result<bool, sqlx::Error> {
         let is_inserted: Option<bool> = query_scalar!(
-            "SELECT EXISTS(SELECT 1 FROM test_runs WHERE sha1 = $1)",
+            r#"SELECT EXISTS(SELECT 1 FROM test_runs WHERE sha1 = $1)
+                OR EXISTS(SELECT 1 FROM test_run_parts WHERE sha1 = $1)"#,
             sha1
         )
         .fetch_one(&self.pool)
@@ -113,36 +283,149 @@ impl RobotRepository {
         Ok(is_inserted.unwrap_or(true))
     }
 
-    pub async fn insert_test_run(&self, test_run: &TestRunDBLegacy, project_id: i32) -> Result<i32, sqlx::Error> {
-        let result = query_file!(
+    pub async fn get_test_run_metadata(
+        &self,
+        test_run_id: i32,
+    ) -> Result<Option<TestRunMetadata>, sqlx::Error> {
+        query!(
+            r#"--sql
+            SELECT p.name AS app_name,
+                tr.application_version AS app_version,
+                tr.environment,
+                tr.branch,
+                tr.ci_system,
+                tr.build_url,
+                tr.build_number,
+                tr.robot_git_hash,
+                tr.app_git_hash,
+                ARRAY(SELECT l.name FROM test_run_labels l WHERE l.test_run_id = tr.id ORDER BY l.name) AS "label_names!",
+                ARRAY(SELECT l.value FROM test_run_labels l WHERE l.test_run_id = tr.id ORDER BY l.name) AS "label_values!"
+            FROM test_runs tr
+            JOIN projects p ON p.id = tr.project_id
+            WHERE tr.id = $1
+            "#,
+            test_run_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .inspect_err(|e| tracing::error!("Query get_test_run_metadata failed: {:?}", e))
+        .map(|metadata| {
+            metadata.map(|metadata| TestRunMetadata {
+                app_name: metadata.app_name,
+                app_version: metadata.app_version,
+                environment: metadata.environment,
+                branch: metadata.branch,
+                ci: CiMetadata {
+                    system: metadata.ci_system,
+                    build_url: metadata.build_url,
+                    build_number: metadata.build_number,
+                    robot_git_hash: metadata.robot_git_hash,
+                    app_git_hash: metadata.app_git_hash,
+                },
+                labels: metadata.label_names.into_iter().zip(metadata.label_values).collect(),
+            })
+        })
+    }
+
+    /// Swaps the suites, tests, statistics and errors of the test run for those
+    /// written under `new_test_run_id`, in a single statement.
+    pub async fn replace_test_run_tree(
+        &self,
+        conn: &mut PgConnection,
+        test_run_id: i32,
+        new_test_run_id: i32,
+        test_run: &TestRunDBLegacy,
+    ) -> Result<(), sqlx::Error> {
+        query_file!(
+            "./src/repositories/queries/robot/replace_test_run_tree.sql",
+            test_run_id,
+            new_test_run_id,
+            test_run.rpa,
+            test_run.generator,
+            test_run.generated_date,
+            test_run.schema_version,
+            test_run.source_format.clone() as SourceFormatDB
+        )
+        .execute(&mut *conn)
+        .await
+        .inspect_err(|e| tracing::error!("Query replace_test_run_tree failed: {:?}", e))?;
+
+        Ok(())
+    }
+
+    pub async fn reserve_test_run_id(&self) -> Result<i32, sqlx::Error> {
+        query_scalar!(r#"SELECT nextval(pg_get_serial_sequence('test_runs', 'id'))::INTEGER as "id!""#)
+            .fetch_one(&self.pool)
+            .await
+            .inspect_err(|e| tracing::error!("Query reserve_test_run_id failed: {:?}", e))
+    }
+
+    /// Ids for suites written later in batches, referenced by their children
+    /// and tests before that.
+    pub async fn reserve_suite_ids(&self, count: i32) -> Result<Vec<i32>, sqlx::Error> {
+        query_scalar!(r#"SELECT nextval(pg_get_serial_sequence('suites', 'id'))::INTEGER as "id!" FROM generate_series(1, $1)"#, count)
+            .fetch_all(&self.pool)
+            .await
+            .inspect_err(|e| tracing::error!("Query reserve_suite_ids failed: {:?}", e))
+    }
+
+    pub async fn reserve_test_ids(&self, count: i32) -> Result<Vec<i32>, sqlx::Error> {
+        query_scalar!(r#"SELECT nextval(pg_get_serial_sequence('tests', 'id'))::INTEGER as "id!" FROM generate_series(1, $1)"#, count)
+            .fetch_all(&self.pool)
+            .await
+            .inspect_err(|e| tracing::error!("Query reserve_test_ids failed: {:?}", e))
+    }
+
+    pub async fn insert_test_run(&self, conn: &mut PgConnection, test_run_id: i32, test_run: &TestRunDBLegacy, project_id: i32) -> Result<(), sqlx::Error> {
+        query_file!(
             "./src/repositories/queries/robot/insert_test_run.sql",
+            test_run_id,
             project_id,
             test_run.rpa,
             test_run.generator,
             test_run.generated_date,
             test_run.schema_version,
             test_run.app_version,
-            test_run.sha1
+            test_run.sha1,
+            test_run.source_format.clone() as SourceFormatDB,
+            test_run.environment,
+            test_run.ci.system,
+            test_run.ci.build_url,
+            test_run.ci.build_number,
+            test_run.ci.robot_git_hash,
+            test_run.ci.app_git_hash,
+            test_run.branch
         )
-        .fetch_one(&self.pool)
+        .execute(&mut *conn)
         .await
         .inspect_err(|e| tracing::error!("Query insert_test_run failed: {:?}", e))?;
 
-        let test_run_id = result.id;
-
-        self.insert_suites( test_run_id, None, &test_run.suites).await?;
-        self.insert_statistics( test_run_id, &test_run.statistics).await?;
-        self.insert_errors( test_run_id, &test_run.errors).await?;
+        if !test_run.labels.is_empty() {
+            let (names, values): (Vec<String>, Vec<String>) = test_run.labels.iter().cloned().unzip();
+            query!(
+                r#"--sql
+                INSERT INTO test_run_labels (test_run_id, name, value)
+                SELECT $1, *
+                FROM unnest($2::TEXT[], $3::TEXT[])
+                "#,
+                test_run_id,
+                &names,
+                &values
+            )
+            .execute(&mut *conn)
+            .await
+            .inspect_err(|e| tracing::error!("Query insert_test_run_labels failed: {:?}", e))?;
+        }
 
-        Ok(test_run_id)
+        Ok(())
     }
-    
+
     pub async fn get_suite_keywords_by_suite_id(
         &self,
         suite_id: i32
     ) -> Result<Option<SuiteKeywords>, sqlx::Error> {
         let keywords = query_as!(
-            SuiteKeywordRecord,
+            KeywordRecord,
             r#"--sql
             SELECT type as keyword_type,
                   value
@@ -169,125 +452,414 @@ impl RobotRepository {
         }))
     }
 
+    pub async fn get_test_identity_by_test_id(&self, test_id: i32) -> Result<Option<TestIdentity>, sqlx::Error> {
+        query!(
+            r#"--sql
+            SELECT tr.project_id,
+                s.full_name,
+                t.name
+            FROM tests t
+            JOIN suites s ON s.id = t.suite_id
+            JOIN test_runs tr ON tr.id = s.test_run_id
+            WHERE t.id = $1
+            "#,
+            test_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .inspect_err(|e| tracing::error!("Query get_test_identity_by_test_id failed: {:?}", e))
+        .map(|test| {
+            test.map(|test| TestIdentity {
+                project_id: test.project_id,
+                suite: test.full_name,
+                name: test.name,
+            })
+        })
+    }
+
+    /// Gives the cursor of the next page when there is one.
+    pub async fn get_test_history(&self, test: &TestIdentity, request: &TestHistoryRequest) -> Result<(Vec<TestHistoryEntry>, Option<PageCursor>), sqlx::Error> {
+        // One more entry tells whether there is a next page.
+        let mut rows = query_file_as!(
+            TestHistoryEntryDB,
+            "./src/repositories/queries/robot/get_test_history.sql",
+            test.project_id,
+            test.suite,
+            test.name,
+            request.status,
+            request.cursor.map(|cursor| cursor.sort_key),
+            request.cursor.map(|cursor| cursor.id),
+            request.limit + 1
+        )
+        .fetch_all(&self.pool)
+        .await
+        .inspect_err(|e| tracing::error!("Query get_test_history failed: {:?}", e))?;
+
+        let mut next_cursor = None;
+        if rows.len() as i64 > request.limit {
+            rows.truncate(request.limit as usize);
+            next_cursor = rows.last().map(|row| PageCursor { sort_key: row.sort_key, id: row.test_id });
+        }
+        let entries = rows.into_iter().map(|row| row.into_entry()).collect();
+        Ok((entries, next_cursor))
+    }
+
+    /// The last attempts of the tests of both runs, matched by the path of
+    /// their suite and their name, ordered by them.
+    pub async fn get_test_run_comparison(&self, base_test_run_id: i32, target_test_run_id: i32) -> Result<Vec<TestComparison>, sqlx::Error> {
+        query_file_as!(
+            TestComparisonDB,
+            "./src/repositories/queries/robot/get_test_run_comparison.sql",
+            base_test_run_id,
+            target_test_run_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .inspect_err(|e| tracing::error!("Query get_test_run_comparison failed: {:?}", e))
+        .map(|tests| tests.into_iter().map(|test| test.into_comparison()).collect())
+    }
+
+    /// The failed tests of a run grouped by signature, most failures first.
+    pub async fn get_failure_groups_by_test_run_id(&self, test_run_id: i32) -> Result<Vec<FailureGroup>, sqlx::Error> {
+        query_file_as!(
+            FailureGroupDB,
+            "./src/repositories/queries/robot/get_failure_groups_by_test_run_id.sql",
+            test_run_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .inspect_err(|e| tracing::error!("Query get_failure_groups_by_test_run_id failed: {:?}", e))
+        .map(|groups| groups.into_iter().map(|group| group.into_failure_group()).collect())
+    }
+
+    /// The failures of the runs of the filter grouped by signature, most
+    /// failures first.
+    pub async fn get_failure_groups_by_project_id(&self, project_id: i32, filter: &TestRunFilter, limit: i64) -> Result<Vec<ProjectFailureGroup>, sqlx::Error> {
+        let (names, values): (Vec<String>, Vec<String>) = filter.metadata.iter().cloned().unzip();
+        let (label_names, label_values): (Vec<String>, Vec<String>) = filter.labels.iter().cloned().unzip();
+        query_file_as!(
+            ProjectFailureGroupDB,
+            "./src/repositories/queries/robot/get_failure_groups_by_project_id.sql",
+            project_id,
+            &names,
+            &values,
+            filter.environment,
+            filter.ci_system,
+            filter.build_number,
+            filter.robot_git_hash,
+            filter.app_git_hash,
+            &label_names,
+            &label_values,
+            filter.branch,
+            filter.from,
+            filter.to,
+            filter.app_version,
+            filter.has_failures,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+        .inspect_err(|e| tracing::error!("Query get_failure_groups_by_project_id failed: {:?}", e))
+        .map(|groups| groups.into_iter().map(|group| group.into_failure_group()).collect())
+    }
+
+    /// Latest runs first. Only the keyword trees that match are walked to
+    /// find the keywords and messages that do. Of a large tree only the first
+    /// 200000 characters of its names, arguments and messages are searched.
+    pub async fn search(&self, request: &SearchRequest) -> Result<Vec<SearchMatch>, sqlx::Error> {
+        query_file_as!(
+            SearchMatchDB,
+            "./src/repositories/queries/robot/search.sql",
+            request.text,
+            request.project_id,
+            request.test_run_id,
+            request.from,
+            request.to,
+            request.limit
+        )
+        .fetch_all(&self.pool)
+        .await
+        .inspect_err(|e| tracing::error!("Query search failed: {:?}", e))
+        .map(|matches| matches.into_iter().map(|found| found.into_match()).collect())
+    }
+
+    /// Adds the results of a new run to the flakiness of its tests. Tests
+    /// whose last result is from a later run are computed again from their
+    /// history instead.
+    pub async fn update_test_flakiness(&self, conn: &mut PgConnection, test_run_id: i32) -> Result<(), sqlx::Error> {
+        query_file!("./src/repositories/queries/robot/update_test_flakiness.sql", test_run_id)
+            .execute(&mut *conn)
+            .await
+            .inspect_err(|e| tracing::error!("Query update_test_flakiness failed: {:?}", e))?;
+        query_file!("./src/repositories/queries/robot/recompute_test_flakiness.sql", test_run_id, true)
+            .execute(&mut *conn)
+            .await
+            .inspect_err(|e| tracing::error!("Query recompute_test_flakiness failed: {:?}", e))?;
+        Ok(())
+    }
+
+    /// Computes the flakiness of the tests of a run again from their history,
+    /// once its results changed.
+    pub async fn recompute_test_flakiness(&self, conn: &mut PgConnection, test_run_id: i32) -> Result<(), sqlx::Error> {
+        query_file!("./src/repositories/queries/robot/recompute_test_flakiness.sql", test_run_id, false)
+            .execute(&mut *conn)
+            .await
+            .inspect_err(|e| tracing::error!("Query recompute_test_flakiness failed: {:?}", e))?;
+        Ok(())
+    }
+
+    /// Flakiest first, tests that never flipped are left out.
+    pub async fn get_flaky_tests_by_project_id(&self, project_id: i32, limit: i64) -> Result<Vec<FlakyTest>, sqlx::Error> {
+        query_as!(
+            FlakyTestDB,
+            r#"--sql
+            SELECT suite,
+                name,
+                score AS "score!",
+                run_count,
+                transitions,
+                flips,
+                last_test_run_id,
+                last_run_date,
+                last_app_version,
+                last_status
+            FROM test_flakiness
+            WHERE project_id = $1
+                AND flips > 0
+            ORDER BY score DESC, flips DESC, suite, name
+            LIMIT $2
+            "#,
+            project_id,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+        .inspect_err(|e| tracing::error!("Query get_flaky_tests_by_project_id failed: {:?}", e))
+        .map(|tests| tests.into_iter().map(|test| test.into_flaky_test()).collect())
+    }
+
     pub async fn get_test_keywords_by_test_id(
         &self,
         test_id: i32
-    ) -> Result<Option<Value>, sqlx::Error> {
-        query_as!(
-            RawJsonRecord,
+    ) -> Result<Option<TestKeywords>, sqlx::Error> {
+        let keywords = query_as!(
+            KeywordRecord,
             r#"--sql
-                SELECT value
-                FROM test_keywords
-                WHERE test_id = $1
-                LIMIT 1;
+            SELECT type as keyword_type,
+                  value
+            FROM test_keywords
+            WHERE test_id = $1
             "#,
             test_id
         )
-        .fetch_optional(&self.pool)
+        .fetch_all(&self.pool)
         .await
-        .inspect_err(|e| tracing::error!("Query get_test_keywords_by_test_id failed: {:?}", e))
-        .map(|raw_json| raw_json.map(|r| r.value))
+        .inspect_err(|e| tracing::error!("Query get_test_keywords_by_test_id failed: {:?}", e))?;
+
+        if keywords.is_empty() {
+            return Ok(None);
+        }
+
+        let map = keywords
+            .into_iter()
+            .map(|k| (k.keyword_type, k.value))
+            .collect::<HashMap<_, _>>();
+
+        Ok(Some(TestKeywords {
+            keywords: Arc::new(map),
+        }))
     }
 
-    async fn get_suites_by_test_run_id_and_parent_suite_id(
+    /// The suite tree of a run with the last attempts of its tests, both in
+    /// execution order. Loaded with one query for the suites and one for the
+    /// tests, whatever the size of the tree.
+    pub async fn get_suites_by_test_run_id(
         &self,
+        conn: &mut PgConnection,
         test_run_id: i32,
-        parent_suite_id: Option<i32>,
     ) -> Result<Vec<TestRunSuite>, sqlx::Error> {
-        Box::pin(async move {
+        let suites = query_file_as!(
+            SuiteDB,
+            "./src/repositories/queries/robot/get_suites_by_test_run_id.sql",
+            test_run_id
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .inspect_err(|e| tracing::error!("Query get_suites_by_test_run_id failed: {:?}", e))?;
 
-        let mut query_builder = sqlx::QueryBuilder::new(
-            r#"
-            SELECT s.id,
-                   s.name,
-                   s.source,
-                   s.status,
-                   s.start_time,
-                   s.end_time,
-                   s.identifier,
-                   s.doc
-            FROM suites s
-            WHERE s.test_run_id = 
-            "#
-        );
-
-        query_builder.push_bind(test_run_id);
-
-        if let Some(parent_suite_id) = parent_suite_id {
-            query_builder.push(" AND s.parent_suite_id = ").push_bind(parent_suite_id);
-        } else {
-            query_builder.push(" AND s.parent_suite_id IS NULL");
+        let tests = query_file_as!(
+            TestDB,
+            "./src/repositories/queries/robot/get_tests_by_test_run_id.sql",
+            test_run_id
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .inspect_err(|e| tracing::error!("Query get_tests_by_test_run_id failed: {:?}", e))?;
+
+        let mut tests_by_suite: HashMap<i32, Vec<TestRunTest>> = HashMap::new();
+        for test in tests {
+            tests_by_suite.entry(test.suite_id).or_default().push(test.into_test());
+        }
+        let mut suites_by_parent: HashMap<Option<i32>, Vec<SuiteDB>> = HashMap::new();
+        for suite in suites {
+            suites_by_parent.entry(suite.parent_suite_id).or_default().push(suite);
         }
+        Ok(build_suite_tree(&mut suites_by_parent, &mut tests_by_suite, None))
+    }
 
-        query_builder.push(" ORDER BY s.start_time ASC");
-        
-        let suites: Vec<SuiteDB> = query_builder
-        .build_query_as()
+    /// The root suites of the run when `parent_suite_id` is `None`, else the
+    /// children of that suite, each with the counts of the tests under it.
+    pub async fn get_suite_nodes(&self, test_run_id: Option<i32>, parent_suite_id: Option<i32>) -> Result<Vec<SuiteNode>, sqlx::Error> {
+        query_file_as!(
+            SuiteNodeDB,
+            "./src/repositories/queries/robot/get_suite_nodes.sql",
+            test_run_id,
+            parent_suite_id
+        )
         .fetch_all(&self.pool)
         .await
-        .inspect_err(|e| tracing::error!("Query get_suites_by_test_run_id_and_parent_suite_id failed: {:?}", e))?;
+        .inspect_err(|e| tracing::error!("Query get_suite_nodes failed: {:?}", e))
+        .map(|rows| rows.into_iter().map(|row| row.into_node()).collect())
+    }
 
-        let mut suite_db_list = Vec::new();
-        for suite in suites {
-            // let (setup_keyword, teardown_keyword) = self.get_suite_keywords_by_suite_id( suite.id.unwrap()).await?;
-            let suites = self.get_suites_by_test_run_id_and_parent_suite_id( test_run_id, Some(suite.id)).await?;
-            let tests = self.get_tests_by_suite_id( suite.id).await?;
+    pub async fn is_suite_existing(&self, suite_id: i32) -> Result<bool, sqlx::Error> {
+        let is_existing: Option<bool> = query_scalar!("SELECT EXISTS(SELECT 1 FROM suites WHERE id = $1)", suite_id)
+            .fetch_one(&self.pool)
+            .await
+            .inspect_err(|e| tracing::error!("Query is_suite_existing failed: {:?}", e))?;
+
+        Ok(is_existing.unwrap_or(false))
+    }
+
+    /// Gives the cursor of the next page when there is one.
+    pub async fn get_tests_page_by_suite_id(&self, suite_id: i32, request: &SuiteTestsRequest) -> Result<(Vec<TestRunTest>, Option<PageCursor>), sqlx::Error> {
+        // One more test tells whether there is a next page.
+        let mut rows = query_file_as!(
+            TestDB,
+            "./src/repositories/queries/robot/get_tests_page_by_suite_id.sql",
+            suite_id,
+            request.cursor.map(|cursor| cursor.sort_key),
+            request.cursor.map(|cursor| cursor.id),
+            request.limit + 1
+        )
+        .fetch_all(&self.pool)
+        .await
+        .inspect_err(|e| tracing::error!("Query get_tests_page_by_suite_id failed: {:?}", e))?;
 
-            suite_db_list.push(suite.to_test_run_suite(suites, tests));
+        let mut next_cursor = None;
+        if rows.len() as i64 > request.limit {
+            rows.truncate(request.limit as usize);
+            next_cursor = rows.last().and_then(|row| {
+                row.sort_key.map(|sort_key| PageCursor { sort_key, id: row.id })
+            });
         }
-        Ok(suite_db_list)
-    }).await
+        let tests = rows.into_iter().map(|row| row.into_test()).collect();
+        Ok((tests, next_cursor))
     }
 
-    async fn get_tests_by_suite_id(
+    /// Every run of the test, the earlier ones being those a rerun replaced.
+    pub async fn get_test_attempts_by_test_id(
         &self,
-        suite_id: i32,
-    ) -> Result<Vec<TestRunTest>, sqlx::Error> {
-        let rows = query_as!(
-            TestDB,
+        test_id: i32,
+    ) -> Result<Vec<TestAttempt>, sqlx::Error> {
+        query_as!(
+            TestAttemptDB,
             r#"--sql
-            SELECT 
-                t.id,
-                t.identifier,
-                t.name,
-                t.status,
-                t.start_time,
-                t.end_time,
-                t.line,
-                t.doc,
-                t.timeout,
-                tt.value as "tag?"
+            SELECT attempts.id,
+                attempts.attempt,
+                attempts.status,
+                attempts.start_time,
+                attempts.end_time
             FROM tests t
-            LEFT JOIN test_tags tt ON t.id = tt.test_id
-            WHERE t.suite_id = $1
-            ORDER BY t.start_time ASC, tt.value ASC
+            JOIN tests attempts ON attempts.suite_id = t.suite_id
+                AND attempts.identifier = t.identifier
+            WHERE t.id = $1
+            ORDER BY attempts.attempt;
             "#,
-            suite_id
+            test_id
         )
         .fetch_all(&self.pool)
         .await
-        .inspect_err(|e| tracing::error!("Query get_tests_by_suite_id failed for suite_id={}: {:?}", suite_id, e))?;
-        
-        let mut tests_map: HashMap<i32, (&TestDB, Vec<String>)> = HashMap::new();
-        
-        for row in &rows {
-            tests_map
-                .entry(row.id)
-                .and_modify(|(_, tags)| {
-                    if let Some(tag) = &row.tag {
-                        tags.push(tag.clone());
-                    }
-                })
-                .or_insert_with(|| (row, row.tag.clone().into_iter().collect()));
-        }
+        .inspect_err(|e| tracing::error!("Query get_test_attempts_by_test_id failed: {:?}", e))
+        .map(|attempts| attempts.iter().map(TestAttemptDB::to_test_attempt).collect())
+    }
+
+    /// Suites of the test run by full name, with the tests a rerun can replace.
+    pub async fn get_rerun_targets_by_test_run_id(
+        &self,
+        conn: &mut PgConnection,
+        test_run_id: i32,
+    ) -> Result<Vec<RerunTargetDB>, sqlx::Error> {
+        query_file_as!(
+            RerunTargetDB,
+            "./src/repositories/queries/robot/get_rerun_targets_by_test_run_id.sql",
+            test_run_id
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .inspect_err(|e| tracing::error!("Query get_rerun_targets_by_test_run_id failed: {:?}", e))
+    }
+
+    /// Hides each previous attempt behind the test that replaced it.
+    pub async fn supersede_tests(
+        &self,
+        conn: &mut PgConnection,
+        previous_test_ids: &[i32],
+        test_ids: &[i32],
+    ) -> Result<(), sqlx::Error> {
+        query!(
+            r#"--sql
+            UPDATE tests
+            SET superseded_by = replacements.test_id
+            FROM unnest($1::INTEGER[], $2::INTEGER[]) AS replacements(previous_test_id, test_id)
+            WHERE tests.id = replacements.previous_test_id
+            "#,
+            previous_test_ids,
+            test_ids
+        )
+        .execute(&mut *conn)
+        .await
+        .inspect_err(|e| tracing::error!("Query supersede_tests failed: {:?}", e))?;
+
+        Ok(())
+    }
 
-        Ok(tests_map
-            .into_values()
-            .map(|(&ref test, tags)| test.to_(tags))
-            .collect())
+    pub async fn update_suite_statuses(
+        &self,
+        conn: &mut PgConnection,
+        suite_ids: &[i32],
+        statuses: &[String],
+    ) -> Result<(), sqlx::Error> {
+        query!(
+            r#"--sql
+            UPDATE suites
+            SET status = updates.status
+            FROM unnest($1::INTEGER[], $2::TEXT[]) AS updates(suite_id, status)
+            WHERE suites.id = updates.suite_id
+            "#,
+            suite_ids,
+            statuses
+        )
+        .execute(&mut *conn)
+        .await
+        .inspect_err(|e| tracing::error!("Query update_suite_statuses failed: {:?}", e))?;
+
+        Ok(())
     }
 
-    async fn get_test_run_statistics_by_test_run_id(
+    pub async fn delete_statistics(&self, conn: &mut PgConnection, test_run_id: i32) -> Result<(), sqlx::Error> {
+        query!(
+            "DELETE FROM test_run_statistics WHERE test_run_id = $1",
+            test_run_id
+        )
+        .execute(&mut *conn)
+        .await
+        .inspect_err(|e| tracing::error!("Query delete_statistics failed: {:?}", e))?;
+
+        Ok(())
+    }
+
+    pub async fn get_test_run_statistics_by_test_run_id(
         &self,
         test_run_id: i32,
     ) -> Result<Vec<TestRunStatistic>, sqlx::Error> {
@@ -328,7 +900,8 @@ impl RobotRepository {
             SELECT errors.id,
                 errors.timestamp,
                 errors.level,
-                errors.content
+                errors.content,
+                errors.html
             FROM test_run_errors errors
             WHERE errors.test_run_id = $1;
             "#,
@@ -342,234 +915,518 @@ impl RobotRepository {
         .map(|errors| errors.iter().map(ErrorDB::to_test_run_error).collect())
     }
 
-    async fn insert_suites(
+    pub async fn get_test_run_parts_by_test_run_id(
         &self,
         test_run_id: i32,
-        parent_suite_id: Option<i32>,
-        suites: &Vec<SuiteDBLegacy>,
+    ) -> Result<Vec<TestRunPart>, sqlx::Error> {
+        query_as!(
+            TestRunPartDB,
+            r#"--sql
+            SELECT parts.file_name,
+                parts.sha1,
+                parts.rerun
+            FROM test_run_parts parts
+            WHERE parts.test_run_id = $1
+            ORDER BY parts.part_index;
+            "#,
+            test_run_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .inspect_err(|e| {
+            tracing::error!("Query get_test_run_parts_by_test_run_id failed: {:?}", e)
+        })
+        .map(|parts| parts.iter().map(TestRunPartDB::to_test_run_part).collect())
+    }
+
+    pub async fn insert_test_run_part(
+        &self,
+        conn: &mut PgConnection,
+        test_run_id: i32,
+        part_index: i32,
+        file_name: Option<&str>,
+        sha1: &str,
+        rerun: bool,
     ) -> Result<(), sqlx::Error> {
-        Box::pin(async move {
-            if suites.is_empty() {
-                return Ok(());
-            }
-            
-            let mut query_builder = sqlx::QueryBuilder::new(
-                "INSERT INTO suites (test_run_id, name, source, status, start_time, end_time, identifier, parent_suite_id, doc) ",
-            );
-
-            query_builder.push_values(suites, |mut b, suite| {
-                b.push_bind(test_run_id)
-                    .push_bind(&suite.name)
-                    .push_bind(&suite.source)
-                    .push_bind(&suite.status)
-                    .push_bind(&suite.start_time)
-                    .push_bind(&suite.end_time)
-                    .push_bind(&suite.identifier)
-                    .push_bind(parent_suite_id)
-                    .push_bind(&suite.doc);
-            });
+        query!(
+            "INSERT INTO test_run_parts (test_run_id, part_index, file_name, sha1, rerun) VALUES ($1, $2, $3, $4, $5)",
+            test_run_id,
+            part_index,
+            file_name,
+            sha1,
+            rerun
+        )
+        .execute(&mut *conn)
+        .await
+        .inspect_err(|e| tracing::error!("Query insert_test_run_part failed: {:?}", e))?;
 
-            query_builder.push(" RETURNING id");
-
-            let ids: Vec<(i32,)> = query_builder.build_query_as().fetch_all(&self.pool).await
-            .inspect_err(|e| tracing::error!("Query insert_suites failed: {:?}", e))?;
-
-            for (suite, (id,)) in suites.iter().zip(ids) {
-                if !suite.suites.is_empty() {
-                    self.insert_suites(test_run_id, Some(id), &suite.suites).await?;
-                }
-                if !suite.tests.is_empty() {
-                    self.insert_tests(id, &suite.tests).await?;
-                }
-                if let Some(setup_kw) = &suite.setup_keyword {
-                    self.insert_suite_keyword(id, SuiteKeywordType::Setup, setup_kw.clone()).await?;
-                }
-                if let Some(teardown_kw) = &suite.teardown_keyword {
-                    self.insert_suite_keyword(id, SuiteKeywordType::Teardown, teardown_kw.clone()).await?;
-                }
-            }
+        Ok(())
+    }
 
-            Ok(())
-        }).await
+    async fn get_test_run_artifacts_by_test_run_id(
+        &self,
+        test_run_id: i32,
+    ) -> Result<Vec<TestRunArtifact>, sqlx::Error> {
+        query_as!(
+            ArtifactDB,
+            r#"--sql
+            SELECT artifacts.path,
+                artifacts.content_type,
+                artifacts.size
+            FROM test_run_artifacts artifacts
+            WHERE artifacts.test_run_id = $1
+            ORDER BY artifacts.path;
+            "#,
+            test_run_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .inspect_err(|e| {
+            tracing::error!("Query get_test_run_artifacts_by_test_run_id failed: {:?}", e)
+        })
+        .map(|artifacts| artifacts.iter().map(ArtifactDB::to_test_run_artifact).collect())
     }
 
-    async fn insert_suite_keyword(
+    pub async fn get_test_run_artifact(
         &self,
-        suite_id: i32,
-        keyword_type: SuiteKeywordType,
-        keyword: parser::Keyword,
+        test_run_id: i32,
+        path: &str,
+    ) -> Result<Option<ArtifactContent>, sqlx::Error> {
+        let artifact = query_as!(
+            ArtifactContentDB,
+            r#"--sql
+            SELECT artifacts.content_type,
+                artifacts.size,
+                artifacts.content_id
+            FROM test_run_artifacts artifacts
+            WHERE artifacts.test_run_id = $1
+                AND artifacts.path = $2;
+            "#,
+            test_run_id,
+            path
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .inspect_err(|e| tracing::error!("Query get_test_run_artifact failed: {:?}", e))?;
+
+        Ok(artifact.map(|artifact| {
+            let content = self.stream_content(artifact.content_id);
+            artifact.into_artifact_content(content)
+        }))
+    }
+
+    /// `content_id` is the content written with [`Self::insert_content_chunk`].
+    pub async fn insert_test_run_artifact(
+        &self,
+        conn: &mut PgConnection,
+        test_run_id: i32,
+        path: &str,
+        content_type: &str,
+        size: i64,
+        content_id: i64,
     ) -> Result<(), sqlx::Error> {
-        let json_keyword = match serde_json::to_value(&keyword) {
-            Ok(json) => json,
-            Err(e) => {
-                tracing::error!("Failed to serialize keyword: {:?}", e);
-                return Err(sqlx::Error::Protocol("Failed to serialize keyword".into()));
-            }
-        };
+        query!(
+            "INSERT INTO test_run_artifacts (test_run_id, path, content_type, size, content_id) VALUES ($1, $2, $3, $4, $5)",
+            test_run_id,
+            path,
+            content_type,
+            size,
+            content_id
+        )
+        .execute(&mut *conn)
+        .await
+        .inspect_err(|e| tracing::error!("Query insert_test_run_artifact failed: {:?}", e))?;
 
-        query_file!(
-            "./src/repositories/queries/robot/insert_suite_keyword.sql",
-            suite_id,
-            keyword_type.as_str(),
-            json_keyword
+        Ok(())
+    }
+
+    pub async fn reserve_content_id(&self) -> Result<i64, sqlx::Error> {
+        query_scalar!(r#"SELECT nextval('content_ids') as "id!""#)
+            .fetch_one(&self.pool)
+            .await
+            .inspect_err(|e| tracing::error!("Query reserve_content_id failed: {:?}", e))
+    }
+
+    /// Chunks are read back in the order of their position.
+    pub async fn insert_content_chunk(&self, conn: &mut PgConnection, content_id: i64, position: i32, content: &[u8]) -> Result<(), sqlx::Error> {
+        query!(
+            "INSERT INTO content_chunks (content_id, position, content) VALUES ($1, $2, $3)",
+            content_id,
+            position,
+            content
         )
-        .execute(&self.pool)
+        .execute(&mut *conn)
         .await
-        .inspect_err(|e| tracing::error!("Query insert_suite_keyword failed: {:?}", e))?;
+        .inspect_err(|e| tracing::error!("Query insert_content_chunk failed: {:?}", e))?;
 
         Ok(())
     }
 
-    async fn insert_tests(
-        &self,
-        suite_id: i32,
-        tests: &Vec<TestDBLegacy>,
-    ) -> Result<(), sqlx::Error> {
-        if tests.is_empty() {
-            return Ok(());
-        }
+    pub async fn get_content_chunk(&self, content_id: i64, position: i32) -> Result<Option<Vec<u8>>, sqlx::Error> {
+        query_scalar!(
+            "SELECT content FROM content_chunks WHERE content_id = $1 AND position = $2",
+            content_id,
+            position
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .inspect_err(|e| tracing::error!("Query get_content_chunk failed: {:?}", e))
+    }
 
-        let mut query_builder = sqlx::QueryBuilder::new(
-            "INSERT INTO tests (suite_id, identifier, name, status, start_time, end_time, line, doc, timeout) ",
-        );
-
-        query_builder.push_values(tests, |mut b, test| {
-            b.push_bind(suite_id)
-                .push_bind(&test.identifier)
-                .push_bind(&test.name)
-                .push_bind(&test.status)
-                .push_bind(&test.start_time)
-                .push_bind(&test.end_time)
-                .push_bind(&test.line)
-                .push_bind(&test.doc)
-                .push_bind(&test.timeout);
-        });
-
-        query_builder.push(" RETURNING id");
-
-        let ids: Vec<(i32,)> = query_builder.build_query_as().fetch_all(&self.pool).await
-        .inspect_err(|e| tracing::error!("Query insert_tests failed: {:?}", e))?;
+    pub async fn delete_content(&self, conn: &mut PgConnection, content_id: i64) -> Result<(), sqlx::Error> {
+        query!("DELETE FROM content_chunks WHERE content_id = $1", content_id)
+            .execute(&mut *conn)
+            .await
+            .inspect_err(|e| tracing::error!("Query delete_content failed: {:?}", e))?;
 
-        for (test, (id,)) in tests.iter().zip(ids) {
-            if !test.tags.is_empty() {
-                self.insert_test_tags(id, &test.tags).await?
-            }
-            if !test.keywords.is_empty() {
-                self.insert_test_keywords(id, &test.keywords).await?
-            }
-        }
+        Ok(())
+    }
 
-        Ok(())  
+    /// Reads the content one chunk at a time, as the stream is polled.
+    pub fn stream_content(&self, content_id: i64) -> ContentChunks {
+        let repository = RobotRepository::new(self.pool.clone());
+        stream::try_unfold((repository, 0), move |(repository, position)| async move {
+            let chunk = repository
+                .get_content_chunk(content_id, position)
+                .await
+                .map_err(io::Error::other)?;
+            Ok(chunk.map(|chunk| (chunk, (repository, position + 1))))
+        })
+        .boxed()
     }
-    
-    async fn insert_test_tags(
+
+    pub async fn is_output_file_stored(&self, sha1: &str) -> Result<bool, sqlx::Error> {
+        let is_stored: Option<bool> = query_scalar!(
+            "SELECT EXISTS(SELECT 1 FROM output_files WHERE sha1 = $1)",
+            sha1
+        )
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(|e| tracing::error!("Query is_output_file_stored failed: {:?}", e))?;
+
+        Ok(is_stored.unwrap_or(false))
+    }
+
+    /// The content is the gzipped file.
+    pub async fn get_output_file(&self, sha1: &str) -> Result<Option<OutputFile>, sqlx::Error> {
+        let output_file = query_as!(
+            OutputFileDB,
+            r#"--sql
+            SELECT (
+                    SELECT parts.file_name
+                    FROM test_run_parts parts
+                    WHERE parts.sha1 = files.sha1
+                ) AS file_name,
+                files.size,
+                files.content_id
+            FROM output_files files
+            WHERE files.sha1 = $1;
+            "#,
+            sha1
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .inspect_err(|e| tracing::error!("Query get_output_file failed: {:?}", e))?;
+
+        Ok(output_file.map(|output_file| {
+            let content = self.stream_content(output_file.content_id);
+            output_file.into_output_file(content)
+        }))
+    }
+
+    /// `content_id` is the gzipped file, `size` the size of the original.
+    /// Returns `false` when the file was stored since.
+    pub async fn insert_output_file(
         &self,
-        test_id: i32,
-        tags: &Vec<String>,
+        conn: &mut PgConnection,
+        sha1: &str,
+        size: i64,
+        content_id: i64,
+    ) -> Result<bool, sqlx::Error> {
+        let result = query!(
+            "INSERT INTO output_files (sha1, size, content_id) VALUES ($1, $2, $3) ON CONFLICT (sha1) DO NOTHING",
+            sha1,
+            size,
+            content_id
+        )
+        .execute(&mut *conn)
+        .await
+        .inspect_err(|e| tracing::error!("Query insert_output_file failed: {:?}", e))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Writes the suites with their keywords and metadata, one statement per
+    /// table.
+    pub async fn insert_suites(
+        &self,
+        conn: &mut PgConnection,
+        test_run_id: i32,
+        suites: &[SuiteRow],
     ) -> Result<(), sqlx::Error> {
-        if tags.is_empty() {
+        if suites.is_empty() {
             return Ok(());
         }
 
-        let mut query_builder = sqlx::QueryBuilder::new(
-            "INSERT INTO test_tags (test_id, value) ",
-        );
+        query_file!(
+            "./src/repositories/queries/robot/insert_suites.sql",
+            test_run_id,
+            &suites.iter().map(|row| row.id).collect::<Vec<_>>(),
+            &suites.iter().map(|row| row.suite.name.clone()).collect::<Vec<_>>(),
+            &suites.iter().map(|row| row.suite.source.clone()).collect::<Vec<_>>() as &[Option<String>],
+            &suites.iter().map(|row| row.suite.status.clone()).collect::<Vec<_>>(),
+            &suites.iter().map(|row| row.suite.start_time).collect::<Vec<_>>(),
+            &suites.iter().map(|row| row.suite.end_time).collect::<Vec<_>>(),
+            &suites.iter().map(|row| row.suite.identifier.clone()).collect::<Vec<_>>(),
+            &suites.iter().map(|row| row.parent_suite_id).collect::<Vec<_>>() as &[Option<i32>],
+            &suites.iter().map(|row| row.suite.doc.clone()).collect::<Vec<_>>() as &[Option<String>],
+            &suites.iter().map(|row| row.suite.message.clone()).collect::<Vec<_>>() as &[Option<String>],
+            &suites.iter().map(|row| row.full_name.clone()).collect::<Vec<_>>()
+        )
+        .execute(&mut *conn)
+        .await
+        .inspect_err(|e| tracing::error!("Query insert_suites failed: {:?}", e))?;
 
-        query_builder.push_values(tags, |mut b, tag| {
-            b.push_bind(test_id)
-                .push_bind(tag);
-        });
+        let mut keywords = KeywordRows::default();
+        for row in suites {
+            if let Some(setup_kw) = &row.suite.setup_keyword {
+                keywords.push(row.id, KeywordType::Setup, setup_kw)?;
+            }
+            if let Some(teardown_kw) = &row.suite.teardown_keyword {
+                keywords.push(row.id, KeywordType::Teardown, teardown_kw)?;
+            }
+        }
+        if !keywords.ids.is_empty() {
+            query_file!(
+                "./src/repositories/queries/robot/insert_suite_keywords.sql",
+                &keywords.ids,
+                &keywords.types,
+                &keywords.values
+            )
+            .execute(&mut *conn)
+            .await
+            .inspect_err(|e| tracing::error!("Query insert_suite_keywords failed: {:?}", e))?;
+        }
 
-        query_builder
-            .build()
-            .execute(&self.pool)
+        let metadata: Vec<(i32, i32, &String, &String)> = suites
+            .iter()
+            .flat_map(|row| {
+                row.suite.metadata.iter().enumerate().map(|(position, (name, value))| (row.id, position as i32, name, value))
+            })
+            .collect();
+        if !metadata.is_empty() {
+            query!(
+                r#"--sql
+                INSERT INTO suite_metadata (suite_id, position, name, value)
+                SELECT *
+                FROM unnest($1::INTEGER[], $2::INTEGER[], $3::TEXT[], $4::TEXT[])
+                "#,
+                &metadata.iter().map(|entry| entry.0).collect::<Vec<_>>(),
+                &metadata.iter().map(|entry| entry.1).collect::<Vec<_>>(),
+                &metadata.iter().map(|entry| entry.2.clone()).collect::<Vec<_>>(),
+                &metadata.iter().map(|entry| entry.3.clone()).collect::<Vec<_>>()
+            )
+            .execute(&mut *conn)
             .await
-            .inspect_err(|e| tracing::error!("Query insert_test_tags failed: {:?}", e))?;
+            .inspect_err(|e| tracing::error!("Query insert_suite_metadata failed: {:?}", e))?;
+        }
+
         Ok(())
     }
 
-    async fn insert_test_keywords(
+    /// Writes the tests with their tags and keywords, one statement per table.
+    pub async fn insert_tests(
         &self,
-        test_id: i32,
-        keywords: &Vec<parser::BaseBody>,
+        conn: &mut PgConnection,
+        tests: &[TestRow],
     ) -> Result<(), sqlx::Error> {
-        let json_keywords = match serde_json::to_value(&keywords) {
-            Ok(json) => json,
-            Err(e) => {
-                tracing::error!("Failed to serialize keyword: {:?}", e);
-                return Err(sqlx::Error::Protocol("Failed to serialize keyword".into()));
-            }
-        };
+        if tests.is_empty() {
+            return Ok(());
+        }
 
         query_file!(
-            "./src/repositories/queries/robot/insert_test_keywords.sql",
-            test_id,
-            json_keywords
+            "./src/repositories/queries/robot/insert_tests.sql",
+            &tests.iter().map(|row| row.id).collect::<Vec<_>>(),
+            &tests.iter().map(|row| row.suite_id).collect::<Vec<_>>(),
+            &tests.iter().map(|row| row.test.identifier.clone()).collect::<Vec<_>>(),
+            &tests.iter().map(|row| row.test.name.clone()).collect::<Vec<_>>(),
+            &tests.iter().map(|row| row.test.status.clone()).collect::<Vec<_>>(),
+            &tests.iter().map(|row| row.test.start_time).collect::<Vec<_>>(),
+            &tests.iter().map(|row| row.test.end_time).collect::<Vec<_>>(),
+            &tests.iter().map(|row| row.test.line).collect::<Vec<_>>() as &[Option<i32>],
+            &tests.iter().map(|row| row.test.doc.clone()).collect::<Vec<_>>() as &[Option<String>],
+            &tests.iter().map(|row| row.test.timeout.clone()).collect::<Vec<_>>() as &[Option<String>],
+            &tests.iter().map(|row| row.test.attempt).collect::<Vec<_>>(),
+            &tests.iter().map(|row| row.test.message.clone()).collect::<Vec<_>>() as &[Option<String>]
         )
-        .execute(&self.pool)
+        .execute(&mut *conn)
         .await
-        .inspect_err(|e| tracing::error!("Query insert_test_keywords failed: {:?}", e))?;
+        .inspect_err(|e| tracing::error!("Query insert_tests failed: {:?}", e))?;
+
+        let (tag_test_ids, tags): (Vec<i32>, Vec<String>) = tests
+            .iter()
+            .flat_map(|row| row.test.tags.iter().map(|tag| (row.id, tag.clone())))
+            .unzip();
+        if !tags.is_empty() {
+            query!(
+                r#"--sql
+                INSERT INTO test_tags (test_id, value)
+                SELECT *
+                FROM unnest($1::INTEGER[], $2::TEXT[])
+                "#,
+                &tag_test_ids,
+                &tags
+            )
+            .execute(&mut *conn)
+            .await
+            .inspect_err(|e| tracing::error!("Query insert_test_tags failed: {:?}", e))?;
+        }
+
+        let mut keywords = KeywordRows::default();
+        for row in tests {
+            if let Some(setup_kw) = &row.test.setup_keyword {
+                keywords.push(row.id, KeywordType::Setup, setup_kw)?;
+            }
+            if !row.test.keywords.is_empty() {
+                keywords.push(row.id, KeywordType::Body, &row.test.keywords)?;
+            }
+            if let Some(teardown_kw) = &row.test.teardown_keyword {
+                keywords.push(row.id, KeywordType::Teardown, teardown_kw)?;
+            }
+        }
+        if !keywords.ids.is_empty() {
+            query_file!(
+                "./src/repositories/queries/robot/insert_test_keywords.sql",
+                &keywords.ids,
+                &keywords.types,
+                &keywords.values
+            )
+            .execute(&mut *conn)
+            .await
+            .inspect_err(|e| tracing::error!("Query insert_test_keywords failed: {:?}", e))?;
+        }
 
         Ok(())
     }
 
-    async fn insert_statistics(
+    /// One statement however many statistics, their columns bound as arrays.
+    pub async fn insert_statistics(
         &self,
+        conn: &mut PgConnection,
         test_run_id: i32,
-        statistics: &Vec<StatDBLegacy>,
+        statistics: &[StatDBLegacy],
     ) -> Result<(), sqlx::Error> {
         if statistics.is_empty() {
             return Ok(());
         }
 
-        let mut query_builder = sqlx::QueryBuilder::new(
-            "INSERT INTO test_run_statistics (test_run_id, stat_type, pass_count, fail_count, skip_count, identifier, name, text) "
-        );
-
-        query_builder.push_values(statistics, |mut b, stat| {
-            b.push_bind(test_run_id)
-                .push_bind(&stat.stat_type)
-                .push_bind(stat.pass_count)
-                .push_bind(stat.fail_count)
-                .push_bind(stat.skip_count)
-                .push_bind(&stat.identifier)
-                .push_bind(&stat.name)
-                .push_bind(&stat.text);
-        });
-
-        query_builder
-            .build()
-            .execute(&self.pool)
-            .await
-            .inspect_err(|e| tracing::error!("Query insert_statistics failed: {:?}", e))?;
+        query_file!(
+            "./src/repositories/queries/robot/insert_statistics.sql",
+            test_run_id,
+            &statistics.iter().map(|stat| stat.stat_type.clone()).collect::<Vec<_>>() as &[StatTypeDB],
+            &statistics.iter().map(|stat| stat.pass_count).collect::<Vec<_>>(),
+            &statistics.iter().map(|stat| stat.fail_count).collect::<Vec<_>>(),
+            &statistics.iter().map(|stat| stat.skip_count).collect::<Vec<_>>(),
+            &statistics.iter().map(|stat| stat.identifier.clone()).collect::<Vec<_>>() as &[Option<String>],
+            &statistics.iter().map(|stat| stat.name.clone()).collect::<Vec<_>>() as &[Option<String>],
+            &statistics.iter().map(|stat| stat.text.clone()).collect::<Vec<_>>()
+        )
+        .execute(&mut *conn)
+        .await
+        .inspect_err(|e| tracing::error!("Query insert_statistics failed: {:?}", e))?;
         Ok(())
     }
 
-    async fn insert_errors(
+    /// One statement however many errors, their columns bound as arrays.
+    pub async fn insert_errors(
         &self,
+        conn: &mut PgConnection,
         test_run_id: i32,
-        errors: &Vec<ErrorDBLegacy>,
+        errors: &[ErrorDBLegacy],
     ) -> Result<(), sqlx::Error> {
         if errors.is_empty() {
             return Ok(());
         }
 
-        let mut query_builder = sqlx::QueryBuilder::new(
-            "INSERT INTO test_run_errors (test_run_id, timestamp, level, content) ",
-        );
+        query_file!(
+            "./src/repositories/queries/robot/insert_errors.sql",
+            test_run_id,
+            &errors.iter().map(|error| error.timestamp).collect::<Vec<_>>(),
+            &errors.iter().map(|error| error.level.clone()).collect::<Vec<_>>(),
+            &errors.iter().map(|error| error.content.clone()).collect::<Vec<_>>(),
+            &errors.iter().map(|error| error.html).collect::<Vec<_>>()
+        )
+        .execute(&mut *conn)
+        .await
+        .inspect_err(|e| tracing::error!("Query insert_errors failed: {:?}", e))?;
+        Ok(())
+    }
+}
+
+/// The children of a suite keep the order they were loaded in.
+fn build_suite_tree(
+    suites_by_parent: &mut HashMap<Option<i32>, Vec<SuiteDB>>,
+    tests_by_suite: &mut HashMap<i32, Vec<TestRunTest>>,
+    parent_suite_id: Option<i32>,
+) -> Vec<TestRunSuite> {
+    suites_by_parent
+        .remove(&parent_suite_id)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|suite| {
+            let suites = build_suite_tree(suites_by_parent, tests_by_suite, Some(suite.id));
+            let tests = tests_by_suite.remove(&suite.id).unwrap_or_default();
+            suite.to_test_run_suite(suites, tests)
+        })
+        .collect()
+}
 
-        query_builder.push_values(errors, |mut b, error| {
-            b.push_bind(test_run_id)
-                .push_bind(&error.timestamp)
-                .push_bind(&error.level)
-                .push_bind(&error.content);
-        });
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
 
-        query_builder
-            .build()
-            .execute(&self.pool)
-            .await
-            .inspect_err(|e| tracing::error!("Query insert_errors failed: {:?}", e))?;
+    use super::*;
+
+    /// More rows than the 65535 bind parameters of a statement would allow
+    /// with one parameter a column and row.
+    #[sqlx::test]
+    async fn test_insert_many_statistics_and_errors(pool: PgPool) -> Result<(), sqlx::Error> {
+        let repository = RobotRepository::new(pool.clone());
+        let statistics: Vec<StatDBLegacy> = (0..10_000)
+            .map(|index| StatDBLegacy {
+                id: None,
+                stat_type: StatTypeDB::Tag,
+                pass_count: index,
+                fail_count: 0,
+                skip_count: 0,
+                identifier: None,
+                name: None,
+                text: format!("tag-{}", index),
+            })
+            .collect();
+        let errors: Vec<ErrorDBLegacy> = (0..15_000)
+            .map(|index| ErrorDBLegacy {
+                id: None,
+                timestamp: NaiveDateTime::default(),
+                level: "WARN".to_string(),
+                content: format!("error {}", index),
+                html: false,
+            })
+            .collect();
+
+        let mut tx = repository.begin().await?;
+        repository.insert_statistics(&mut tx, 1, &statistics).await?;
+        repository.insert_errors(&mut tx, 1, &errors).await?;
+        tx.commit().await?;
+
+        let statistics_count = query_scalar!("SELECT COUNT(*) FROM test_run_statistics WHERE test_run_id = 1")
+            .fetch_one(&pool)
+            .await?;
+        let errors_count = query_scalar!("SELECT COUNT(*) FROM test_run_errors WHERE test_run_id = 1")
+            .fetch_one(&pool)
+            .await?;
+        assert_eq!(statistics_count, Some(10_000));
+        assert_eq!(errors_count, Some(15_000));
         Ok(())
     }
 }
@@ -0,0 +1,91 @@
+use std::sync::Arc;
+
+use actix_web::{http::header, web, Error, HttpResponse, Scope};
+use serde_json::json;
+use tracing::error;
+
+use crate::services::imports::{ImportError, ImportService};
+
+/// Maintenance operations, e.g. importing test runs again from their stored
+/// output files once the parser learned something new. The test runs are
+/// queued as import jobs and imported again in the background.
+pub struct AdminHandler {
+    imports_service: Arc<ImportService>,
+}
+
+impl AdminHandler {
+    fn new(imports_service: Arc<ImportService>) -> Self {
+        AdminHandler { imports_service }
+    }
+
+    pub fn init(cfg: &mut web::ServiceConfig, imports_service: Arc<ImportService>) {
+        let handler = AdminHandler::new(imports_service);
+        cfg.service(handler.routes());
+    }
+
+    fn routes(&self) -> Scope {
+        web::scope("/api/admin")
+            .app_data(web::Data::new(self.imports_service.clone()))
+            .route(
+                "/test-runs/{id}/reimport",
+                web::post().to(Self::reimport_test_run),
+            )
+            .route(
+                "/projects/{id}/reimport",
+                web::post().to(Self::reimport_project_test_runs),
+            )
+            .route("/reimport", web::post().to(Self::reimport_all_test_runs))
+    }
+
+    async fn reimport_test_run(
+        imports_service: web::Data<Arc<ImportService>>,
+        test_run_id: web::Path<i32>,
+    ) -> Result<HttpResponse, Error> {
+        let job_ids = imports_service
+            .enqueue_reimports(None, Some(test_run_id.into_inner()))
+            .await;
+
+        match job_ids.as_deref() {
+            Ok([job_id]) => Ok(HttpResponse::Accepted()
+                .insert_header((header::LOCATION, format!("/api/robot/imports/{}", job_id)))
+                .json(json!({
+                    "jobId": job_id,
+                    "status": "queued"
+                }))),
+            Ok(_) => Ok(HttpResponse::NotFound().finish()),
+            Err(e) => {
+                error!("Error queuing reimport: {:?}", e);
+                Ok(HttpResponse::InternalServerError().finish())
+            }
+        }
+    }
+
+    async fn reimport_project_test_runs(
+        imports_service: web::Data<Arc<ImportService>>,
+        project_id: web::Path<i32>,
+    ) -> Result<HttpResponse, Error> {
+        let job_ids = imports_service
+            .enqueue_reimports(Some(project_id.into_inner()), None)
+            .await;
+        Self::reimports_queued(job_ids)
+    }
+
+    async fn reimport_all_test_runs(
+        imports_service: web::Data<Arc<ImportService>>,
+    ) -> Result<HttpResponse, Error> {
+        let job_ids = imports_service.enqueue_reimports(None, None).await;
+        Self::reimports_queued(job_ids)
+    }
+
+    fn reimports_queued(job_ids: Result<Vec<i32>, ImportError>) -> Result<HttpResponse, Error> {
+        match job_ids {
+            Ok(job_ids) => Ok(HttpResponse::Accepted().json(json!({
+                "jobIds": job_ids
+            }))),
+            Err(e) => {
+                error!("Error queuing reimports: {:?}", e);
+                Ok(HttpResponse::InternalServerError().finish())
+            }
+        }
+    }
+}
@@ -1,3 +1,4 @@
+pub mod admin;
 pub mod frontend;
 pub mod projects;
 pub mod robot;
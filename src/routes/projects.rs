@@ -1,8 +1,24 @@
 use actix_web::{web, Error, HttpResponse, Scope};
+use serde::Deserialize;
+use serde_json::json;
 use std::sync::Arc;
 use tracing::error;
 
-use crate::services::projects::ProjectsService;
+use crate::{
+    models::robot::domain::{
+        PageCursor, TestIdentity, TestRunFilter, TestRunPageRequest, TestRunSort,
+    },
+    routes::robot::TestHistoryQuery,
+    services::projects::ProjectsService,
+    utils,
+};
+
+const DEFAULT_TEST_RUNS_LIMIT: i64 = 20;
+const MAX_TEST_RUNS_LIMIT: i64 = 100;
+const DEFAULT_FLAKY_TESTS_LIMIT: i64 = 20;
+const MAX_FLAKY_TESTS_LIMIT: i64 = 100;
+const DEFAULT_FAILURE_GROUPS_LIMIT: i64 = 20;
+const MAX_FAILURE_GROUPS_LIMIT: i64 = 100;
 
 pub struct ProjectsHandler {
     projects_service: Arc<ProjectsService>,
@@ -25,6 +41,10 @@ impl ProjectsHandler {
             .app_data(web::Data::new(self.projects_service.clone()))
             .route("/overview", web::get().to(Self::get_projects_overview))
             .route("/{id}", web::get().to(Self::get_project_by_id))
+            .route("/{id}/test-runs", web::get().to(Self::get_test_runs))
+            .route("/{id}/test-history", web::get().to(Self::get_test_history))
+            .route("/{id}/flaky-tests", web::get().to(Self::get_flaky_tests))
+            .route("/{id}/failures", web::get().to(Self::get_failure_groups))
     }
 
     async fn get_projects_overview(
@@ -39,11 +59,30 @@ impl ProjectsHandler {
         }
     }
 
+    /// Test runs can be filtered by the metadata of their root suite, with
+    /// `?metadata=Browser:firefox` repeated for each entry to match, by their
+    /// labels the same way with `label=team:checkout`, and by `environment`,
+    /// `branch`, `ciSystem`, `buildNumber`, `robotGitHash` or `appGitHash`.
+    /// `?environment=prod&branch=main` scopes the history to one context of
+    /// the overview, an empty value to the runs without one. `from` and `to`
+    /// take a day or a date and time, `appVersion` a version, and `status`
+    /// is `failed` for the runs with failures or `passed` for the others.
     async fn get_project_by_id(
         projects_service: web::Data<Arc<ProjectsService>>,
         path: web::Path<i32>,
+        query: web::Query<Vec<(String, String)>>,
     ) -> Result<HttpResponse, Error> {
-        match projects_service.get_project_by_id(path.into_inner()).await {
+        let mut filter = TestRunFilter::default();
+        for (key, value) in query.into_inner() {
+            if let Err(message) = apply_filter(&mut filter, &key, value) {
+                return Ok(HttpResponse::BadRequest().json(json!({ "error": message })));
+            }
+        }
+
+        match projects_service
+            .get_project_by_id(path.into_inner(), &filter)
+            .await
+        {
             Ok(Some(project)) => Ok(HttpResponse::Ok().json(project)),
             Ok(None) => Ok(HttpResponse::NotFound().finish()),
             Err(e) => {
@@ -52,4 +91,221 @@ impl ProjectsHandler {
             }
         }
     }
+
+    /// A page of the test runs of a project, with the filters of
+    /// `get_project_by_id`. `sort` is `date`, `duration` or `failedTests`,
+    /// `order` is `desc` by default, and `cursor` takes the `nextCursor` of
+    /// the previous page with the same sort.
+    async fn get_test_runs(
+        projects_service: web::Data<Arc<ProjectsService>>,
+        path: web::Path<i32>,
+        query: web::Query<Vec<(String, String)>>,
+    ) -> Result<HttpResponse, Error> {
+        let mut filter = TestRunFilter::default();
+        let mut page = TestRunPageRequest {
+            sort: TestRunSort::Date,
+            descending: true,
+            cursor: None,
+            limit: DEFAULT_TEST_RUNS_LIMIT,
+        };
+        for (key, value) in query.into_inner() {
+            let result = match key.as_str() {
+                "sort" => match value.as_str() {
+                    "date" => Ok(TestRunSort::Date),
+                    "duration" => Ok(TestRunSort::Duration),
+                    "failedTests" => Ok(TestRunSort::FailedTests),
+                    _ => Err(format!(
+                        "Invalid sort {}, expected date, duration or failedTests",
+                        value
+                    )),
+                }
+                .map(|sort| page.sort = sort),
+                "order" => match value.as_str() {
+                    "asc" => Ok(false),
+                    "desc" => Ok(true),
+                    _ => Err(format!("Invalid order {}, expected asc or desc", value)),
+                }
+                .map(|descending| page.descending = descending),
+                "cursor" => PageCursor::parse(&value)
+                    .map(|cursor| page.cursor = Some(cursor))
+                    .ok_or(format!("Invalid cursor {}", value)),
+                "limit" => value
+                    .parse::<i64>()
+                    .map(|limit| page.limit = limit.clamp(1, MAX_TEST_RUNS_LIMIT))
+                    .map_err(|_| format!("Invalid limit {}", value)),
+                _ => apply_filter(&mut filter, &key, value),
+            };
+            if let Err(message) = result {
+                return Ok(HttpResponse::BadRequest().json(json!({ "error": message })));
+            }
+        }
+
+        match projects_service
+            .get_test_runs_page(path.into_inner(), &filter, &page)
+            .await
+        {
+            Ok(Some(test_runs)) => Ok(HttpResponse::Ok().json(test_runs)),
+            Ok(None) => Ok(HttpResponse::NotFound().finish()),
+            Err(e) => {
+                error!("Error getting test runs: {:?}", e);
+                Ok(HttpResponse::InternalServerError().finish())
+            }
+        }
+    }
+
+    /// The tests of the project that pass and fail on the same app version,
+    /// flakiest first.
+    async fn get_flaky_tests(
+        projects_service: web::Data<Arc<ProjectsService>>,
+        path: web::Path<i32>,
+        query: web::Query<FlakyTestsQuery>,
+    ) -> Result<HttpResponse, Error> {
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_FLAKY_TESTS_LIMIT)
+            .clamp(1, MAX_FLAKY_TESTS_LIMIT);
+
+        match projects_service
+            .get_flaky_tests(path.into_inner(), limit)
+            .await
+        {
+            Ok(Some(tests)) => Ok(HttpResponse::Ok().json(tests)),
+            Ok(None) => Ok(HttpResponse::NotFound().finish()),
+            Err(e) => {
+                error!("Error getting flaky tests: {:?}", e);
+                Ok(HttpResponse::InternalServerError().finish())
+            }
+        }
+    }
+
+    /// The failures of the runs of the project grouped by signature, most
+    /// failures first, with the filters of `get_project_by_id`, e.g.
+    /// `?from=2025-04-01` for the causes of failure since then.
+    async fn get_failure_groups(
+        projects_service: web::Data<Arc<ProjectsService>>,
+        path: web::Path<i32>,
+        query: web::Query<Vec<(String, String)>>,
+    ) -> Result<HttpResponse, Error> {
+        let mut filter = TestRunFilter::default();
+        let mut limit = DEFAULT_FAILURE_GROUPS_LIMIT;
+        for (key, value) in query.into_inner() {
+            let result = match key.as_str() {
+                "limit" => value
+                    .parse::<i64>()
+                    .map(|value| limit = value.clamp(1, MAX_FAILURE_GROUPS_LIMIT))
+                    .map_err(|_| format!("Invalid limit {}", value)),
+                _ => apply_filter(&mut filter, &key, value),
+            };
+            if let Err(message) = result {
+                return Ok(HttpResponse::BadRequest().json(json!({ "error": message })));
+            }
+        }
+
+        match projects_service
+            .get_failure_groups(path.into_inner(), &filter, limit)
+            .await
+        {
+            Ok(Some(groups)) => Ok(HttpResponse::Ok().json(groups)),
+            Ok(None) => Ok(HttpResponse::NotFound().finish()),
+            Err(e) => {
+                error!("Error getting failure groups: {:?}", e);
+                Ok(HttpResponse::InternalServerError().finish())
+            }
+        }
+    }
+
+    /// The outcomes of a test in every run of the project, e.g.
+    /// `?suite=Tests.Login&test=Valid Login`, latest runs first.
+    async fn get_test_history(
+        projects_service: web::Data<Arc<ProjectsService>>,
+        path: web::Path<i32>,
+        test: web::Query<TestQuery>,
+        query: web::Query<TestHistoryQuery>,
+    ) -> Result<HttpResponse, Error> {
+        let request = match query.to_request() {
+            Ok(request) => request,
+            Err(message) => {
+                return Ok(HttpResponse::BadRequest().json(json!({ "error": message })));
+            }
+        };
+        let test = test.into_inner();
+        let test = TestIdentity {
+            project_id: path.into_inner(),
+            suite: test.suite,
+            name: test.test,
+        };
+
+        match projects_service.get_test_history(test, &request).await {
+            Ok(Some(history)) => Ok(HttpResponse::Ok().json(history)),
+            Ok(None) => Ok(HttpResponse::NotFound().finish()),
+            Err(e) => {
+                error!("Error getting test history: {:?}", e);
+                Ok(HttpResponse::InternalServerError().finish())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct FlakyTestsQuery {
+    limit: Option<i64>,
+}
+
+/// A test of a project, by the path of its suite and its name.
+#[derive(Debug, Deserialize)]
+struct TestQuery {
+    suite: String,
+    test: String,
+}
+
+/// Unknown keys are left to the caller.
+fn apply_filter(filter: &mut TestRunFilter, key: &str, value: String) -> Result<(), String> {
+    match key {
+        "metadata" | "label" => {
+            let Some((name, entry_value)) = value.split_once(':') else {
+                return Err(format!(
+                    "Invalid {} filter {}, expected name:value",
+                    key, value
+                ));
+            };
+            let entry = (name.to_string(), entry_value.to_string());
+            if key == "metadata" {
+                filter.metadata.push(entry);
+            } else {
+                filter.labels.push(entry);
+            }
+        }
+        "from" | "to" => {
+            let Some(date) = utils::date::parse_datetime(&value, key == "to") else {
+                return Err(format!(
+                    "Invalid {} date {}, expected YYYY-MM-DD or YYYY-MM-DDTHH:MM:SS",
+                    key, value
+                ));
+            };
+            if key == "from" {
+                filter.from = Some(date);
+            } else {
+                filter.to = Some(date);
+            }
+        }
+        "status" => match value.as_str() {
+            "failed" => filter.has_failures = Some(true),
+            "passed" => filter.has_failures = Some(false),
+            _ => {
+                return Err(format!(
+                    "Invalid status {}, expected failed or passed",
+                    value
+                ))
+            }
+        },
+        "environment" => filter.environment = Some(value),
+        "branch" => filter.branch = Some(value),
+        "appVersion" => filter.app_version = Some(value),
+        "ciSystem" => filter.ci_system = Some(value),
+        "buildNumber" => filter.build_number = Some(value),
+        "robotGitHash" => filter.robot_git_hash = Some(value),
+        "appGitHash" => filter.app_git_hash = Some(value),
+        _ => {}
+    }
+    Ok(())
 }
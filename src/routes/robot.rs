@@ -1,46 +1,211 @@
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
 use actix_multipart::form::{json::Json as MpJson, tempfile::TempFile, MultipartForm};
-use actix_web::{web, Error, HttpResponse, Scope};
-use serde::Deserialize;
+use actix_web::{
+    http::header::{self, ContentDisposition},
+    web, Error, HttpResponse, Scope,
+};
+use futures_util::TryStreamExt;
+use serde::{de, Deserialize, Deserializer};
 use serde_json::json;
 use tracing::{error, info};
 
-use crate::services::{
-    self,
-    parser::{ParserError, RobotOutputParserService},
-    projects::ProjectsService,
-    robot::RobotService,
+use crate::{
+    models::robot::domain::{
+        CiMetadata, PageCursor, SearchRequest, SuiteTestsRequest, TestHistoryRequest,
+    },
+    services::{
+        self,
+        imports::{ImportError, ImportService, UploadedFile},
+        parser::{ParserError, RobotOutputParserService},
+        projects::ProjectsService,
+        robot::{MappingErrors, OutputPart, RobotService},
+    },
+    utils,
 };
 
+const DEFAULT_IMPORT_JOBS_LIMIT: i64 = 20;
+const MAX_IMPORT_JOBS_LIMIT: i64 = 100;
+const DEFAULT_TEST_HISTORY_LIMIT: i64 = 20;
+const MAX_TEST_HISTORY_LIMIT: i64 = 100;
+const DEFAULT_SUITE_TESTS_LIMIT: i64 = 100;
+const MAX_SUITE_TESTS_LIMIT: i64 = 500;
+const DEFAULT_SEARCH_LIMIT: i64 = 50;
+const MAX_SEARCH_LIMIT: i64 = 200;
+const TEST_STATUSES: [&str; 4] = ["PASS", "FAIL", "SKIP", "NOT RUN"];
+
 // TODO: move to api model layer
-/*
-Metadata ideas:
-- test env (staging, prod, etc)
-- triggering system (jenkins, github, etc)
-- robot project git hash
-- project git hash
-- jenkins URL / build number for linking back
- */
 #[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct RobotTestRunMetadata {
-    #[serde(rename = "appName")]
     pub app_name: String,
-    #[serde(rename = "appVersion")]
     pub app_version: String,
+    /// e.g. `staging` or `prod`.
+    pub environment: Option<String>,
+    /// Branch of the application under test, e.g. `main`.
+    pub branch: Option<String>,
+    #[serde(default)]
+    pub ci: RobotCiMetadata,
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+}
+
+/// The CI build that ran the tests, to link back to it.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RobotCiMetadata {
+    /// e.g. `jenkins` or `github`.
+    pub system: Option<String>,
+    pub build_url: Option<String>,
+    /// A number for most CI systems, taken as text.
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub build_number: Option<String>,
+    pub robot_git_hash: Option<String>,
+    pub app_git_hash: Option<String>,
+}
+
+fn string_or_number<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<serde_json::Value>::deserialize(deserializer)? {
+        None | Some(serde_json::Value::Null) => Ok(None),
+        Some(serde_json::Value::String(value)) => Ok(Some(value)),
+        Some(serde_json::Value::Number(value)) => Ok(Some(value.to_string())),
+        Some(value) => Err(de::Error::custom(format!(
+            "invalid build number {}, expected a string or a number",
+            value
+        ))),
+    }
 }
 
 #[derive(Debug, MultipartForm)]
 pub struct RobotOuputUploadForm {
+    #[multipart(rename = "file", limit = "1000MB")]
+    pub files: Vec<TempFile>,
+    pub metadata: MpJson<RobotTestRunMetadata>,
+}
+
+#[derive(Debug, MultipartForm)]
+pub struct RobotRerunUploadForm {
     #[multipart(limit = "1000MB")]
     pub file: TempFile,
-    pub metadata: MpJson<RobotTestRunMetadata>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportJobsQuery {
+    #[serde(rename = "projectId")]
+    pub project_id: i32,
+    pub limit: Option<i64>,
+}
+
+/// `cursor` takes the `nextCursor` of the previous page.
+#[derive(Debug, Deserialize)]
+pub struct TestHistoryQuery {
+    pub status: Option<String>,
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+impl TestHistoryQuery {
+    pub fn to_request(&self) -> Result<TestHistoryRequest, String> {
+        let status = match &self.status {
+            Some(status) if !TEST_STATUSES.contains(&status.as_str()) => {
+                return Err(format!(
+                    "Invalid status {}, expected one of {}",
+                    status,
+                    TEST_STATUSES.join(", ")
+                ))
+            }
+            status => status.clone(),
+        };
+        let cursor = match &self.cursor {
+            Some(cursor) => {
+                Some(PageCursor::parse(cursor).ok_or(format!("Invalid cursor {}", cursor))?)
+            }
+            None => None,
+        };
+        Ok(TestHistoryRequest {
+            status,
+            cursor,
+            limit: self
+                .limit
+                .unwrap_or(DEFAULT_TEST_HISTORY_LIMIT)
+                .clamp(1, MAX_TEST_HISTORY_LIMIT),
+        })
+    }
+}
+
+/// `cursor` takes the `nextCursor` of the previous page.
+#[derive(Debug, Deserialize)]
+pub struct SuiteTestsQuery {
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+impl SuiteTestsQuery {
+    pub fn to_request(&self) -> Result<SuiteTestsRequest, String> {
+        let cursor = match &self.cursor {
+            Some(cursor) => {
+                Some(PageCursor::parse(cursor).ok_or(format!("Invalid cursor {}", cursor))?)
+            }
+            None => None,
+        };
+        Ok(SuiteTestsRequest {
+            cursor,
+            limit: self
+                .limit
+                .unwrap_or(DEFAULT_SUITE_TESTS_LIMIT)
+                .clamp(1, MAX_SUITE_TESTS_LIMIT),
+        })
+    }
+}
+
+/// `from` and `to` take a day or a date and time.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchQuery {
+    pub q: String,
+    pub project_id: Option<i32>,
+    pub test_run_id: Option<i32>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub limit: Option<i64>,
+}
+
+impl SearchQuery {
+    pub fn to_request(&self) -> Result<SearchRequest, String> {
+        if self.q.trim().is_empty() {
+            return Err("Missing search text".to_string());
+        }
+        let date = |value: &Option<String>, end_of_day: bool| match value {
+            Some(value) => utils::date::parse_datetime(value, end_of_day)
+                .map(Some)
+                .ok_or(format!(
+                    "Invalid date {}, expected YYYY-MM-DD or YYYY-MM-DDTHH:MM:SS",
+                    value
+                )),
+            None => Ok(None),
+        };
+        Ok(SearchRequest {
+            text: self.q.clone(),
+            project_id: self.project_id,
+            test_run_id: self.test_run_id,
+            from: date(&self.from, false)?,
+            to: date(&self.to, true)?,
+            limit: self
+                .limit
+                .unwrap_or(DEFAULT_SEARCH_LIMIT)
+                .clamp(1, MAX_SEARCH_LIMIT),
+        })
+    }
 }
 
 pub struct RobotHandler {
     robot_service: Arc<RobotService>,
     projects_service: Arc<ProjectsService>,
     robot_output_parser_service: Arc<RobotOutputParserService>,
+    imports_service: Arc<ImportService>,
 }
 
 impl RobotHandler {
@@ -48,11 +213,13 @@ impl RobotHandler {
         robot_service: Arc<RobotService>,
         projects_service: Arc<ProjectsService>,
         robot_output_parser_service: Arc<RobotOutputParserService>,
+        imports_service: Arc<ImportService>,
     ) -> Self {
         RobotHandler {
             robot_service,
             projects_service,
             robot_output_parser_service,
+            imports_service,
         }
     }
 
@@ -61,9 +228,14 @@ impl RobotHandler {
         robot_service: Arc<RobotService>,
         projects_service: Arc<ProjectsService>,
         robot_output_parser_service: Arc<RobotOutputParserService>,
+        imports_service: Arc<ImportService>,
     ) {
-        let handler =
-            RobotHandler::new(robot_service, projects_service, robot_output_parser_service);
+        let handler = RobotHandler::new(
+            robot_service,
+            projects_service,
+            robot_output_parser_service,
+            imports_service,
+        );
         cfg.service(handler.routes());
     }
 
@@ -72,7 +244,31 @@ impl RobotHandler {
             .app_data(web::Data::new(self.robot_service.clone()))
             .app_data(web::Data::new(self.projects_service.clone()))
             .app_data(web::Data::new(self.robot_output_parser_service.clone()))
+            .app_data(web::Data::new(self.imports_service.clone()))
             .route("/test-runs/{id}", web::get().to(Self::get_test_run))
+            .route(
+                "/test-runs/{id}/suites",
+                web::get().to(Self::get_root_suites),
+            )
+            .route(
+                "/test-runs/{id}/failures",
+                web::get().to(Self::get_failure_groups),
+            )
+            .route(
+                "/test-runs/{base_id}/compare/{target_id}",
+                web::get().to(Self::compare_test_runs),
+            )
+            .route(
+                "/test-runs/{id}/reruns",
+                web::post().to(Self::upload_rerun_output),
+            )
+            .route(
+                "/test-runs/{id}/artifacts/{path:.*}",
+                web::get().to(Self::get_test_run_artifact),
+            )
+            .route("/outputs/{sha1}", web::get().to(Self::get_output_file))
+            .route("/suites/{id}/suites", web::get().to(Self::get_child_suites))
+            .route("/suites/{id}/tests", web::get().to(Self::get_suite_tests))
             .route(
                 "/suites/{id}/keywords",
                 web::get().to(Self::get_suite_keywords),
@@ -81,9 +277,19 @@ impl RobotHandler {
                 "/tests/{id}/keywords",
                 web::get().to(Self::get_test_keywords),
             )
+            .route(
+                "/tests/{id}/attempts",
+                web::get().to(Self::get_test_attempts),
+            )
+            .route("/tests/{id}/history", web::get().to(Self::get_test_history))
+            .route("/search", web::get().to(Self::search))
             .route("/upload", web::post().to(Self::upload_robot_output))
+            .route("/imports", web::get().to(Self::get_import_jobs))
+            .route("/imports/{job_id}", web::get().to(Self::get_import_job))
     }
 
+    /// The whole suite tree with every test, large runs are better loaded
+    /// level by level from `/test-runs/{id}/suites`.
     async fn get_test_run(
         robot_service: web::Data<Arc<RobotService>>,
         test_run_id: web::Path<i32>,
@@ -102,6 +308,154 @@ impl RobotHandler {
         }
     }
 
+    /// The root suites of the run with the counts of the tests under them,
+    /// their children come from `/suites/{id}/suites`.
+    async fn get_root_suites(
+        robot_service: web::Data<Arc<RobotService>>,
+        test_run_id: web::Path<i32>,
+    ) -> Result<HttpResponse, Error> {
+        let suites = robot_service
+            .get_root_suites_by_test_run_id(test_run_id.into_inner())
+            .await;
+
+        match suites {
+            Ok(Some(suites)) => Ok(HttpResponse::Ok().json(
+                suites
+                    .iter()
+                    .map(|suite| suite.to_api())
+                    .collect::<Vec<_>>(),
+            )),
+            Ok(None) => Ok(HttpResponse::NotFound().finish()),
+            Err(e) => {
+                error!("Error getting root suites: {:?}", e);
+                Ok(HttpResponse::InternalServerError().finish())
+            }
+        }
+    }
+
+    async fn get_child_suites(
+        robot_service: web::Data<Arc<RobotService>>,
+        suite_id: web::Path<i32>,
+    ) -> Result<HttpResponse, Error> {
+        let suites = robot_service
+            .get_child_suites_by_suite_id(suite_id.into_inner())
+            .await;
+
+        match suites {
+            Ok(Some(suites)) => Ok(HttpResponse::Ok().json(
+                suites
+                    .iter()
+                    .map(|suite| suite.to_api())
+                    .collect::<Vec<_>>(),
+            )),
+            Ok(None) => Ok(HttpResponse::NotFound().finish()),
+            Err(e) => {
+                error!("Error getting child suites: {:?}", e);
+                Ok(HttpResponse::InternalServerError().finish())
+            }
+        }
+    }
+
+    /// The tests of the suite itself in the order they ran, e.g.
+    /// `?limit=200&cursor=...`.
+    async fn get_suite_tests(
+        robot_service: web::Data<Arc<RobotService>>,
+        suite_id: web::Path<i32>,
+        query: web::Query<SuiteTestsQuery>,
+    ) -> Result<HttpResponse, Error> {
+        let request = match query.to_request() {
+            Ok(request) => request,
+            Err(message) => {
+                return Ok(HttpResponse::BadRequest().json(json!({ "error": message })));
+            }
+        };
+        let page = robot_service
+            .get_tests_page_by_suite_id(suite_id.into_inner(), &request)
+            .await;
+
+        match page {
+            Ok(Some(page)) => Ok(HttpResponse::Ok().json(page.to_api())),
+            Ok(None) => Ok(HttpResponse::NotFound().finish()),
+            Err(e) => {
+                error!("Error getting suite tests: {:?}", e);
+                Ok(HttpResponse::InternalServerError().finish())
+            }
+        }
+    }
+
+    /// Tests, keywords and log messages of all runs, e.g.
+    /// `?q="HTTP 502"&projectId=1&from=2025-04-01`.
+    async fn search(
+        robot_service: web::Data<Arc<RobotService>>,
+        query: web::Query<SearchQuery>,
+    ) -> Result<HttpResponse, Error> {
+        let request = match query.to_request() {
+            Ok(request) => request,
+            Err(message) => {
+                return Ok(HttpResponse::BadRequest().json(json!({ "error": message })));
+            }
+        };
+
+        match robot_service.search(&request).await {
+            Ok(matches) => Ok(HttpResponse::Ok().json(
+                matches
+                    .iter()
+                    .map(|found| found.to_api())
+                    .collect::<Vec<_>>(),
+            )),
+            Err(e) => {
+                error!("Error searching: {:?}", e);
+                Ok(HttpResponse::InternalServerError().finish())
+            }
+        }
+    }
+
+    /// The failed tests of the run grouped by the signature of their message,
+    /// to triage causes rather than tests.
+    async fn get_failure_groups(
+        robot_service: web::Data<Arc<RobotService>>,
+        test_run_id: web::Path<i32>,
+    ) -> Result<HttpResponse, Error> {
+        let groups = robot_service
+            .get_failure_groups_by_test_run_id(test_run_id.into_inner())
+            .await;
+
+        match groups {
+            Ok(Some(groups)) => Ok(HttpResponse::Ok().json(
+                groups
+                    .iter()
+                    .map(|group| group.to_api())
+                    .collect::<Vec<_>>(),
+            )),
+            Ok(None) => Ok(HttpResponse::NotFound().finish()),
+            Err(e) => {
+                error!("Error getting failure groups: {:?}", e);
+                Ok(HttpResponse::InternalServerError().finish())
+            }
+        }
+    }
+
+    /// What changed from the base run to the target run, e.g. from
+    /// yesterday's nightly to today's.
+    async fn compare_test_runs(
+        robot_service: web::Data<Arc<RobotService>>,
+        path: web::Path<(i32, i32)>,
+    ) -> Result<HttpResponse, Error> {
+        let (base_test_run_id, target_test_run_id) = path.into_inner();
+        let comparison = robot_service
+            .compare_test_runs(base_test_run_id, target_test_run_id)
+            .await;
+
+        match comparison {
+            Ok(Some(comparison)) => Ok(HttpResponse::Ok().json(comparison.to_api())),
+            Ok(None) => Ok(HttpResponse::NotFound().finish()),
+            Err(e) => {
+                error!("Error comparing test runs: {:?}", e);
+                Ok(HttpResponse::InternalServerError().finish())
+            }
+        }
+    }
+
     async fn get_suite_keywords(
         robot_service: web::Data<Arc<RobotService>>,
         suite_id: web::Path<i32>,
@@ -129,7 +483,7 @@ impl RobotHandler {
             .await;
 
         match keywords {
-            Ok(Some(keywords)) => Ok(HttpResponse::Ok().json(keywords)),
+            Ok(Some(keywords)) => Ok(HttpResponse::Ok().json(keywords.to_api())),
             Ok(None) => Ok(HttpResponse::NotFound().finish()),
             Err(e) => {
                 error!("Error getting keywords: {:?}", e);
@@ -138,16 +492,190 @@ impl RobotHandler {
         }
     }
 
+    async fn get_test_attempts(
+        robot_service: web::Data<Arc<RobotService>>,
+        test_id: web::Path<i32>,
+    ) -> Result<HttpResponse, Error> {
+        let attempts = robot_service
+            .get_test_attempts_by_test_id(test_id.into_inner())
+            .await;
+
+        match attempts {
+            Ok(attempts) if attempts.is_empty() => Ok(HttpResponse::NotFound().finish()),
+            Ok(attempts) => Ok(HttpResponse::Ok().json(
+                attempts
+                    .iter()
+                    .map(|attempt| attempt.to_api())
+                    .collect::<Vec<_>>(),
+            )),
+            Err(e) => {
+                error!("Error getting test attempts: {:?}", e);
+                Ok(HttpResponse::InternalServerError().finish())
+            }
+        }
+    }
+
+    /// The outcomes of the test in every run of its project, matched by the
+    /// path of its suite and its name. `?status=PASS&limit=1` tells when it
+    /// last passed.
+    async fn get_test_history(
+        robot_service: web::Data<Arc<RobotService>>,
+        test_id: web::Path<i32>,
+        query: web::Query<TestHistoryQuery>,
+    ) -> Result<HttpResponse, Error> {
+        let request = match query.to_request() {
+            Ok(request) => request,
+            Err(message) => {
+                return Ok(HttpResponse::BadRequest().json(json!({ "error": message })));
+            }
+        };
+        let history = robot_service
+            .get_test_history_by_test_id(test_id.into_inner(), &request)
+            .await;
+
+        match history {
+            Ok(Some(history)) => Ok(HttpResponse::Ok().json(history.to_api())),
+            Ok(None) => Ok(HttpResponse::NotFound().finish()),
+            Err(e) => {
+                error!("Error getting test history: {:?}", e);
+                Ok(HttpResponse::InternalServerError().finish())
+            }
+        }
+    }
+
+    /// The output of `--rerunfailed` replaces the results of the tests it
+    /// contains, like `rebot --merge`.
+    async fn upload_rerun_output(
+        MultipartForm(form): MultipartForm<RobotRerunUploadForm>,
+        test_run_id: web::Path<i32>,
+        robot_service: web::Data<Arc<RobotService>>,
+        robot_output_parser_service: web::Data<Arc<RobotOutputParserService>>,
+    ) -> Result<HttpResponse, Error> {
+        let test_run_id = test_run_id.into_inner();
+        let file_name = form.file.file_name.clone().unwrap_or_default();
+        info!(
+            "Processing rerun of test run {}: {}",
+            test_run_id, file_name
+        );
+
+        let events = match robot_output_parser_service
+            .stream_file(file_name.clone(), form.file.file.path())
+        {
+            Ok(events) => events,
+            Err(e) => return Ok(output_file_error(&file_name, &e)),
+        };
+
+        let result = robot_service
+            .save_rerun(
+                test_run_id,
+                OutputPart {
+                    file_name: file_name.clone(),
+                    file: form.file.file.path().to_path_buf(),
+                    events,
+                },
+            )
+            .await;
+        if let Some(errors) = result.as_ref().err().and_then(|e| e.downcast_ref()) {
+            return Ok(invalid_output_file(errors));
+        }
+
+        match result {
+            Ok(true) => Ok(HttpResponse::Ok().finish()),
+            Ok(false) => Ok(HttpResponse::NotFound().finish()),
+            Err(e) => match e.downcast_ref::<ParserError>() {
+                Some(e) => Ok(output_file_error(&file_name, e)),
+                None => {
+                    let error_message = format!("Failed to merge rerun: {}", e);
+                    error!("{}", error_message);
+                    Ok(HttpResponse::InternalServerError().json(json!({
+                        "error": error_message
+                    })))
+                }
+            },
+        }
+    }
+
+    /// Artifacts come from whoever uploaded the run: HTML and SVG files are
+    /// sandboxed so that their scripts cannot act on behalf of the viewer.
+    async fn get_test_run_artifact(
+        robot_service: web::Data<Arc<RobotService>>,
+        path: web::Path<(i32, String)>,
+    ) -> Result<HttpResponse, Error> {
+        let (test_run_id, path) = path.into_inner();
+        let artifact = robot_service
+            .get_test_run_artifact(test_run_id, &path)
+            .await;
+
+        match artifact {
+            Ok(Some(artifact)) => Ok(HttpResponse::Ok()
+                .content_type(artifact.content_type)
+                .insert_header((header::CONTENT_SECURITY_POLICY, "sandbox"))
+                .insert_header((header::X_CONTENT_TYPE_OPTIONS, "nosniff"))
+                .no_chunking(artifact.size as u64)
+                .streaming(artifact.content.map_ok(web::Bytes::from))),
+            Ok(None) => Ok(HttpResponse::NotFound().finish()),
+            Err(e) => {
+                error!("Error getting artifact: {:?}", e);
+                Ok(HttpResponse::InternalServerError().finish())
+            }
+        }
+    }
+
+    /// Downloads an uploaded output file, found by the SHA-1 listed in the
+    /// parts of its test run.
+    async fn get_output_file(
+        robot_service: web::Data<Arc<RobotService>>,
+        sha1: web::Path<String>,
+    ) -> Result<HttpResponse, Error> {
+        let output_file = robot_service.get_output_file(&sha1.into_inner()).await;
+
+        match output_file {
+            Ok(Some(output_file)) => {
+                let file_name = output_file
+                    .file_name
+                    .as_deref()
+                    .and_then(|file_name| file_name.rsplit('/').next())
+                    .unwrap_or("output");
+                Ok(HttpResponse::Ok()
+                    .content_type(
+                        mime_guess::from_path(file_name)
+                            .first_or_octet_stream()
+                            .to_string(),
+                    )
+                    .insert_header(ContentDisposition::attachment(file_name))
+                    .no_chunking(output_file.size as u64)
+                    .streaming(output_file.content.map_ok(web::Bytes::from)))
+            }
+            Ok(None) => Ok(HttpResponse::NotFound().finish()),
+            Err(e) => {
+                error!("Error getting output file: {:?}", e);
+                Ok(HttpResponse::InternalServerError().finish())
+            }
+        }
+    }
+
+    /// Several `file` fields are combined into one test run, e.g. the outputs
+    /// of pabot shards, or come in a `.zip` or `.tar.gz` archive. The upload is
+    /// imported in the background, the response gives the job to follow.
     async fn upload_robot_output(
         MultipartForm(form): MultipartForm<RobotOuputUploadForm>,
-        robot_service: web::Data<Arc<RobotService>>,
         projects_service: web::Data<Arc<ProjectsService>>,
-        robot_output_parser_service: web::Data<Arc<RobotOutputParserService>>,
+        imports_service: web::Data<Arc<ImportService>>,
     ) -> Result<HttpResponse, Error> {
-        let file_name = form.file.file_name.unwrap_or_default();
+        let files: Vec<UploadedFile> = form
+            .files
+            .into_iter()
+            .map(|file| UploadedFile {
+                file_name: file.file_name.unwrap_or_default(),
+                file: file.file,
+            })
+            .collect();
+        let file_names: Vec<&str> = files.iter().map(|file| file.file_name.as_str()).collect();
         info!(
             "Processing upload: {} [{} - {}]",
-            file_name, form.metadata.app_name, form.metadata.app_version
+            file_names.join(", "),
+            form.metadata.app_name,
+            form.metadata.app_version
         );
         if form.metadata.app_name.is_empty() {
             error!("Missing appName");
@@ -155,47 +683,132 @@ impl RobotHandler {
                 "error": "Missing appName"
             })));
         }
+        if files.is_empty() {
+            error!("Missing file");
+            return Ok(HttpResponse::BadRequest().json(json!({
+                "error": "Missing file"
+            })));
+        }
 
-        let file_path = form.file.file.path();
-
-        // TODO: check sha1 uniqueness BEFORE parsing...
-        match robot_output_parser_service.from_file(file_name, file_path) {
-            Ok(test_run) => {
-                let metadata = services::robot::TestRunMetadata {
-                    app_name: form.metadata.app_name.clone(),
-                    app_version: form.metadata.app_version.clone(),
-                };
-
-                let project_id = projects_service
-                    .get_or_create_project_by_name(form.metadata.app_name.as_str())
-                    .await?;
-
-                match robot_service
-                    .save_test_run(test_run, metadata, project_id)
-                    .await
-                {
-                    Ok(_) => Ok(HttpResponse::Ok().finish()),
-                    Err(e) => {
-                        let error_message = format!("Failed to save test run: {}", e);
-                        error!("{}", error_message);
-                        Ok(HttpResponse::InternalServerError().json(json!({
-                            "error": error_message
-                        })))
-                    }
-                }
-            }
-            Err(ParserError::InvalidFileExtension(message)) => {
-                error!("Invalid file extension: {}", message);
-                Ok(HttpResponse::BadRequest().json(json!({
-                    "error": "Invalid file extension, expected XML"
+        let form_metadata = form.metadata.into_inner();
+        let metadata = services::robot::TestRunMetadata {
+            app_name: form_metadata.app_name.clone(),
+            app_version: form_metadata.app_version,
+            environment: non_empty(form_metadata.environment),
+            branch: non_empty(form_metadata.branch),
+            ci: CiMetadata {
+                system: non_empty(form_metadata.ci.system),
+                build_url: non_empty(form_metadata.ci.build_url),
+                build_number: non_empty(form_metadata.ci.build_number),
+                robot_git_hash: non_empty(form_metadata.ci.robot_git_hash),
+                app_git_hash: non_empty(form_metadata.ci.app_git_hash),
+            },
+            labels: form_metadata.labels.into_iter().collect(),
+        };
+
+        let project_id = projects_service
+            .get_or_create_project_by_name(metadata.app_name.as_str())
+            .await?;
+
+        match imports_service.enqueue(project_id, files, metadata).await {
+            Ok(job_id) => Ok(HttpResponse::Accepted()
+                .insert_header((header::LOCATION, format!("/api/robot/imports/{}", job_id)))
+                .json(json!({
+                    "jobId": job_id,
+                    "status": "queued"
+                }))),
+            Err(ImportError::QueueFull) => {
+                error!("Import queue is full");
+                Ok(HttpResponse::ServiceUnavailable().json(json!({
+                    "error": "Import queue is full, try again later"
                 })))
             }
             Err(e) => {
-                error!("Failed to process XML: {}", e);
-                Ok(HttpResponse::InternalServerError().json(json!({
-                    "error": "Failed to process XML file"
-                })))
+                error!("Error queuing import: {:?}", e);
+                Ok(HttpResponse::InternalServerError().finish())
             }
         }
     }
+
+    async fn get_import_job(
+        imports_service: web::Data<Arc<ImportService>>,
+        job_id: web::Path<i32>,
+    ) -> Result<HttpResponse, Error> {
+        let job = imports_service.get_import_job(job_id.into_inner()).await;
+
+        match job {
+            Ok(Some(job)) => Ok(HttpResponse::Ok().json(job.to_api())),
+            Ok(None) => Ok(HttpResponse::NotFound().finish()),
+            Err(e) => {
+                error!("Error getting import job: {:?}", e);
+                Ok(HttpResponse::InternalServerError().finish())
+            }
+        }
+    }
+
+    /// Latest import jobs of a project first.
+    async fn get_import_jobs(
+        imports_service: web::Data<Arc<ImportService>>,
+        query: web::Query<ImportJobsQuery>,
+    ) -> Result<HttpResponse, Error> {
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_IMPORT_JOBS_LIMIT)
+            .clamp(1, MAX_IMPORT_JOBS_LIMIT);
+        let jobs = imports_service
+            .get_import_jobs_by_project_id(query.project_id, limit)
+            .await;
+
+        match jobs {
+            Ok(jobs) => Ok(
+                HttpResponse::Ok().json(jobs.iter().map(|job| job.to_api()).collect::<Vec<_>>())
+            ),
+            Err(e) => {
+                error!("Error getting import jobs: {:?}", e);
+                Ok(HttpResponse::InternalServerError().finish())
+            }
+        }
+    }
+}
+
+/// Blank values are left out, e.g. unset CI variables.
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|value| !value.trim().is_empty())
+}
+
+/// Lists the values of the output file that cannot be stored, e.g. malformed
+/// timestamps, for the client to fix them.
+fn invalid_output_file(errors: &MappingErrors) -> HttpResponse {
+    error!("{}", errors);
+    HttpResponse::UnprocessableEntity().json(json!({
+        "error": "Invalid output file",
+        "problems": errors.0
+    }))
+}
+
+/// A file that can't be parsed is the client's error, reading it or the
+/// parser giving up is not.
+fn output_file_error(file_name: &str, e: &ParserError) -> HttpResponse {
+    match e {
+        ParserError::UnsupportedFormat(message) => {
+            error!("Unsupported output file {}: {}", file_name, message);
+            HttpResponse::BadRequest().json(json!({
+                "error": services::imports::unsupported_file(file_name)
+            }))
+        }
+        ParserError::Io(_)
+        | ParserError::Xml(quick_xml::Error::Io(_))
+        | ParserError::Interrupted => {
+            error!("Failed to process output file: {}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "error": "Failed to process output file"
+            }))
+        }
+        e => {
+            error!("Invalid output file {}: {}", file_name, e);
+            HttpResponse::UnprocessableEntity().json(json!({
+                "error": format!("Invalid output file {}: {}", file_name, e)
+            }))
+        }
+    }
 }
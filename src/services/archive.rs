@@ -0,0 +1,478 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, Read, Seek},
+    path::{Component, Path, PathBuf},
+};
+
+use flate2::{bufread::GzEncoder, read::GzDecoder, Compression};
+use tempfile::{NamedTempFile, TempDir};
+use thiserror::Error;
+use tracing::warn;
+
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+const GZIP_MAGIC: &[u8] = b"\x1f\x8b";
+const TAR_BLOCK_SIZE: usize = 512;
+
+/// Archive uploaded in place of an output file, told apart from its first
+/// bytes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArchiveFormat {
+    Zip,
+    TarGz,
+}
+
+impl ArchiveFormat {
+    /// `None` when the file is not an archive, most likely an output file. A
+    /// gzipped file is only taken for a tarball when it starts with a tar
+    /// header once decompressed, e.g. not a gzipped `output.xml`.
+    pub fn detect(path: &Path) -> Result<Option<Self>, ArchiveError> {
+        let mut magic = Vec::with_capacity(ZIP_MAGIC.len());
+        File::open(path)?
+            .take(ZIP_MAGIC.len() as u64)
+            .read_to_end(&mut magic)?;
+        if magic.starts_with(ZIP_MAGIC) {
+            Ok(Some(ArchiveFormat::Zip))
+        } else if magic.starts_with(GZIP_MAGIC) {
+            let mut block = Vec::with_capacity(TAR_BLOCK_SIZE);
+            GzDecoder::new(File::open(path)?)
+                .take(TAR_BLOCK_SIZE as u64)
+                .read_to_end(&mut block)?;
+            if block.len() < TAR_BLOCK_SIZE {
+                return Err(ArchiveError::NotTar);
+            }
+            let header = tar::Header::from_byte_slice(&block);
+            if header.as_ustar().is_none() && header.as_gnu().is_none() {
+                return Err(ArchiveError::NotTar);
+            }
+            Ok(Some(ArchiveFormat::TarGz))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ArchiveError {
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+    #[error("Zip error: {0}")]
+    Zip(#[from] zip::result::ZipError),
+    #[error("No output file found in archive")]
+    NoOutput,
+    #[error("Gzip file is not a tar archive, upload the output file itself or a .tar.gz")]
+    NotTar,
+    #[error("More than {0} files in archive")]
+    TooManyEntries(usize),
+    #[error("Archive larger than {0} bytes once extracted")]
+    TooLarge(u64),
+}
+
+/// Bounds of an extracted archive, so that an archive crafted to expand
+/// enormously cannot fill the disk.
+#[derive(Debug, Clone, Copy)]
+pub struct ArchiveLimits {
+    /// Total size of the extracted files, in bytes.
+    pub max_size: u64,
+    /// Files and directories of the archive.
+    pub max_entries: usize,
+}
+
+/// A file of the archive that is not an output file, e.g. `log.html` or a
+/// screenshot. The path is relative to the directory of the output files,
+/// the way Robot Framework links them from messages.
+pub struct Artifact {
+    pub path: String,
+    pub file: PathBuf,
+}
+
+/// Content of an archive, extracted to a temporary directory removed on drop.
+pub struct ExtractedArchive {
+    _dir: TempDir,
+    pub outputs: Vec<(String, PathBuf)>,
+    pub artifacts: Vec<Artifact>,
+}
+
+/// Extracts the archive and picks its output files: those named like Robot
+/// Framework names them (`output*.xml`, `output*.json`) or, failing that, any
+/// XML or JSON file. Only the least nested ones are kept, so the merged
+/// `output.xml` of pabot wins over the outputs of its shards.
+pub fn extract(
+    path: &Path,
+    format: ArchiveFormat,
+    limits: ArchiveLimits,
+) -> Result<ExtractedArchive, ArchiveError> {
+    let dir = tempfile::tempdir()?;
+    let file = BufReader::new(File::open(path)?);
+    match format {
+        ArchiveFormat::Zip => extract_zip(file, dir.path(), limits)?,
+        ArchiveFormat::TarGz => extract_tar_gz(file, dir.path(), limits)?,
+    }
+
+    let mut files = Vec::new();
+    list_files(dir.path(), String::new(), &mut files)?;
+    files.sort();
+
+    let named_outputs: Vec<&String> = files
+        .iter()
+        .filter(|path| is_output(path) && file_name(path).starts_with("output"))
+        .collect();
+    let candidates = if named_outputs.is_empty() {
+        files.iter().filter(|path| is_output(path)).collect()
+    } else {
+        named_outputs
+    };
+    let depth = candidates
+        .iter()
+        .map(|path| path.matches('/').count())
+        .min()
+        .ok_or(ArchiveError::NoOutput)?;
+    let outputs: Vec<String> = candidates
+        .into_iter()
+        .filter(|path| path.matches('/').count() == depth)
+        .cloned()
+        .collect();
+
+    let base = common_directory(&outputs);
+    let artifacts = files
+        .iter()
+        .filter(|path| !outputs.contains(path))
+        .map(|path| Artifact {
+            path: path.strip_prefix(&base).unwrap_or(path).to_string(),
+            file: dir.path().join(path),
+        })
+        .collect();
+    let outputs = outputs
+        .into_iter()
+        .map(|path| {
+            let file = dir.path().join(&path);
+            (path, file)
+        })
+        .collect();
+
+    Ok(ExtractedArchive {
+        _dir: dir,
+        outputs,
+        artifacts,
+    })
+}
+
+/// Only files and directories are extracted. Links could point anywhere on
+/// the server, and what they point to would then be stored as an artifact.
+fn extract_zip(
+    file: impl Read + Seek,
+    dir: &Path,
+    limits: ArchiveLimits,
+) -> Result<(), ArchiveError> {
+    let mut archive = zip::ZipArchive::new(file)?;
+    let mut extraction = Extraction::new(dir, limits);
+    for index in 0..archive.len() {
+        extraction.count_entry()?;
+        let mut entry = archive.by_index(index)?;
+        let Some(path) = entry.enclosed_name() else {
+            warn!(
+                "Skipping archive entry {} outside of the archive",
+                entry.name()
+            );
+            continue;
+        };
+        if entry.is_dir() {
+            extraction.create_dir(&path)?;
+        } else if entry.is_file() {
+            extraction.write_file(&path, &mut entry)?;
+        } else {
+            warn!("Skipping archive entry {}, not a file", entry.name());
+        }
+    }
+    Ok(())
+}
+
+/// Like [`extract_zip`], symbolic and hard links are skipped.
+fn extract_tar_gz(file: impl Read, dir: &Path, limits: ArchiveLimits) -> Result<(), ArchiveError> {
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+    let mut extraction = Extraction::new(dir, limits);
+    for entry in archive.entries()? {
+        extraction.count_entry()?;
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        let Some(enclosed_path) = enclosed_path(&path) else {
+            warn!(
+                "Skipping archive entry {} outside of the archive",
+                path.display()
+            );
+            continue;
+        };
+        let entry_type = entry.header().entry_type();
+        if entry_type.is_dir() {
+            extraction.create_dir(&enclosed_path)?;
+        } else if entry_type.is_file() {
+            extraction.write_file(&enclosed_path, &mut entry)?;
+        } else {
+            warn!("Skipping archive entry {}, not a file", path.display());
+        }
+    }
+    Ok(())
+}
+
+/// Counts what is extracted against the limits. Sizes are counted as the
+/// files are written, whatever size the archive claims for them.
+struct Extraction<'a> {
+    dir: &'a Path,
+    limits: ArchiveLimits,
+    entries: usize,
+    size: u64,
+}
+
+impl<'a> Extraction<'a> {
+    fn new(dir: &'a Path, limits: ArchiveLimits) -> Self {
+        Extraction {
+            dir,
+            limits,
+            entries: 0,
+            size: 0,
+        }
+    }
+
+    fn count_entry(&mut self) -> Result<(), ArchiveError> {
+        self.entries += 1;
+        if self.entries > self.limits.max_entries {
+            return Err(ArchiveError::TooManyEntries(self.limits.max_entries));
+        }
+        Ok(())
+    }
+
+    fn create_dir(&self, path: &Path) -> Result<(), ArchiveError> {
+        fs::create_dir_all(self.dir.join(path))?;
+        Ok(())
+    }
+
+    fn write_file(&mut self, path: &Path, content: &mut impl Read) -> Result<(), ArchiveError> {
+        let target = self.dir.join(path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let remaining = self.limits.max_size - self.size;
+        let written = io::copy(
+            &mut content.take(remaining + 1),
+            &mut File::create(&target)?,
+        )?;
+        if written > remaining {
+            return Err(ArchiveError::TooLarge(self.limits.max_size));
+        }
+        self.size += written;
+        Ok(())
+    }
+}
+
+/// The path without its `.` parts, `None` when it is absolute or goes up.
+fn enclosed_path(path: &Path) -> Option<PathBuf> {
+    let mut enclosed = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => enclosed.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (!enclosed.as_os_str().is_empty()).then_some(enclosed)
+}
+
+/// Gzipped copy of an uploaded output file, kept to import it again later.
+/// It is written to a temporary file, removed on drop.
+pub fn compress_file(path: &Path) -> io::Result<NamedTempFile> {
+    let mut compressed = NamedTempFile::new()?;
+    io::copy(
+        &mut GzEncoder::new(BufReader::new(File::open(path)?), Compression::default()),
+        &mut compressed,
+    )?;
+    Ok(compressed)
+}
+
+/// Reads back what [`compress_file`] wrote.
+pub fn decompress(content: impl Read) -> impl Read {
+    GzDecoder::new(content)
+}
+
+/// Paths relative to the archive root, with `/` separators. Anything that
+/// is not a plain file is left out.
+fn list_files(dir: &Path, prefix: String, files: &mut Vec<String>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = format!("{}{}", prefix, entry.file_name().to_string_lossy());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            list_files(&entry.path(), format!("{}/", path), files)?;
+        } else if file_type.is_file() {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+fn is_output(path: &str) -> bool {
+    let path = path.to_lowercase();
+    path.ends_with(".xml") || path.ends_with(".json")
+}
+
+/// `results/` when all the outputs are in `results`, nothing otherwise.
+fn common_directory(outputs: &[String]) -> String {
+    let directories: Vec<&str> = outputs
+        .iter()
+        .map(|path| match path.rsplit_once('/') {
+            Some((directory, _)) => directory,
+            None => "",
+        })
+        .collect();
+    match directories.split_first() {
+        Some((first, others)) if !first.is_empty() && others.iter().all(|dir| dir == first) => {
+            format!("{}/", first)
+        }
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use flate2::{write::GzEncoder, Compression};
+
+    use super::*;
+
+    const LIMITS: ArchiveLimits = ArchiveLimits {
+        max_size: 1024,
+        max_entries: 10,
+    };
+
+    fn tar_gz(files: &[(&str, &str)]) -> tempfile::NamedTempFile {
+        let archive = tempfile::NamedTempFile::new().unwrap();
+        let mut builder = tar::Builder::new(GzEncoder::new(
+            archive.reopen().unwrap(),
+            Compression::default(),
+        ));
+        for (path, content) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            builder
+                .append_data(&mut header, path, content.as_bytes())
+                .unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+        archive
+    }
+
+    #[test]
+    fn test_extract_keeps_least_nested_outputs() {
+        let archive = tar_gz(&[
+            ("results/output.xml", "<robot/>"),
+            ("results/log.html", "<html/>"),
+            ("results/selenium-screenshot-1.png", "png"),
+            ("results/pabot_results/0/output.xml", "<robot/>"),
+        ]);
+        assert_eq!(
+            ArchiveFormat::detect(archive.path()).unwrap(),
+            Some(ArchiveFormat::TarGz)
+        );
+
+        let extracted = extract(archive.path(), ArchiveFormat::TarGz, LIMITS).unwrap();
+        let outputs: Vec<&str> = extracted
+            .outputs
+            .iter()
+            .map(|(path, _)| path.as_str())
+            .collect();
+        assert_eq!(outputs, vec!["results/output.xml"]);
+        let artifacts: Vec<&str> = extracted
+            .artifacts
+            .iter()
+            .map(|artifact| artifact.path.as_str())
+            .collect();
+        assert_eq!(
+            artifacts,
+            vec![
+                "log.html",
+                "pabot_results/0/output.xml",
+                "selenium-screenshot-1.png"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_detect_gzip_without_tar() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let mut encoder = GzEncoder::new(file.reopen().unwrap(), Compression::default());
+        io::Write::write_all(&mut encoder, "<robot/>".repeat(100).as_bytes()).unwrap();
+        encoder.finish().unwrap();
+        assert!(matches!(
+            ArchiveFormat::detect(file.path()),
+            Err(ArchiveError::NotTar)
+        ));
+    }
+
+    #[test]
+    fn test_extract_skips_links() {
+        let archive = tempfile::NamedTempFile::new().unwrap();
+        let mut builder = tar::Builder::new(GzEncoder::new(
+            archive.reopen().unwrap(),
+            Compression::default(),
+        ));
+        let mut header = tar::Header::new_gnu();
+        header.set_size(8);
+        header.set_mode(0o644);
+        builder
+            .append_data(&mut header, "output.xml", "<robot/>".as_bytes())
+            .unwrap();
+        for (entry_type, path) in [
+            (tar::EntryType::Symlink, "log.png"),
+            (tar::EntryType::Link, "report.html"),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_entry_type(entry_type);
+            header.set_size(0);
+            builder
+                .append_link(&mut header, path, "/proc/self/environ")
+                .unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let extracted = extract(archive.path(), ArchiveFormat::TarGz, LIMITS).unwrap();
+        assert_eq!(extracted.outputs.len(), 1);
+        assert!(extracted.artifacts.is_empty());
+        let dir = extracted.outputs[0].1.parent().unwrap();
+        assert!(!dir.join("log.png").exists());
+        assert!(!dir.join("report.html").exists());
+    }
+
+    #[test]
+    fn test_extract_limits() {
+        let content = "x".repeat(600);
+        let archive = tar_gz(&[("output.xml", &content), ("log.html", &content)]);
+        assert!(matches!(
+            extract(archive.path(), ArchiveFormat::TarGz, LIMITS),
+            Err(ArchiveError::TooLarge(1024))
+        ));
+
+        let files: Vec<(String, &str)> = (0..11)
+            .map(|index| (format!("output{}.xml", index), "<robot/>"))
+            .collect();
+        let files: Vec<(&str, &str)> = files
+            .iter()
+            .map(|(path, content)| (path.as_str(), *content))
+            .collect();
+        let archive = tar_gz(&files);
+        assert!(matches!(
+            extract(archive.path(), ArchiveFormat::TarGz, LIMITS),
+            Err(ArchiveError::TooManyEntries(10))
+        ));
+    }
+
+    #[test]
+    fn test_extract_without_output() {
+        let archive = tar_gz(&[("log.html", "<html/>")]);
+        assert!(matches!(
+            extract(archive.path(), ArchiveFormat::TarGz, LIMITS),
+            Err(ArchiveError::NoOutput)
+        ));
+    }
+}
@@ -0,0 +1,395 @@
+use std::{path::PathBuf, sync::Arc};
+
+use actix_rt::Arbiter;
+
+use tempfile::NamedTempFile;
+use thiserror::Error;
+use tokio::sync::{mpsc, Mutex};
+use tracing::{error, info};
+
+use crate::{
+    config::imports::ImportsConfig,
+    models::imports::domain::{ImportJob, ImportStatus},
+    repositories::imports::ImportsRepository,
+};
+
+use super::{
+    archive::{self, ArchiveFormat, ArchiveLimits},
+    parser::{ParserError, RobotOutputParserService},
+    robot::{ImportedTestRun, MappingErrors, OutputPart, RobotService, TestRunMetadata},
+};
+
+const INTERRUPTED_JOB_ERROR: &str = "Interrupted by a server restart";
+
+/// A file of an upload, removed once its import is over.
+pub struct UploadedFile {
+    pub file_name: String,
+    pub file: NamedTempFile,
+}
+
+struct QueuedImport {
+    job_id: i32,
+    work: ImportWork,
+}
+
+enum ImportWork {
+    Upload {
+        project_id: i32,
+        files: Vec<UploadedFile>,
+        metadata: Box<TestRunMetadata>,
+    },
+    /// A stored test run parsed again from its output files.
+    Reimport { test_run_id: i32 },
+}
+
+#[derive(Error, Debug)]
+pub enum ImportError {
+    #[error("Import queue is full")]
+    QueueFull,
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("{0}")]
+    InvalidUpload(String),
+}
+
+/// Queues uploads and imports them in the background, so that the request
+/// returns before the output files are parsed. The queue and the number of
+/// uploads imported at the same time are bounded. Test runs imported again
+/// share the workers but wait in a queue of their own, taken only when no
+/// upload is waiting.
+pub struct ImportService {
+    repository: Arc<ImportsRepository>,
+    sender: mpsc::Sender<QueuedImport>,
+    reimport_sender: mpsc::UnboundedSender<QueuedImport>,
+}
+
+struct ImportWorker {
+    repository: Arc<ImportsRepository>,
+    robot_service: Arc<RobotService>,
+    robot_output_parser_service: Arc<RobotOutputParserService>,
+    archive_limits: ArchiveLimits,
+}
+
+impl ImportService {
+    /// Starts each worker on a thread of its own, so that the mapping and
+    /// writing of several imports run in parallel; parsing itself runs on the
+    /// blocking pool. Jobs left unfinished by a previous run are failed, their
+    /// files went with it.
+    pub async fn start(
+        repository: ImportsRepository,
+        robot_service: Arc<RobotService>,
+        robot_output_parser_service: Arc<RobotOutputParserService>,
+        config: &ImportsConfig,
+    ) -> Result<Self, sqlx::Error> {
+        let interrupted = repository
+            .fail_unfinished_import_jobs(INTERRUPTED_JOB_ERROR)
+            .await?;
+        if interrupted > 0 {
+            info!(
+                "Failed {} import jobs interrupted by a restart",
+                interrupted
+            );
+        }
+
+        let repository = Arc::new(repository);
+        let worker = Arc::new(ImportWorker {
+            repository: Arc::clone(&repository),
+            robot_service,
+            robot_output_parser_service,
+            archive_limits: ArchiveLimits {
+                max_size: config.archive_max_size,
+                max_entries: config.archive_max_entries,
+            },
+        });
+        let (sender, receiver) = mpsc::channel(config.queue_size);
+        let receiver = Arc::new(Mutex::new(receiver));
+        // Only ids wait in it, it is left unbounded.
+        let (reimport_sender, reimport_receiver) = mpsc::unbounded_channel();
+        let reimport_receiver = Arc::new(Mutex::new(reimport_receiver));
+        for _ in 0..config.workers {
+            let worker = Arc::clone(&worker);
+            let receiver = Arc::clone(&receiver);
+            let reimport_receiver = Arc::clone(&reimport_receiver);
+            // Only the closure crosses to the thread, the loop is not `Send`.
+            Arbiter::new().spawn_fn(move || {
+                actix_rt::spawn(async move {
+                    loop {
+                        let queued = tokio::select! {
+                            biased;
+                            queued = async { receiver.lock().await.recv().await } => queued,
+                            queued = async { reimport_receiver.lock().await.recv().await } => queued,
+                        };
+                        match queued {
+                            Some(queued) => worker.run(queued).await,
+                            None => break,
+                        }
+                    }
+                });
+            });
+        }
+
+        Ok(Self {
+            repository,
+            sender,
+            reimport_sender,
+        })
+    }
+
+    /// Returns the id of the job, to follow the import.
+    pub async fn enqueue(
+        &self,
+        project_id: i32,
+        files: Vec<UploadedFile>,
+        metadata: TestRunMetadata,
+    ) -> Result<i32, ImportError> {
+        let permit = self
+            .sender
+            .try_reserve()
+            .map_err(|_| ImportError::QueueFull)?;
+        let file_names: Vec<String> = files.iter().map(|file| file.file_name.clone()).collect();
+        let job_id = self
+            .repository
+            .insert_import_job(project_id, &file_names)
+            .await?;
+        info!("Queued import job {}: {}", job_id, file_names.join(", "));
+        permit.send(QueuedImport {
+            job_id,
+            work: ImportWork::Upload {
+                project_id,
+                files,
+                metadata: Box::new(metadata),
+            },
+        });
+        Ok(job_id)
+    }
+
+    /// Queues one job for each test run of the project, or of all projects,
+    /// or for the one test run, to import it again from its stored output
+    /// files. Returns the ids of the jobs, none when there is no such run.
+    pub async fn enqueue_reimports(
+        &self,
+        project_id: Option<i32>,
+        test_run_id: Option<i32>,
+    ) -> Result<Vec<i32>, ImportError> {
+        let jobs = self
+            .repository
+            .insert_reimport_jobs(project_id, test_run_id)
+            .await?;
+        info!("Queued {} reimport jobs", jobs.len());
+        for &(job_id, test_run_id) in &jobs {
+            // Sending only fails once the workers are stopped, with the server.
+            let _ = self.reimport_sender.send(QueuedImport {
+                job_id,
+                work: ImportWork::Reimport { test_run_id },
+            });
+        }
+        Ok(jobs.into_iter().map(|(job_id, _)| job_id).collect())
+    }
+
+    pub async fn get_import_job(&self, job_id: i32) -> Result<Option<ImportJob>, sqlx::Error> {
+        self.repository.get_import_job_by_id(job_id).await
+    }
+
+    /// Latest jobs first.
+    pub async fn get_import_jobs_by_project_id(
+        &self,
+        project_id: i32,
+        limit: i64,
+    ) -> Result<Vec<ImportJob>, sqlx::Error> {
+        self.repository
+            .get_import_jobs_by_project_id(project_id, limit)
+            .await
+    }
+}
+
+impl ImportWorker {
+    async fn run(&self, queued: QueuedImport) {
+        let job_id = queued.job_id;
+        info!("Starting import job {}", job_id);
+        let result = match queued.work {
+            ImportWork::Upload {
+                project_id,
+                files,
+                metadata,
+            } => self.import(job_id, project_id, files, *metadata).await,
+            ImportWork::Reimport { test_run_id } => self.reimport(job_id, test_run_id).await,
+        };
+
+        let outcome = match result {
+            Ok(ImportedTestRun::Created(test_run_id)) => Ok((test_run_id, false)),
+            Ok(ImportedTestRun::Existing(test_run_id)) => Ok((test_run_id, true)),
+            Err(e) => Err(failure(e.as_ref())),
+        };
+        let result = match outcome {
+            Ok((test_run_id, already_imported)) => {
+                info!("Import job {} done, test run {}", job_id, test_run_id);
+                self.repository
+                    .finish_import_job(job_id, test_run_id, already_imported)
+                    .await
+            }
+            Err((message, problems)) => {
+                error!("Import job {} failed: {}", job_id, message);
+                self.repository
+                    .fail_import_job(job_id, &message, problems)
+                    .await
+            }
+        };
+        if let Err(e) = result {
+            error!(
+                "Failed to record the outcome of import job {}: {}",
+                job_id, e
+            );
+        }
+    }
+
+    /// Several files are combined into one test run, e.g. the outputs of
+    /// pabot shards. A `.zip` or `.tar.gz` archive brings its output files and
+    /// the files next to them, kept as artifacts of the test run.
+    async fn import(
+        &self,
+        job_id: i32,
+        project_id: i32,
+        files: Vec<UploadedFile>,
+        metadata: TestRunMetadata,
+    ) -> Result<ImportedTestRun, Box<dyn std::error::Error>> {
+        self.repository
+            .update_import_job_status(job_id, ImportStatus::Parsing)
+            .await?;
+
+        // Extracted archives are removed once the test run is saved.
+        let mut archives = Vec::new();
+        let mut outputs: Vec<(String, PathBuf)> = Vec::new();
+        let mut artifacts = Vec::new();
+        for file in &files {
+            let path = file.file.path().to_path_buf();
+            let limits = self.archive_limits;
+            let archive =
+                tokio::task::spawn_blocking(move || match ArchiveFormat::detect(&path)? {
+                    Some(format) => archive::extract(&path, format, limits).map(Some),
+                    None => Ok(None),
+                })
+                .await?;
+            match archive {
+                Ok(Some(mut archive)) => {
+                    info!(
+                        "Found {} output files and {} artifacts in {}",
+                        archive.outputs.len(),
+                        archive.artifacts.len(),
+                        file.file_name
+                    );
+                    for (path, output) in archive.outputs.drain(..) {
+                        outputs.push((format!("{}/{}", file.file_name, path), output));
+                    }
+                    artifacts.append(&mut archive.artifacts);
+                    archives.push(archive);
+                }
+                Ok(None) => outputs.push((file.file_name.clone(), file.file.path().to_path_buf())),
+                Err(e) => Err(ImportError::InvalidUpload(format!(
+                    "Failed to read archive {}: {}",
+                    file.file_name, e
+                )))?,
+            }
+        }
+
+        let mut parts = Vec::new();
+        for (file_name, path) in outputs {
+            match self
+                .robot_output_parser_service
+                .stream_file(file_name.clone(), &path)
+            {
+                Ok(events) => parts.push(OutputPart {
+                    file_name,
+                    file: path,
+                    events,
+                }),
+                Err(ParserError::UnsupportedFormat(message)) => {
+                    error!("Unsupported output file {}: {}", file_name, message);
+                    Err(ImportError::InvalidUpload(unsupported_file(&file_name)))?
+                }
+                Err(e) => Err(e)?,
+            }
+        }
+
+        let saving = async {
+            let result = self
+                .repository
+                .update_import_job_status(job_id, ImportStatus::Saving)
+                .await;
+            if let Err(e) = result {
+                error!("Failed to update import job {}: {}", job_id, e);
+            }
+        };
+        self.robot_service
+            .save_test_run(parts, artifacts, metadata, project_id, saving)
+            .await
+    }
+
+    /// The test run keeps its id, the stored output files are parsed and
+    /// saved in one go.
+    async fn reimport(
+        &self,
+        job_id: i32,
+        test_run_id: i32,
+    ) -> Result<ImportedTestRun, Box<dyn std::error::Error>> {
+        self.repository
+            .update_import_job_status(job_id, ImportStatus::Parsing)
+            .await?;
+        let reimported = self
+            .robot_service
+            .reimport_test_run(test_run_id, &self.robot_output_parser_service)
+            .await?;
+        if !reimported {
+            Err(format!("Test run {} no longer exists", test_run_id))?;
+        }
+        Ok(ImportedTestRun::Created(test_run_id))
+    }
+}
+
+pub fn unsupported_file(file_name: &str) -> String {
+    format!(
+        "Unsupported file {}, expected a Robot Framework XML or JSON output or an archive",
+        file_name
+    )
+}
+
+/// The message of a failed job, with the values of the output file that
+/// cannot be stored when that is why.
+fn failure(e: &(dyn std::error::Error + 'static)) -> (String, Option<serde_json::Value>) {
+    if let Some(errors) = e.downcast_ref::<MappingErrors>() {
+        let problems = serde_json::to_value(&errors.0).ok();
+        return ("Invalid output file".to_string(), problems);
+    }
+    if let Some(ImportError::InvalidUpload(message)) = e.downcast_ref() {
+        return (message.clone(), None);
+    }
+    if e.is::<ParserError>() {
+        return (format!("Failed to process output file: {}", e), None);
+    }
+    (format!("Failed to save test run: {}", e), None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failure_keeps_mapping_problems() {
+        let errors: Box<dyn std::error::Error> = Box::new(MappingErrors(vec![]));
+        let (message, problems) = failure(errors.as_ref());
+        assert_eq!(message, "Invalid output file");
+        assert_eq!(problems, Some(serde_json::json!([])));
+
+        let rejected: Box<dyn std::error::Error> =
+            Box::new(ImportError::InvalidUpload(unsupported_file("report.html")));
+        let (message, problems) = failure(rejected.as_ref());
+        assert_eq!(
+            message,
+            "Unsupported file report.html, expected a Robot Framework XML or JSON output or an archive"
+        );
+        assert_eq!(problems, None);
+
+        let (message, _) =
+            failure(Box::<dyn std::error::Error>::from("Missing root suite").as_ref());
+        assert_eq!(message, "Failed to save test run: Missing root suite");
+    }
+}
@@ -1,95 +1,234 @@
-use chrono::{NaiveDateTime, ParseResult};
+use chrono::{Duration, NaiveDateTime};
+use itertools::Itertools;
+use serde::Serialize;
+use thiserror::Error;
 
+use crate::models::robot::db::SourceFormatDB;
 use crate::models::robot_legacy::{
     ErrorDBLegacy, StatDBLegacy, StatTypeDB, SuiteDBLegacy, TestDBLegacy, TestRunDBLegacy,
 };
 use crate::services::{self, parser};
 
+/// Layout of the timestamps of an output file, detected from its format and
+/// schema version.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimestampFormat {
+    /// Robot Framework 6 and older: `20241217 11:27:23.679`, with `starttime`/`endtime`.
+    Legacy,
+    /// Robot Framework 7 and newer (schema 5): ISO 8601 `start` plus `elapsed` seconds.
+    Iso,
+}
+
+impl TimestampFormat {
+    pub fn of(test_run: &parser::TestRun) -> Self {
+        if test_run.format != parser::OutputFormat::Xml {
+            return TimestampFormat::Iso;
+        }
+        match test_run.schema_version.parse::<u32>() {
+            Ok(version) if version >= 5 => TimestampFormat::Iso,
+            _ => TimestampFormat::Legacy,
+        }
+    }
+
+    fn pattern(&self) -> &'static str {
+        match self {
+            TimestampFormat::Legacy => "%Y%m%d %H:%M:%S%.3f",
+            TimestampFormat::Iso => "%Y-%m-%dT%H:%M:%S%.f",
+        }
+    }
+}
+
+/// A value of an output file that cannot be stored. `path` locates it from
+/// the id of its element, e.g. `s1-s2-t3/status@starttime`.
+#[derive(Error, Debug, Clone, PartialEq, Serialize)]
+#[error("{path}: {reason}")]
+pub struct MappingError {
+    pub path: String,
+    pub reason: String,
+}
+
+impl MappingError {
+    fn new(path: String, reason: impl Into<String>) -> Self {
+        Self {
+            path,
+            reason: reason.into(),
+        }
+    }
+}
+
+/// The problems of an output file, all of them rather than the first one.
+#[derive(Error, Debug, Default, PartialEq)]
+#[error("Invalid output file: {}", .0.iter().join(", "))]
+pub struct MappingErrors(pub Vec<MappingError>);
+
+impl MappingErrors {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Keeps the problems of `result`, if any, and returns its value otherwise.
+    pub fn take<T>(&mut self, result: Result<T, impl Into<MappingErrors>>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(errors) => {
+                self.0.extend(errors.into().0);
+                None
+            }
+        }
+    }
+}
+
+impl From<MappingError> for MappingErrors {
+    fn from(error: MappingError) -> Self {
+        MappingErrors(vec![error])
+    }
+}
+
 pub fn map_test_run(
     test_run: &parser::TestRun,
+    sha1: &str,
     metadata: &services::robot::TestRunMetadata,
-) -> Result<TestRunDBLegacy, chrono::ParseError> {
+) -> Result<TestRunDBLegacy, MappingErrors> {
     Ok(TestRunDBLegacy {
         id: None,
         imported_date: None,
         rpa: test_run.rpa,
         generator: test_run.generator.clone(),
-        generated_date: map_timestamp(&test_run.generated_date.clone())?,
+        generated_date: map_timestamp(
+            "robot@generated",
+            Some(&test_run.generated_date),
+            TimestampFormat::of(test_run),
+        )?,
         schema_version: test_run.schema_version.clone(),
-        sha1: test_run.sha1.clone(),
+        source_format: map_source_format(test_run.format),
+        sha1: sha1.to_string(),
         app_name: metadata.app_name.clone(),
         app_version: metadata.app_version.clone(),
-        suites: map_suites(&test_run.suites),
-        statistics: map_statistics(&test_run.statistics),
-        errors: map_errors(&test_run.errors),
+        environment: metadata.environment.clone(),
+        branch: metadata.branch.clone(),
+        ci: metadata.ci.clone(),
+        labels: metadata.labels.clone(),
     })
 }
 
-fn map_suites(suites: &Vec<parser::Suite>) -> Vec<SuiteDBLegacy> {
-    suites.iter().map(|suite| map_suite(suite)).collect()
+/// Header of outputs combined into one run, dated from the earliest of them.
+pub fn map_combined_test_run(
+    test_runs: &[parser::TestRun],
+    sha1: &str,
+    metadata: &services::robot::TestRunMetadata,
+) -> Result<TestRunDBLegacy, MappingErrors> {
+    let mut combined = map_test_run(&test_runs[0], sha1, metadata)?;
+    for test_run in &test_runs[1..] {
+        let generated_date = map_timestamp(
+            "robot@generated",
+            Some(&test_run.generated_date),
+            TimestampFormat::of(test_run),
+        )?;
+        combined.generated_date = combined.generated_date.min(generated_date);
+    }
+    Ok(combined)
 }
 
-fn map_suite(suite: &parser::Suite) -> SuiteDBLegacy {
-    let setup_keyword = match &suite.children.first() {
-        Some(parser::SuiteChildren::Keyword(keyword)) => Some(keyword.clone()),
-        _ => None,
-    };
-    let teardown_keyword = if suite.children.len() > 1 {
-        match &suite.children.last() {
-            Some(parser::SuiteChildren::Keyword(keyword)) => Some(keyword.clone()),
-            _ => None,
-        }
+fn map_source_format(format: parser::OutputFormat) -> SourceFormatDB {
+    match format {
+        parser::OutputFormat::Xml => SourceFormatDB::RobotXml,
+        parser::OutputFormat::Json => SourceFormatDB::RobotJson,
+        parser::OutputFormat::Junit => SourceFormatDB::Junit,
+    }
+}
+
+pub fn map_suite(
+    suite_start: &parser::SuiteStart,
+    suite_end: parser::SuiteEnd,
+    format: TimestampFormat,
+) -> Result<SuiteDBLegacy, MappingErrors> {
+    let (start_time, end_time) = map_status_times(&suite_start.id, &suite_end.status, format)?;
+    Ok(SuiteDBLegacy {
+        id: None,
+        name: suite_start.name.clone(),
+        source: suite_start.source_file.clone(),
+        status: suite_end.status.status.clone(),
+        message: suite_end.status.message.clone(),
+        start_time,
+        end_time,
+        identifier: suite_start.id.clone(),
+        doc: suite_end.doc,
+        metadata: suite_end.metadata,
+        setup_keyword: suite_end.setup_keyword,
+        teardown_keyword: suite_end.teardown_keyword,
+    })
+}
+
+/// Root suite of combined outputs, like `rebot output1.xml output2.xml` creates:
+/// named after the suites it contains and spanning from the earliest start to
+/// the latest end.
+pub fn map_combined_suite(suites: &[SuiteDBLegacy]) -> SuiteDBLegacy {
+    let status = if suites.iter().any(|suite| suite.status == "FAIL") {
+        "FAIL"
+    } else if suites.iter().any(|suite| suite.status == "PASS") {
+        "PASS"
     } else {
-        None
+        "SKIP"
     };
-
     SuiteDBLegacy {
         id: None,
-        name: suite.name.clone(),
-        source: suite.source_file.clone(),
-        status: suite.status.status.clone(),
-        start_time: map_timestamp(&suite.status.start_time).unwrap(),
-        end_time: map_timestamp(&suite.status.end_time).unwrap(),
-        identifier: suite.id.clone(),
-        doc: suite.doc.clone(),
-        setup_keyword,
-        suites: suite
-            .children
+        name: suites.iter().map(|suite| suite.name.as_str()).join(" & "),
+        source: None,
+        status: status.to_string(),
+        message: None,
+        start_time: suites
             .iter()
-            .filter_map(|child| match child {
-                parser::SuiteChildren::Suite(suite) => Some(map_suite(suite)),
-                _ => None,
-            })
-            .collect(),
-        tests: suite
-            .children
+            .map(|suite| suite.start_time)
+            .min()
+            .unwrap_or_default(),
+        end_time: suites
             .iter()
-            .filter_map(|child| match child {
-                parser::SuiteChildren::Test(test) => Some(map_test(test)),
-                _ => None,
-            })
-            .collect(),
-        teardown_keyword,
+            .map(|suite| suite.end_time)
+            .max()
+            .unwrap_or_default(),
+        doc: None,
+        identifier: "s1".to_string(),
+        metadata: Vec::new(),
+        setup_keyword: None,
+        teardown_keyword: None,
     }
 }
 
-fn map_test(test: &parser::Test) -> TestDBLegacy {
-    TestDBLegacy {
+pub fn map_test(
+    test: parser::Test,
+    format: TimestampFormat,
+) -> Result<TestDBLegacy, MappingErrors> {
+    let mut errors = MappingErrors::default();
+    let times = errors.take(map_status_times(&test.id, &test.status, format));
+    let line = errors.take(
+        test.line
+            .as_deref()
+            .map(|line| map_line(&test.id, line))
+            .transpose(),
+    );
+    let (Some((start_time, end_time)), Some(line)) = (times, line) else {
+        return Err(errors);
+    };
+    Ok(TestDBLegacy {
         id: None,
         name: test.name.clone(),
-        line: test.line.parse::<i32>().unwrap(),
+        line,
         identifier: test.id.clone(),
-        tags: test.tags.clone(),
+        tags: test.tags,
         status: test.status.status.clone(),
-        start_time: map_timestamp(&test.status.start_time).unwrap(),
-        end_time: map_timestamp(&test.status.end_time).unwrap(),
-        doc: test.doc.clone(),
-        timeout: test.timeout.clone(),
-        keywords: test.keywords.clone(),
-    }
+        message: test.status.message.clone(),
+        start_time,
+        end_time,
+        doc: test.doc,
+        timeout: test.timeout,
+        setup_keyword: test.setup_keyword,
+        keywords: test.keywords,
+        teardown_keyword: test.teardown_keyword,
+        attempt: 1,
+    })
 }
 
-fn map_statistics(statistics: &parser::Statistics) -> Vec<StatDBLegacy> {
+pub fn map_statistics(statistics: &parser::Statistics) -> Vec<StatDBLegacy> {
     let mut stats = Vec::new();
     stats.push(map_statistic(&statistics.total.stats, StatTypeDB::Total));
     stats.extend(
@@ -122,23 +261,303 @@ fn map_statistic(statistic: &parser::StatisticsTag, stat_type: StatTypeDB) -> St
     }
 }
 
-fn map_errors(errors: &parser::Errors) -> Vec<ErrorDBLegacy> {
-    errors
+pub fn map_errors(
+    errors: &parser::Errors,
+    format: TimestampFormat,
+) -> Result<Vec<ErrorDBLegacy>, MappingErrors> {
+    let mut mapping_errors = MappingErrors::default();
+    let errors: Vec<ErrorDBLegacy> = errors
         .messages
         .iter()
-        .map(|message| map_error(message))
-        .collect()
+        .enumerate()
+        .filter_map(|(index, error)| mapping_errors.take(map_error(index, error, format)))
+        .collect();
+    if mapping_errors.is_empty() {
+        Ok(errors)
+    } else {
+        Err(mapping_errors)
+    }
 }
 
-fn map_error(error: &parser::Message) -> ErrorDBLegacy {
-    ErrorDBLegacy {
+fn map_error(
+    index: usize,
+    error: &parser::Message,
+    format: TimestampFormat,
+) -> Result<ErrorDBLegacy, MappingError> {
+    let (timestamp, attribute) = match format {
+        TimestampFormat::Legacy => (&error.timestamp, "timestamp"),
+        TimestampFormat::Iso => (&error.time, "time"),
+    };
+    let path = format!("errors/msg[{}]@{}", index + 1, attribute);
+    Ok(ErrorDBLegacy {
         id: None,
-        timestamp: map_timestamp(&error.timestamp).unwrap(),
+        timestamp: map_timestamp(&path, timestamp.as_deref(), format)?,
         level: error.level.clone(),
         content: error.value.clone(),
-    }
+        html: error.html,
+    })
+}
+
+/// Start and end of an element. Schema 5 only records the start, the end is
+/// derived from the elapsed time.
+fn map_status_times(
+    id: &str,
+    status: &parser::Status,
+    format: TimestampFormat,
+) -> Result<(NaiveDateTime, NaiveDateTime), MappingErrors> {
+    let mut errors = MappingErrors::default();
+    let times = match format {
+        TimestampFormat::Legacy => {
+            let start_time = errors.take(map_timestamp(
+                &format!("{}/status@starttime", id),
+                status.start_time.as_deref(),
+                format,
+            ));
+            let end_time = errors.take(map_timestamp(
+                &format!("{}/status@endtime", id),
+                status.end_time.as_deref(),
+                format,
+            ));
+            start_time.zip(end_time)
+        }
+        TimestampFormat::Iso => {
+            let start_time = errors.take(map_timestamp(
+                &format!("{}/status@start", id),
+                status.start.as_deref(),
+                format,
+            ));
+            let elapsed = errors.take(
+                status
+                    .elapsed
+                    .as_deref()
+                    .map(|elapsed| map_elapsed(&format!("{}/status@elapsed", id), elapsed))
+                    .transpose(),
+            );
+            start_time
+                .zip(elapsed)
+                .map(|(start_time, elapsed)| (start_time, start_time + elapsed.unwrap_or_default()))
+        }
+    };
+    times.ok_or(errors)
+}
+
+fn map_timestamp(
+    path: &str,
+    timestamp: Option<&str>,
+    format: TimestampFormat,
+) -> Result<NaiveDateTime, MappingError> {
+    let timestamp =
+        timestamp.ok_or_else(|| MappingError::new(path.to_string(), "missing timestamp"))?;
+    NaiveDateTime::parse_from_str(timestamp, format.pattern()).map_err(|e| {
+        MappingError::new(
+            path.to_string(),
+            format!("invalid timestamp {:?}: {}", timestamp, e),
+        )
+    })
 }
 
-fn map_timestamp(timestamp: &str) -> ParseResult<NaiveDateTime> {
-    NaiveDateTime::parse_from_str(&timestamp, "%Y%m%d %H:%M:%S%.3f")
+fn map_elapsed(path: &str, elapsed: &str) -> Result<Duration, MappingError> {
+    let seconds = elapsed.parse::<f64>().map_err(|e| {
+        MappingError::new(
+            path.to_string(),
+            format!("invalid elapsed time {:?}: {}", elapsed, e),
+        )
+    })?;
+    Ok(Duration::microseconds(
+        (seconds * 1_000_000.0).round() as i64
+    ))
+}
+
+fn map_line(id: &str, line: &str) -> Result<i32, MappingError> {
+    line.parse::<i32>().map_err(|e| {
+        MappingError::new(
+            format!("{}@line", id),
+            format!("invalid line {:?}: {}", line, e),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+    use crate::services::parser::{OutputEvent, OutputSource, RobotOutputParserService};
+
+    fn parse_fixture(name: &str) -> Vec<OutputEvent> {
+        let file = File::open(format!("./src/services/resources/{}", name)).unwrap();
+        let mut events = Vec::new();
+        let source = OutputSource::new(file).unwrap();
+        RobotOutputParserService::new(false)
+            .parse(source, |event| {
+                events.push(event);
+                Ok(())
+            })
+            .unwrap();
+        events
+    }
+
+    fn timestamp(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f").unwrap()
+    }
+
+    /// Maps the run header, the first test and the root suite of a fixture.
+    fn map_fixture(name: &str) -> (TestRunDBLegacy, TestDBLegacy, SuiteDBLegacy, ErrorDBLegacy) {
+        let metadata = services::robot::TestRunMetadata {
+            app_name: "Shop".to_string(),
+            app_version: "1.0.0".to_string(),
+            ..Default::default()
+        };
+        let mut test_run = None;
+        let mut format = TimestampFormat::Legacy;
+        let mut suite_starts = Vec::new();
+        let mut tests = Vec::new();
+        let mut suites = Vec::new();
+        let mut errors = Vec::new();
+
+        for event in parse_fixture(name) {
+            match event {
+                OutputEvent::TestRun(header) => {
+                    format = TimestampFormat::of(&header);
+                    test_run = Some(map_test_run(&header, "sha1", &metadata).unwrap());
+                }
+                OutputEvent::SuiteStart(suite_start) => suite_starts.push(suite_start),
+                OutputEvent::Test(test) => tests.push(map_test(*test, format).unwrap()),
+                OutputEvent::SuiteEnd(suite_end) => {
+                    let suite_start = suite_starts.pop().unwrap();
+                    suites.push(map_suite(&suite_start, *suite_end, format).unwrap());
+                }
+                OutputEvent::Errors(parsed_errors) => {
+                    errors = map_errors(&parsed_errors, format).unwrap()
+                }
+                OutputEvent::Statistics(_) | OutputEvent::Finished { .. } => {}
+            }
+        }
+
+        (
+            test_run.unwrap(),
+            tests.remove(0),
+            suites.pop().unwrap(),
+            errors.remove(0),
+        )
+    }
+
+    #[test]
+    fn test_timestamp_format_from_schema_version() {
+        for (format, schema_version, expected) in [
+            (parser::OutputFormat::Xml, "2", TimestampFormat::Legacy),
+            (parser::OutputFormat::Xml, "3", TimestampFormat::Legacy),
+            (parser::OutputFormat::Xml, "4", TimestampFormat::Legacy),
+            (parser::OutputFormat::Xml, "5", TimestampFormat::Iso),
+            (parser::OutputFormat::Xml, "6", TimestampFormat::Iso),
+            (parser::OutputFormat::Json, "5", TimestampFormat::Iso),
+            (parser::OutputFormat::Junit, "", TimestampFormat::Iso),
+        ] {
+            let test_run = parser::TestRun {
+                format,
+                generator: "Robot".to_string(),
+                generated_date: String::new(),
+                rpa: false,
+                schema_version: schema_version.to_string(),
+            };
+            assert_eq!(TimestampFormat::of(&test_run), expected);
+        }
+    }
+
+    #[test]
+    fn test_map_legacy_timestamps() {
+        for (fixture, date, line) in [
+            ("output_rf4.xml", "2022-01-05", None),
+            ("output_rf5.xml", "2022-06-14", Some(8)),
+            ("output_rf6.xml", "2023-09-21", Some(8)),
+        ] {
+            let (test_run, test, suite, error) = map_fixture(fixture);
+
+            let at = |time: &str| timestamp(&format!("{} {}", date, time));
+            assert_eq!(test_run.generated_date, at("09:15:02.311"), "{}", fixture);
+            assert_eq!(test.line, line, "{}", fixture);
+            assert_eq!(test.start_time, at("09:15:02.356"), "{}", fixture);
+            assert_eq!(test.end_time, at("09:15:02.359"), "{}", fixture);
+            assert_eq!(suite.identifier, "s1", "{}", fixture);
+            assert_eq!(suite.start_time, at("09:15:02.312"), "{}", fixture);
+            assert_eq!(suite.end_time, at("09:15:02.370"), "{}", fixture);
+            assert_eq!(error.timestamp, at("09:15:02.320"), "{}", fixture);
+        }
+    }
+
+    #[test]
+    fn test_map_start_and_elapsed_timestamps() {
+        for fixture in ["output_rf7.xml", "output_rf7.json"] {
+            let (test_run, test, suite, error) = map_fixture(fixture);
+
+            assert_eq!(test_run.schema_version, "5", "{}", fixture);
+            assert_eq!(
+                test_run.generated_date,
+                timestamp("2024-12-17 11:27:23.676544"),
+                "{}",
+                fixture
+            );
+            assert_eq!(test.line, Some(8), "{}", fixture);
+            let start = timestamp("2024-12-17 11:27:23.696012");
+            assert_eq!(test.start_time, start, "{}", fixture);
+            let end = timestamp("2024-12-17 11:27:24.930012");
+            assert_eq!(test.end_time, end, "{}", fixture);
+            let start = timestamp("2024-12-17 11:27:23.678");
+            assert_eq!(suite.start_time, start, "{}", fixture);
+            let end = timestamp("2024-12-17 11:27:24.939100");
+            assert_eq!(suite.end_time, end, "{}", fixture);
+            let time = timestamp("2024-12-17 11:27:23.690001");
+            assert_eq!(error.timestamp, time, "{}", fixture);
+        }
+    }
+
+    #[test]
+    fn test_map_invalid_values() {
+        let test = parser::Test {
+            id: "s1-s2-t3".to_string(),
+            name: "Checkout".to_string(),
+            line: Some("eight".to_string()),
+            keywords: Vec::new(),
+            doc: None,
+            tags: Vec::new(),
+            timeout: None,
+            status: parser::Status {
+                status: "PASS".to_string(),
+                start_time: Some("20241217 11:27".to_string()),
+                end_time: None,
+                start: None,
+                elapsed: None,
+                message: None,
+            },
+            setup_keyword: None,
+            teardown_keyword: None,
+        };
+
+        let errors = map_test(test, TimestampFormat::Legacy).unwrap_err();
+
+        let paths: Vec<&str> = errors.0.iter().map(|error| error.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "s1-s2-t3/status@starttime",
+                "s1-s2-t3/status@endtime",
+                "s1-s2-t3@line"
+            ]
+        );
+        assert_eq!(errors.0[1].reason, "missing timestamp");
+    }
+
+    #[test]
+    fn test_map_combined_suite() {
+        let (_, _, legacy_suite, _) = map_fixture("output_rf6.xml");
+        let (_, _, suite, _) = map_fixture("output_rf7.xml");
+
+        let combined = map_combined_suite(&[suite, legacy_suite]);
+
+        assert_eq!(combined.name, "Shop & Shop");
+        assert_eq!(combined.identifier, "s1");
+        assert_eq!(combined.status, "FAIL");
+        assert_eq!(combined.start_time, timestamp("2023-09-21 09:15:02.312"));
+        assert_eq!(combined.end_time, timestamp("2024-12-17 11:27:24.939100"));
+    }
 }
@@ -1,3 +1,5 @@
+pub mod archive;
+pub mod imports;
 pub mod parser;
 pub mod projects;
 pub mod robot;
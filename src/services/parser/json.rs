@@ -0,0 +1,787 @@
+use std::{fmt, io::Read};
+
+use serde::{
+    de::{self, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+use serde_json::{Map, Value};
+
+use super::{
+    BaseBody, Break, Continue, Errors, For, ForIter, ForIterVar, Group, If, IfBranch,
+    InvalidSyntax, Keyword, Message, OutputEvent, OutputFormat, ParserError, Return, Statistics,
+    StatisticsSuites, StatisticsTag, StatisticsTags, StatisticsTotal, Status, SuiteEnd, SuiteStart,
+    Test, TestRun, Try, TryBranch, Var, While, WhileIter,
+};
+
+/// JSON results only exist since Robot Framework 7, whose timestamps follow
+/// the schema 5 conventions (ISO 8601 start plus elapsed seconds).
+const JSON_SCHEMA_VERSION: &str = "5";
+
+/// Reads a Robot Framework JSON result (`--output output.json`). Suites and
+/// tests are deserialized through seeds that emit each element as soon as it
+/// is complete, so like the XML parser only the open suites and the current
+/// test are held in memory. Suite and test ids are not part of the format and
+/// are derived from their position, the same way Robot Framework does.
+pub fn parse<R: Read>(
+    source: R,
+    tolerant: bool,
+    emit: &mut impl FnMut(OutputEvent) -> Result<(), ParserError>,
+) -> Result<(), ParserError> {
+    let mut context = Context {
+        emit,
+        error: None,
+        tolerant,
+    };
+    let mut deserializer = serde_json::Deserializer::from_reader(source);
+
+    let result = RootSeed {
+        context: &mut context,
+    }
+    .deserialize(&mut deserializer)
+    .and_then(|_| deserializer.end());
+
+    match (result, context.error) {
+        (_, Some(error)) => Err(error),
+        (Err(error), None) => Err(ParserError::from(error)),
+        (Ok(()), None) => Ok(()),
+    }
+}
+
+/// Shared by the nested seeds. Errors raised by `emit` are kept aside so they
+/// are not flattened into a `serde_json::Error`.
+struct Context<'e, F> {
+    emit: &'e mut F,
+    error: Option<ParserError>,
+    tolerant: bool,
+}
+
+impl<F: FnMut(OutputEvent) -> Result<(), ParserError>> Context<'_, F> {
+    fn emit<E: de::Error>(&mut self, event: OutputEvent) -> Result<(), E> {
+        (self.emit)(event).map_err(|error| {
+            let message = error.to_string();
+            self.error = Some(error);
+            E::custom(message)
+        })
+    }
+}
+
+struct RootSeed<'a, 'e, F> {
+    context: &'a mut Context<'e, F>,
+}
+
+impl<'de, F: FnMut(OutputEvent) -> Result<(), ParserError>> DeserializeSeed<'de>
+    for RootSeed<'_, '_, F>
+{
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, F: FnMut(OutputEvent) -> Result<(), ParserError>> Visitor<'de> for RootSeed<'_, '_, F> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a Robot Framework JSON result")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        let mut generator: Option<String> = None;
+        let mut generated: Option<String> = None;
+        let mut rpa = false;
+        let mut has_suite = false;
+
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "generator" => generator = Some(map.next_value()?),
+                "generated" => generated = Some(map.next_value()?),
+                "rpa" => rpa = map.next_value()?,
+                "suite" => {
+                    let test_run = TestRun {
+                        format: OutputFormat::Json,
+                        generator: generator
+                            .clone()
+                            .ok_or_else(|| de::Error::missing_field("generator"))?,
+                        generated_date: generated
+                            .clone()
+                            .ok_or_else(|| de::Error::missing_field("generated"))?,
+                        rpa,
+                        schema_version: JSON_SCHEMA_VERSION.to_string(),
+                    };
+                    self.context.emit(OutputEvent::TestRun(test_run))?;
+                    map.next_value_seed(SuiteSeed {
+                        context: &mut *self.context,
+                        id: "s1".to_string(),
+                    })?;
+                    has_suite = true;
+                }
+                "statistics" => {
+                    let statistics: JsonStatistics = map.next_value()?;
+                    self.context
+                        .emit(OutputEvent::Statistics(statistics.into_statistics()))?;
+                }
+                "errors" => {
+                    let messages: Vec<JsonMessage> = map.next_value()?;
+                    let errors = Errors {
+                        messages: messages
+                            .into_iter()
+                            .map(JsonMessage::into_message)
+                            .collect(),
+                    };
+                    self.context.emit(OutputEvent::Errors(errors))?;
+                }
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        if !has_suite {
+            return Err(de::Error::missing_field("suite"));
+        }
+        Ok(())
+    }
+}
+
+struct SuiteSeed<'a, 'e, F> {
+    context: &'a mut Context<'e, F>,
+    id: String,
+}
+
+impl<'de, F: FnMut(OutputEvent) -> Result<(), ParserError>> DeserializeSeed<'de>
+    for SuiteSeed<'_, '_, F>
+{
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, F: FnMut(OutputEvent) -> Result<(), ParserError>> Visitor<'de> for SuiteSeed<'_, '_, F> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a suite")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(mut self, mut map: A) -> Result<(), A::Error> {
+        let mut name: Option<String> = None;
+        let mut source: Option<String> = None;
+        let mut doc: Option<String> = None;
+        let mut metadata = JsonMetadata::default();
+        let mut setup_keyword = None;
+        let mut teardown_keyword = None;
+        let mut status = JsonStatus::default();
+        let mut started = false;
+
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "name" => name = Some(map.next_value()?),
+                "source" => source = map.next_value()?,
+                "doc" => doc = map.next_value()?,
+                "metadata" => metadata = map.next_value()?,
+                "setup" => {
+                    let keyword: JsonBodyItem = map.next_value()?;
+                    setup_keyword =
+                        Some(keyword.into_keyword(Some("SETUP"), self.context.tolerant)?);
+                }
+                "teardown" => {
+                    let keyword: JsonBodyItem = map.next_value()?;
+                    teardown_keyword =
+                        Some(keyword.into_keyword(Some("TEARDOWN"), self.context.tolerant)?);
+                }
+                "status" => status.status = map.next_value()?,
+                "start_time" => status.start_time = map.next_value()?,
+                "elapsed_time" => status.elapsed_time = map.next_value()?,
+                "message" => status.message = map.next_value()?,
+                "tests" | "suites" => {
+                    if !started {
+                        self.start_suite(&name, &source)?;
+                        started = true;
+                    }
+                    let children = ChildrenSeed {
+                        context: &mut *self.context,
+                        parent_id: &self.id,
+                        kind: if key == "tests" {
+                            ChildKind::Test
+                        } else {
+                            ChildKind::Suite
+                        },
+                    };
+                    map.next_value_seed(children)?;
+                }
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        if !started {
+            self.start_suite(&name, &source)?;
+        }
+
+        let suite_end = SuiteEnd {
+            status: status.into_status()?,
+            doc,
+            metadata: metadata.0,
+            setup_keyword,
+            teardown_keyword,
+        };
+        self.context
+            .emit(OutputEvent::SuiteEnd(Box::new(suite_end)))
+    }
+}
+
+impl<F: FnMut(OutputEvent) -> Result<(), ParserError>> SuiteSeed<'_, '_, F> {
+    fn start_suite<E: de::Error>(
+        &mut self,
+        name: &Option<String>,
+        source: &Option<String>,
+    ) -> Result<(), E> {
+        let suite_start = SuiteStart {
+            id: self.id.clone(),
+            name: name.clone().ok_or_else(|| {
+                E::custom(format!("suite {} has no name before its children", self.id))
+            })?,
+            source_file: source.clone(),
+        };
+        self.context.emit(OutputEvent::SuiteStart(suite_start))
+    }
+}
+
+enum ChildKind {
+    Suite,
+    Test,
+}
+
+struct ChildrenSeed<'a, 'e, 'p, F> {
+    context: &'a mut Context<'e, F>,
+    parent_id: &'p str,
+    kind: ChildKind,
+}
+
+impl<'de, F: FnMut(OutputEvent) -> Result<(), ParserError>> DeserializeSeed<'de>
+    for ChildrenSeed<'_, '_, '_, F>
+{
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, F: FnMut(OutputEvent) -> Result<(), ParserError>> Visitor<'de>
+    for ChildrenSeed<'_, '_, '_, F>
+{
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a list of suites or tests")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let mut index = 1;
+        loop {
+            match self.kind {
+                ChildKind::Suite => {
+                    let seed = SuiteSeed {
+                        context: &mut *self.context,
+                        id: format!("{}-s{}", self.parent_id, index),
+                    };
+                    if seq.next_element_seed(seed)?.is_none() {
+                        return Ok(());
+                    }
+                }
+                ChildKind::Test => {
+                    let Some(test) = seq.next_element::<JsonTest>()? else {
+                        return Ok(());
+                    };
+                    let id = format!("{}-t{}", self.parent_id, index);
+                    let test = test.into_test(id, self.context.tolerant)?;
+                    self.context.emit(OutputEvent::Test(Box::new(test)))?;
+                }
+            }
+            index += 1;
+        }
+    }
+}
+
+/// Suite metadata is an object, read as a list to keep the order of its entries.
+#[derive(Default)]
+struct JsonMetadata(Vec<(String, String)>);
+
+impl<'de> Deserialize<'de> for JsonMetadata {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct MetadataVisitor;
+
+        impl<'de> Visitor<'de> for MetadataVisitor {
+            type Value = JsonMetadata;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("suite metadata")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<JsonMetadata, A::Error> {
+                let mut metadata = Vec::new();
+                while let Some(entry) = map.next_entry()? {
+                    metadata.push(entry);
+                }
+                Ok(JsonMetadata(metadata))
+            }
+        }
+
+        deserializer.deserialize_map(MetadataVisitor)
+    }
+}
+
+#[derive(Deserialize, Serialize, Default)]
+struct JsonStatus {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    start_time: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    elapsed_time: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+}
+
+impl JsonStatus {
+    fn into_status<E: de::Error>(self) -> Result<Status, E> {
+        self.into_optional_status()
+            .ok_or_else(|| E::missing_field("status"))
+    }
+
+    /// Keywords that were not run (e.g. a skipped setup) may have no status.
+    fn into_optional_status(self) -> Option<Status> {
+        Some(Status {
+            status: self.status?,
+            start_time: None,
+            end_time: None,
+            start: self.start_time,
+            elapsed: self.elapsed_time.map(|elapsed| elapsed.to_string()),
+            message: self.message,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct JsonTest {
+    name: String,
+    #[serde(default)]
+    lineno: Option<i64>,
+    #[serde(default)]
+    doc: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    timeout: Option<String>,
+    #[serde(default)]
+    setup: Option<JsonBodyItem>,
+    #[serde(default)]
+    body: Vec<JsonBodyItem>,
+    #[serde(default)]
+    teardown: Option<JsonBodyItem>,
+    #[serde(flatten)]
+    status: JsonStatus,
+}
+
+impl JsonTest {
+    fn into_test<E: de::Error>(self, id: String, tolerant: bool) -> Result<Test, E> {
+        Ok(Test {
+            id,
+            name: self.name,
+            line: self.lineno.map(|lineno| lineno.to_string()),
+            keywords: into_body(self.body, tolerant)?,
+            doc: self.doc,
+            tags: self.tags,
+            timeout: self.timeout,
+            status: self.status.into_status()?,
+            setup_keyword: self
+                .setup
+                .map(|setup| setup.into_keyword(Some("SETUP"), tolerant))
+                .transpose()?,
+            teardown_keyword: self
+                .teardown
+                .map(|teardown| teardown.into_keyword(Some("TEARDOWN"), tolerant))
+                .transpose()?,
+        })
+    }
+}
+
+/// Any item of a `body` list. The `type` tells which of the fields are used,
+/// keywords have no type. Items of an unknown type are serialized back to
+/// JSON in tolerant mode, with the fields not listed here in `other`.
+#[derive(Deserialize, Serialize)]
+struct JsonBodyItem {
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    type_: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    owner: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    lineno: Option<i64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    args: Vec<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    assign: Option<Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    doc: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timeout: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    flavor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    start: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    mode: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    fill: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    values: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    condition: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    limit: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    on_limit: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    on_limit_message: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    patterns: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pattern_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    scope: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    separator: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    value: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    level: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    html: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timestamp: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    setup: Option<Box<JsonBodyItem>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    body: Vec<JsonBodyItem>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    teardown: Option<Box<JsonBodyItem>>,
+    #[serde(flatten)]
+    status: JsonStatus,
+    #[serde(flatten)]
+    other: Map<String, Value>,
+}
+
+impl JsonBodyItem {
+    fn into_base_body<E: de::Error>(self, tolerant: bool) -> Result<BaseBody, E> {
+        let base_body = match self.type_.as_deref() {
+            None | Some("KEYWORD") | Some("SETUP") | Some("TEARDOWN") => {
+                let type_ = self.type_.clone();
+                BaseBody::Keyword(self.into_keyword(type_.as_deref(), tolerant)?)
+            }
+            Some("FOR") => {
+                let (msg, iterations) = split_messages(self.body);
+                BaseBody::For(For {
+                    flavor: self.flavor.unwrap_or_else(|| "IN".to_string()),
+                    start: self.start,
+                    mode: self.mode,
+                    fill: self.fill,
+                    vars: value_to_strings(self.assign),
+                    values: self.values,
+                    msg,
+                    status: self.status.into_status()?,
+                    iters: iterations
+                        .into_iter()
+                        .map(|iteration| {
+                            Ok(ForIter {
+                                vars: assigned_values(iteration.assign),
+                                children: into_body(iteration.body, tolerant)?,
+                                status: iteration.status.into_status()?,
+                            })
+                        })
+                        .collect::<Result<_, E>>()?,
+                })
+            }
+            Some("WHILE") => {
+                let (msg, iterations) = split_messages(self.body);
+                BaseBody::While(While {
+                    condition: self.condition.into_iter().collect(),
+                    limit: self.limit.into_iter().collect(),
+                    on_limit: self.on_limit.into_iter().collect(),
+                    on_limit_message: self.on_limit_message.into_iter().collect(),
+                    msg,
+                    status: self.status.into_status()?,
+                    iters: iterations
+                        .into_iter()
+                        .map(|iteration| {
+                            Ok(WhileIter {
+                                children: into_body(iteration.body, tolerant)?,
+                                status: iteration.status.into_status()?,
+                            })
+                        })
+                        .collect::<Result<_, E>>()?,
+                })
+            }
+            Some("GROUP") => BaseBody::Group(Group {
+                name: self.name.unwrap_or_default(),
+                children: into_body(self.body, tolerant)?,
+                status: self.status.into_status()?,
+            }),
+            Some("IF/ELSE ROOT") => {
+                let (msg, branches) = split_messages(self.body);
+                BaseBody::If(If {
+                    msg,
+                    status: self.status.into_status()?,
+                    branches: branches
+                        .into_iter()
+                        .map(|branch| {
+                            Ok(IfBranch {
+                                type_: branch.type_.unwrap_or_default(),
+                                condition: branch.condition,
+                                children: into_body(branch.body, tolerant)?,
+                                status: branch.status.into_status()?,
+                            })
+                        })
+                        .collect::<Result<_, E>>()?,
+                })
+            }
+            Some("TRY/EXCEPT ROOT") => {
+                let (msg, branches) = split_messages(self.body);
+                BaseBody::Try(Try {
+                    msg,
+                    status: self.status.into_status()?,
+                    branches: branches
+                        .into_iter()
+                        .map(|branch| {
+                            Ok(TryBranch {
+                                type_: branch.type_.unwrap_or_default(),
+                                pattern_type: branch.pattern_type,
+                                assign: value_to_strings(branch.assign).into_iter().next(),
+                                patterns: branch.patterns,
+                                children: into_body(branch.body, tolerant)?,
+                                status: branch.status.into_status()?,
+                            })
+                        })
+                        .collect::<Result<_, E>>()?,
+                })
+            }
+            Some("VAR") => {
+                let (messages, _) = split_messages(self.body);
+                BaseBody::Var(Var {
+                    name: self.name.unwrap_or_default(),
+                    scope: self.scope,
+                    separator: self.separator,
+                    message: messages.into_iter().next(),
+                    vars: value_to_strings(self.value),
+                    status: self.status.into_status()?,
+                })
+            }
+            Some("RETURN") => BaseBody::Return(Return {
+                value: self.values,
+                msg: split_messages(self.body).0,
+                status: self.status.into_status()?,
+            }),
+            Some("CONTINUE") => BaseBody::Continue(Continue {
+                msg: split_messages(self.body).0,
+                status: self.status.into_status()?,
+            }),
+            Some("BREAK") => BaseBody::Break(Break {
+                msg: split_messages(self.body).0,
+                status: self.status.into_status()?,
+            }),
+            Some("ERROR") => BaseBody::Error(InvalidSyntax {
+                values: self.values,
+                msg: split_messages(self.body).0,
+                status: self.status.into_status()?,
+            }),
+            Some("MESSAGE") => BaseBody::Message(self.into_message()),
+            Some(_) if tolerant => {
+                BaseBody::Unknown(serde_json::to_value(&self).map_err(E::custom)?)
+            }
+            Some(other) => return Err(E::custom(format!("unsupported body item type {}", other))),
+        };
+        Ok(base_body)
+    }
+
+    /// Messages logged by a keyword are kept apart from its other children.
+    /// A user keyword setup and teardown are part of its body, as in output.xml.
+    fn into_keyword<E: de::Error>(self, type_: Option<&str>, tolerant: bool) -> Result<Keyword, E> {
+        let (messages, children) = split_messages(self.body);
+        let mut keywords = Vec::new();
+        if let Some(setup) = self.setup {
+            keywords.push(BaseBody::Keyword(
+                setup.into_keyword(Some("SETUP"), tolerant)?,
+            ));
+        }
+        keywords.extend(into_body(children, tolerant)?);
+        if let Some(teardown) = self.teardown {
+            keywords.push(BaseBody::Keyword(
+                teardown.into_keyword(Some("TEARDOWN"), tolerant)?,
+            ));
+        }
+
+        Ok(Keyword {
+            name: self.name.unwrap_or_default(),
+            owner: self.owner,
+            library: None,
+            type_: type_.map(str::to_string),
+            source_name: self.source_name,
+            source: self.source,
+            lineno: self.lineno.map(|lineno| lineno.to_string()),
+            msg: messages,
+            keywords,
+            var: value_to_strings(self.assign),
+            args: self.args.into_iter().map(value_to_string).collect(),
+            tags: self.tags,
+            doc: self.doc,
+            timeout: self.timeout,
+            status: self.status.into_optional_status(),
+        })
+    }
+
+    /// The `message` of a message item is read along with the status fields,
+    /// where keywords, tests and suites have their failure message.
+    fn into_message(self) -> Message {
+        Message {
+            timestamp: None,
+            time: self.timestamp,
+            level: self.level.unwrap_or_default(),
+            html: self.html,
+            value: self.status.message.unwrap_or_default(),
+        }
+    }
+}
+
+fn into_body<E: de::Error>(items: Vec<JsonBodyItem>, tolerant: bool) -> Result<Vec<BaseBody>, E> {
+    items
+        .into_iter()
+        .map(|item| item.into_base_body(tolerant))
+        .collect()
+}
+
+/// Messages of a body, apart from its other items.
+fn split_messages(items: Vec<JsonBodyItem>) -> (Vec<Message>, Vec<JsonBodyItem>) {
+    let (messages, others): (Vec<_>, Vec<_>) = items
+        .into_iter()
+        .partition(|item| item.type_.as_deref() == Some("MESSAGE"));
+    (
+        messages
+            .into_iter()
+            .map(JsonBodyItem::into_message)
+            .collect(),
+        others,
+    )
+}
+
+fn value_to_string(value: Value) -> String {
+    match value {
+        Value::String(value) => value,
+        other => other.to_string(),
+    }
+}
+
+fn value_to_strings(value: Option<Value>) -> Vec<String> {
+    match value {
+        Some(Value::Array(values)) => values.into_iter().map(value_to_string).collect(),
+        Some(Value::Null) | None => Vec::new(),
+        Some(value) => vec![value_to_string(value)],
+    }
+}
+
+/// Loop iterations record their variables as a `{"${name}": "value"}` map.
+fn assigned_values(assign: Option<Value>) -> Vec<ForIterVar> {
+    match assign {
+        Some(Value::Object(values)) => values
+            .into_iter()
+            .map(|(name, value)| ForIterVar {
+                name,
+                value: Some(value_to_string(value)),
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[derive(Deserialize)]
+struct JsonMessage {
+    message: String,
+    level: String,
+    #[serde(default)]
+    timestamp: Option<String>,
+    #[serde(default)]
+    html: bool,
+}
+
+impl JsonMessage {
+    fn into_message(self) -> Message {
+        Message {
+            timestamp: None,
+            time: self.timestamp,
+            level: self.level,
+            html: self.html,
+            value: self.message,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct JsonStatistics {
+    total: JsonStat,
+    #[serde(default)]
+    tags: Vec<JsonStat>,
+    #[serde(default)]
+    suites: Vec<JsonStat>,
+}
+
+#[derive(Deserialize)]
+struct JsonStat {
+    pass: u32,
+    fail: u32,
+    skip: u32,
+    label: String,
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    name: Option<String>,
+}
+
+impl JsonStatistics {
+    fn into_statistics(self) -> Statistics {
+        Statistics {
+            total: StatisticsTotal {
+                stats: self.total.into_tag(),
+            },
+            tags: StatisticsTags {
+                stats: self.tags.into_iter().map(JsonStat::into_tag).collect(),
+            },
+            suites: StatisticsSuites {
+                stats: self.suites.into_iter().map(JsonStat::into_tag).collect(),
+            },
+        }
+    }
+}
+
+impl JsonStat {
+    fn into_tag(self) -> StatisticsTag {
+        StatisticsTag {
+            pass: self.pass,
+            fail: self.fail,
+            skip: self.skip,
+            id: self.id,
+            name: self.name,
+            text: self.label,
+        }
+    }
+}
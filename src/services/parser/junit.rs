@@ -0,0 +1,378 @@
+use std::io::BufRead;
+
+use chrono::{DateTime, Duration, Local, NaiveDateTime};
+use quick_xml::events::{BytesStart, Event};
+use serde::Deserialize;
+
+use super::{
+    statistics::StatisticsBuilder,
+    xml::{attribute, unexpected_eof, XmlOutputParser},
+    BaseBody, Message, OutputEvent, OutputFormat, ParserError, Status, SuiteEnd, SuiteStart, Test,
+    TestRun,
+};
+
+const GENERATOR: &str = "JUnit XML";
+const TIMESTAMP_PATTERN: &str = "%Y-%m-%dT%H:%M:%S%.6f";
+
+/// `<testcase>` with the outcome elements understood by the common producers
+/// (Robot's `--xunit`, pytest, Maven Surefire, Gradle).
+#[derive(Debug, Deserialize)]
+struct JunitTestCase {
+    #[serde(rename = "@name")]
+    name: String,
+    #[serde(rename = "@classname")]
+    classname: Option<String>,
+    #[serde(rename = "@time")]
+    time: Option<String>,
+    #[serde(rename = "@line")]
+    line: Option<String>,
+    #[serde(default)]
+    failure: Vec<JunitOutcome>,
+    #[serde(default)]
+    error: Vec<JunitOutcome>,
+    skipped: Option<JunitOutcome>,
+    #[serde(rename = "system-out", default)]
+    system_out: Vec<String>,
+    #[serde(rename = "system-err", default)]
+    system_err: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JunitOutcome {
+    #[serde(rename = "@message")]
+    message: Option<String>,
+    #[serde(rename = "$text")]
+    text: Option<String>,
+}
+
+impl JunitOutcome {
+    /// Status message of the test: the short reason, or the text without one.
+    fn reason(&self) -> Option<String> {
+        self.message
+            .clone()
+            .or_else(|| self.text.clone())
+            .filter(|reason| !reason.trim().is_empty())
+    }
+}
+
+/// A `<testsuite>` (or the root `<testsuites>`) being read. JUnit only gives
+/// durations, so start times are derived by adding the durations of the
+/// previous siblings to the suite timestamp.
+struct JunitSuite {
+    id: String,
+    name: String,
+    full_name: String,
+    source_file: Option<String>,
+    start: Option<NaiveDateTime>,
+    time: Option<Duration>,
+    cursor: NaiveDateTime,
+    started: bool,
+    suite_count: usize,
+    test_count: usize,
+    passed: bool,
+    failed: bool,
+}
+
+impl JunitSuite {
+    /// Same rule as Robot Framework: any failure fails the suite, a suite
+    /// whose tests were all skipped is skipped.
+    fn status(&self) -> &'static str {
+        if self.failed {
+            "FAIL"
+        } else if self.passed {
+            "PASS"
+        } else {
+            "SKIP"
+        }
+    }
+
+    fn record(&mut self, status: &str) {
+        match status {
+            "FAIL" => self.failed = true,
+            "PASS" => self.passed = true,
+            _ => {}
+        }
+    }
+}
+
+impl<R: BufRead> XmlOutputParser<R> {
+    /// Reads a JUnit report whose root element is `root`. Both `<testsuites>`
+    /// and a single `<testsuite>` become the root suite `s1`. JUnit has no
+    /// statistics block, they are computed from the emitted tests.
+    pub(super) fn parse_junit(
+        &mut self,
+        root: BytesStart<'static>,
+        emit: &mut impl FnMut(OutputEvent) -> Result<(), ParserError>,
+    ) -> Result<(), ParserError> {
+        let mut statistics = StatisticsBuilder::default();
+        {
+            let mut emit = |event: OutputEvent| {
+                statistics.record(&event);
+                emit(event)
+            };
+            self.parse_junit_suite(root, "s1".to_string(), None, &mut emit)?;
+        }
+        emit(OutputEvent::Statistics(statistics.build()))
+    }
+
+    /// Returns the status and end time of the suite, for its parent.
+    fn parse_junit_suite(
+        &mut self,
+        start: BytesStart<'static>,
+        id: String,
+        parent: Option<(&str, NaiveDateTime)>,
+        emit: &mut impl FnMut(OutputEvent) -> Result<(), ParserError>,
+    ) -> Result<(&'static str, NaiveDateTime), ParserError> {
+        let name = attribute(&start, "name")?.unwrap_or_else(|| match parent {
+            Some(_) => id.clone(),
+            None => GENERATOR.to_string(),
+        });
+        let suite_start = attribute(&start, "timestamp")?
+            .map(|timestamp| parse_timestamp(&timestamp))
+            .transpose()?;
+        let mut suite = JunitSuite {
+            id,
+            full_name: match parent {
+                Some((parent_name, _)) => format!("{}.{}", parent_name, name),
+                None => name.clone(),
+            },
+            name,
+            source_file: attribute(&start, "file")?,
+            start: suite_start,
+            time: attribute(&start, "time")?
+                .map(|time| parse_duration(&time))
+                .transpose()?,
+            cursor: suite_start
+                .or(parent.map(|(_, cursor)| cursor))
+                .unwrap_or_else(|| Local::now().naive_local()),
+            started: false,
+            suite_count: 0,
+            test_count: 0,
+            passed: false,
+            failed: false,
+        };
+
+        loop {
+            match self.next_event()? {
+                Event::Start(child) if child.name().as_ref() == b"testsuite" => {
+                    let child_start = attribute(&child, "timestamp")?
+                        .map(|timestamp| parse_timestamp(&timestamp))
+                        .transpose()?;
+                    start_junit_suite(&mut suite, child_start, emit)?;
+                    suite.suite_count += 1;
+                    let child_id = format!("{}-s{}", suite.id, suite.suite_count);
+                    let (status, end) = self.parse_junit_suite(
+                        child,
+                        child_id,
+                        Some((&suite.full_name, suite.cursor)),
+                        emit,
+                    )?;
+                    suite.record(status);
+                    suite.cursor = suite.cursor.max(end);
+                }
+                Event::Start(child) if child.name().as_ref() == b"testcase" => {
+                    let test_case = self.read_element(child)?;
+                    add_junit_test(&mut suite, test_case, emit)?;
+                }
+                Event::Empty(child) if child.name().as_ref() == b"testcase" => {
+                    let test_case = self.read_empty_element(child)?;
+                    add_junit_test(&mut suite, test_case, emit)?;
+                }
+                Event::Start(child) => self.skip_element(child)?,
+                Event::End(_) => break,
+                Event::Eof => return Err(unexpected_eof("testsuite")),
+                _ => {}
+            }
+        }
+
+        start_junit_suite(&mut suite, None, emit)?;
+        let start = suite.start.unwrap_or(suite.cursor);
+        let elapsed = suite.time.unwrap_or(suite.cursor - start);
+        let status = suite.status();
+        emit(OutputEvent::SuiteEnd(Box::new(SuiteEnd {
+            status: junit_status(status, start, elapsed, None),
+            doc: None,
+            metadata: Vec::new(),
+            setup_keyword: None,
+            teardown_keyword: None,
+        })))?;
+        Ok((status, start + elapsed))
+    }
+}
+
+/// Emits the suite start once its first child is met, so a `<testsuites>`
+/// root without timestamp can take the one of its first `<testsuite>`. The
+/// run header is sent along with the root suite.
+fn start_junit_suite(
+    suite: &mut JunitSuite,
+    first_child_start: Option<NaiveDateTime>,
+    emit: &mut impl FnMut(OutputEvent) -> Result<(), ParserError>,
+) -> Result<(), ParserError> {
+    if suite.started {
+        return Ok(());
+    }
+    suite.started = true;
+    if suite.start.is_none() {
+        if let Some(first_child_start) = first_child_start {
+            suite.cursor = first_child_start;
+        }
+        suite.start = Some(suite.cursor);
+    }
+
+    if suite.id == "s1" {
+        emit(OutputEvent::TestRun(TestRun {
+            format: OutputFormat::Junit,
+            generator: GENERATOR.to_string(),
+            generated_date: format_timestamp(suite.cursor),
+            rpa: false,
+            schema_version: String::new(),
+        }))?;
+    }
+    emit(OutputEvent::SuiteStart(SuiteStart {
+        id: suite.id.clone(),
+        name: suite.name.clone(),
+        source_file: suite.source_file.clone(),
+    }))
+}
+
+fn add_junit_test(
+    suite: &mut JunitSuite,
+    test_case: JunitTestCase,
+    emit: &mut impl FnMut(OutputEvent) -> Result<(), ParserError>,
+) -> Result<(), ParserError> {
+    start_junit_suite(suite, None, emit)?;
+    suite.test_count += 1;
+
+    let start = suite.cursor;
+    let elapsed = test_case
+        .time
+        .as_deref()
+        .map(parse_duration)
+        .transpose()?
+        .unwrap_or_default();
+    suite.cursor = start + elapsed;
+    let time = format_timestamp(start);
+    let message = |level: &str, value: String| {
+        BaseBody::Message(Message {
+            timestamp: None,
+            time: Some(time.clone()),
+            level: level.to_string(),
+            html: false,
+            value,
+        })
+    };
+
+    let mut keywords = Vec::new();
+    let failures = test_case.failure.into_iter().chain(test_case.error);
+    let mut status = "PASS";
+    let mut reason = None;
+    for failure in failures {
+        if status == "PASS" {
+            status = "FAIL";
+            reason = failure.reason();
+        }
+        keywords.extend(outcome_messages(failure, "FAIL", &message));
+    }
+    if let Some(skipped) = test_case.skipped {
+        if status == "PASS" {
+            status = "SKIP";
+            reason = skipped.reason();
+        }
+        keywords.extend(outcome_messages(skipped, "SKIP", &message));
+    }
+    keywords.extend(
+        test_case
+            .system_out
+            .into_iter()
+            .map(|output| message("INFO", output)),
+    );
+    keywords.extend(
+        test_case
+            .system_err
+            .into_iter()
+            .map(|output| message("WARN", output)),
+    );
+    suite.record(status);
+
+    // Robot's --xunit and most producers use the suite name as class name,
+    // others (pytest) need it to tell apart tests of the same suite.
+    let name = match test_case.classname {
+        Some(classname) if !classname.is_empty() && classname != suite.full_name => {
+            format!("{}.{}", classname, test_case.name)
+        }
+        _ => test_case.name,
+    };
+
+    emit(OutputEvent::Test(Box::new(Test {
+        id: format!("{}-t{}", suite.id, suite.test_count),
+        name,
+        line: test_case.line.filter(|line| line.parse::<i32>().is_ok()),
+        keywords,
+        doc: None,
+        tags: Vec::new(),
+        timeout: None,
+        status: junit_status(status, start, elapsed, reason),
+        setup_keyword: None,
+        teardown_keyword: None,
+    })))
+}
+
+/// The `message` attribute is the short reason, the text usually a stack trace.
+fn outcome_messages(
+    outcome: JunitOutcome,
+    level: &str,
+    message: &impl Fn(&str, String) -> BaseBody,
+) -> Vec<BaseBody> {
+    let text = outcome.text.filter(|text| !text.trim().is_empty());
+    match (outcome.message, text) {
+        (Some(reason), Some(text)) => vec![message(level, reason), message("DEBUG", text)],
+        (Some(reason), None) => vec![message(level, reason)],
+        (None, Some(text)) => vec![message(level, text)],
+        (None, None) => Vec::new(),
+    }
+}
+
+fn junit_status(
+    status: &str,
+    start: NaiveDateTime,
+    elapsed: Duration,
+    message: Option<String>,
+) -> Status {
+    Status {
+        status: status.to_string(),
+        start_time: None,
+        end_time: None,
+        start: Some(format_timestamp(start)),
+        elapsed: Some(format!(
+            "{:.6}",
+            elapsed.num_microseconds().unwrap_or_default() as f64 / 1_000_000.0
+        )),
+        message,
+    }
+}
+
+/// Timestamps are kept as written, an offset (pytest) is dropped so they
+/// compare with the local times Robot Framework writes.
+fn parse_timestamp(timestamp: &str) -> Result<NaiveDateTime, ParserError> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map(|timestamp| timestamp.naive_local())
+        .or_else(|_| NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%dT%H:%M:%S%.f"))
+        .or_else(|_| NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S%.f"))
+        .map_err(|e| ParserError::Malformed(format!("invalid timestamp {}: {}", timestamp, e)))
+}
+
+fn format_timestamp(timestamp: NaiveDateTime) -> String {
+    timestamp.format(TIMESTAMP_PATTERN).to_string()
+}
+
+/// Durations in seconds, some producers format them with thousands separators.
+fn parse_duration(time: &str) -> Result<Duration, ParserError> {
+    let seconds = time
+        .replace(',', "")
+        .trim()
+        .parse::<f64>()
+        .map_err(|e| ParserError::Malformed(format!("invalid time {}: {}", time, e)))?;
+    Ok(Duration::microseconds(
+        (seconds * 1_000_000.0).round() as i64
+    ))
+}
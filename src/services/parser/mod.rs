@@ -0,0 +1,1139 @@
+use serde::{de, Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use sha1::Digest;
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Read},
+    path::Path,
+};
+use thiserror::Error;
+use tokio::sync::mpsc;
+use tracing::{error, info};
+
+mod json;
+mod junit;
+mod statistics;
+mod xml;
+
+pub use statistics::StatisticsBuilder;
+
+/// Number of parsed events buffered between the parser and the consumer.
+const EVENT_BUFFER_SIZE: usize = 64;
+
+/// Serialization of an output file, told apart from its first bytes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    /// `output.xml`, any schema version.
+    Xml,
+    /// `output.json`, written by Robot Framework 7 and newer.
+    Json,
+    /// JUnit/xUnit report (Robot's `--xunit`, pytest, Maven...). Detected as
+    /// `Xml` at first, told apart by its root element.
+    Junit,
+}
+
+impl OutputFormat {
+    /// Looks at the first significant byte. A UTF-8 BOM and leading whitespace
+    /// may be consumed, both parsers would skip them anyway.
+    fn detect<R: BufRead>(source: &mut R) -> Result<Self, ParserError> {
+        if source.fill_buf()?.starts_with(b"\xEF\xBB\xBF") {
+            source.consume(3);
+        }
+        loop {
+            let content = source.fill_buf()?;
+            if content.is_empty() {
+                return Err(ParserError::UnsupportedFormat("empty file".to_string()));
+            }
+            match content.iter().find(|byte| !byte.is_ascii_whitespace()) {
+                Some(b'<') => return Ok(OutputFormat::Xml),
+                Some(b'{') => return Ok(OutputFormat::Json),
+                Some(_) => {
+                    return Err(ParserError::UnsupportedFormat(
+                        "expected an XML or JSON output file".to_string(),
+                    ))
+                }
+                None => {
+                    let length = content.len();
+                    source.consume(length);
+                }
+            }
+        }
+    }
+}
+
+/// Attributes of the root `<robot>` element.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestRun {
+    pub format: OutputFormat,
+    pub generator: String,
+    pub generated_date: String,
+    pub rpa: bool,
+    pub schema_version: String,
+}
+
+/// Attributes of a `<suite>` element, known as soon as the suite is opened.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SuiteStart {
+    pub id: String,
+    pub name: String,
+    pub source_file: Option<String>,
+}
+
+/// Content of a `<suite>` element, known once the suite is closed.
+/// Child suites and tests are emitted as their own events in between.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SuiteEnd {
+    pub status: Status,
+    pub doc: Option<String>,
+    /// `Metadata` setting of the suite, name and value in the order they are
+    /// written.
+    pub metadata: Vec<(String, String)>,
+    pub setup_keyword: Option<Keyword>,
+    pub teardown_keyword: Option<Keyword>,
+}
+
+/// Parsed pieces of an output file, emitted in document order.
+#[derive(Debug, PartialEq)]
+pub enum OutputEvent {
+    TestRun(TestRun),
+    SuiteStart(SuiteStart),
+    Test(Box<Test>),
+    SuiteEnd(Box<SuiteEnd>),
+    Statistics(Statistics),
+    Errors(Errors),
+    Finished { sha1: String },
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct Test {
+    #[serde(rename = "@id")]
+    pub id: String,
+    #[serde(rename = "@name")]
+    pub name: String,
+    #[serde(rename = "@line")] // schema >= 3
+    pub line: Option<String>,
+    #[serde(rename = "$value", default)]
+    pub keywords: Vec<BaseBody>,
+    #[serde(rename = "doc")]
+    pub doc: Option<String>,
+    #[serde(rename = "tag", default)]
+    pub tags: Vec<String>,
+    #[serde(rename = "timeout", default, deserialize_with = "timeout_value")]
+    pub timeout: Option<String>,
+    #[serde(rename = "status")]
+    pub status: Status,
+    /// Moved out of the body by the parser, like the ones of suites.
+    #[serde(skip)]
+    pub setup_keyword: Option<Keyword>,
+    #[serde(skip)]
+    pub teardown_keyword: Option<Keyword>,
+}
+
+impl Test {
+    /// output.xml writes the setup and teardown of a test as the first and
+    /// last `<kw>` of its body, with their type.
+    fn split_setup_and_teardown(&mut self) {
+        let is_keyword_of_type = |item: &BaseBody, type_: &str| matches!(item, BaseBody::Keyword(keyword) if keyword.type_.as_deref() == Some(type_));
+        if self
+            .keywords
+            .last()
+            .is_some_and(|item| is_keyword_of_type(item, "TEARDOWN"))
+        {
+            if let Some(BaseBody::Keyword(keyword)) = self.keywords.pop() {
+                self.teardown_keyword = Some(keyword);
+            }
+        }
+        if self
+            .keywords
+            .first()
+            .is_some_and(|item| is_keyword_of_type(item, "SETUP"))
+        {
+            if let BaseBody::Keyword(keyword) = self.keywords.remove(0) {
+                self.setup_keyword = Some(keyword);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Serialize, PartialEq)]
+pub enum BaseBody {
+    #[serde(rename = "kw")]
+    Keyword(Keyword),
+    #[serde(rename = "for")]
+    For(For),
+    #[serde(rename = "while")]
+    While(While),
+    #[serde(rename = "group")]
+    Group(Group),
+    #[serde(rename = "if")]
+    If(If),
+    #[serde(rename = "try")]
+    Try(Try),
+    #[serde(rename = "variable")]
+    Var(Var),
+    #[serde(rename = "return")]
+    Return(Return),
+    #[serde(rename = "continue")]
+    Continue(Continue),
+    #[serde(rename = "break")]
+    Break(Break),
+    #[serde(rename = "message", alias = "msg")]
+    Message(Message),
+    #[serde(rename = "error")]
+    Error(InvalidSyntax),
+    /// Element the parser does not know, kept as raw JSON in tolerant mode.
+    #[serde(rename = "unknown", deserialize_with = "raw_json")]
+    Unknown(Value),
+}
+
+#[derive(Debug, Deserialize, Clone, Serialize, PartialEq)]
+pub struct Keyword {
+    #[serde(alias = "@name")]
+    pub name: String,
+    #[serde(alias = "@owner")] // v5
+    pub owner: Option<String>,
+    #[serde(alias = "@library")] // v4
+    pub library: Option<String>,
+    #[serde(alias = "@type")]
+    pub type_: Option<String>,
+    /// Name as written in the data, for keywords with embedded arguments.
+    #[serde(alias = "@source_name", alias = "@sourcename")]
+    pub source_name: Option<String>,
+    #[serde(alias = "@source")]
+    pub source: Option<String>,
+    #[serde(alias = "@lineno")]
+    pub lineno: Option<String>,
+    #[serde(rename = "msg", default)]
+    pub msg: Vec<Message>,
+    #[serde(alias = "$value", default)]
+    pub keywords: Vec<BaseBody>,
+    #[serde(rename = "var", default)]
+    pub var: Vec<String>,
+    #[serde(alias = "arg", default)]
+    pub args: Vec<String>,
+    #[serde(alias = "tag", default)]
+    pub tags: Vec<String>,
+    #[serde(rename = "doc")]
+    pub doc: Option<String>,
+    #[serde(rename = "timeout", default, deserialize_with = "timeout_value")]
+    pub timeout: Option<String>,
+    #[serde(rename = "status")]
+    pub status: Option<Status>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct For {
+    #[serde(alias = "@flavor")]
+    pub flavor: String, // 'IN', 'IN RANGE', 'IN ENUMERATE', 'IN ZIP'
+    #[serde(alias = "@start")]
+    pub start: Option<String>,
+    #[serde(alias = "@mode")]
+    pub mode: Option<String>,
+    #[serde(alias = "@fill")]
+    pub fill: Option<String>,
+    #[serde(alias = "iter", default)]
+    pub iters: Vec<ForIter>,
+    #[serde(alias = "var", default)]
+    pub vars: Vec<String>,
+    #[serde(alias = "value", default)]
+    pub values: Vec<String>,
+    #[serde(rename = "msg", default)]
+    pub msg: Vec<Message>,
+    #[serde(rename = "status")]
+    pub status: Status,
+}
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct ForIter {
+    #[serde(alias = "$value", default)]
+    pub children: Vec<BaseBody>,
+    #[serde(alias = "var", default)]
+    pub vars: Vec<ForIterVar>,
+    #[serde(rename = "status")]
+    pub status: Status,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct ForIterVar {
+    #[serde(alias = "@name")]
+    pub name: String,
+    #[serde(alias = "$text")]
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct While {
+    #[serde(alias = "@condition")]
+    pub condition: Vec<String>,
+    #[serde(alias = "@limit")]
+    pub limit: Vec<String>,
+    #[serde(alias = "@on_limit")]
+    pub on_limit: Vec<String>,
+    #[serde(alias = "@on_limit_message")]
+    pub on_limit_message: Vec<String>,
+    #[serde(alias = "iter", default)]
+    pub iters: Vec<WhileIter>,
+    #[serde(rename = "msg", default)]
+    pub msg: Vec<Message>,
+    #[serde(rename = "status")]
+    pub status: Status,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct WhileIter {
+    #[serde(alias = "$value", default)]
+    pub children: Vec<BaseBody>,
+    #[serde(rename = "status")]
+    pub status: Status,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct Group {
+    #[serde(rename = "@name")]
+    pub name: String,
+    #[serde(alias = "$value", default)]
+    pub children: Vec<BaseBody>,
+    #[serde(rename = "status")]
+    pub status: Status,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct If {
+    #[serde(alias = "branch", default)]
+    pub branches: Vec<IfBranch>,
+    #[serde(rename = "msg", default)]
+    pub msg: Vec<Message>,
+    #[serde(rename = "status")]
+    pub status: Status,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct IfBranch {
+    #[serde(alias = "@type")]
+    pub type_: String,
+    #[serde(alias = "@condition")]
+    pub condition: Option<String>,
+    #[serde(alias = "$value", default)]
+    pub children: Vec<BaseBody>,
+    #[serde(rename = "status")]
+    pub status: Status,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct Try {
+    #[serde(alias = "branch", default)]
+    pub branches: Vec<TryBranch>,
+    #[serde(rename = "msg", default)]
+    pub msg: Vec<Message>,
+    #[serde(rename = "status")]
+    pub status: Status,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct TryBranch {
+    #[serde(alias = "@type")]
+    pub type_: String,
+    #[serde(alias = "@pattern_type")]
+    pub pattern_type: Option<String>,
+    #[serde(alias = "@assign", alias = "@variable")] // `variable` in schema 3 and 4
+    pub assign: Option<String>,
+    #[serde(alias = "pattern", default)]
+    pub patterns: Vec<String>,
+    #[serde(alias = "$value", default)]
+    pub children: Vec<BaseBody>,
+    #[serde(rename = "status")]
+    pub status: Status,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct Var {
+    #[serde(alias = "@name")]
+    pub name: String,
+    #[serde(alias = "@scope")]
+    pub scope: Option<String>,
+    #[serde(alias = "@separator")]
+    pub separator: Option<String>,
+    #[serde(rename = "msg")]
+    pub message: Option<Message>,
+    #[serde(alias = "var", default)]
+    pub vars: Vec<String>,
+    #[serde(rename = "status")]
+    pub status: Status,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct Return {
+    #[serde(alias = "value", default)]
+    pub value: Vec<String>,
+    #[serde(rename = "msg", default)]
+    pub msg: Vec<Message>,
+    #[serde(rename = "status")]
+    pub status: Status,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct Continue {
+    #[serde(rename = "msg", default)]
+    pub msg: Vec<Message>,
+    #[serde(rename = "status")]
+    pub status: Status,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct Break {
+    #[serde(rename = "msg", default)]
+    pub msg: Vec<Message>,
+    #[serde(rename = "status")]
+    pub status: Status,
+}
+
+/// `<error>`: a row of the data Robot Framework could not make sense of,
+/// e.g. an unknown setting. Its values are the cells of the row.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct InvalidSyntax {
+    #[serde(alias = "value", default)]
+    pub values: Vec<String>,
+    #[serde(rename = "msg", default)]
+    pub msg: Vec<Message>,
+    #[serde(rename = "status")]
+    pub status: Status,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct Message {
+    #[serde(alias = "@timestamp")] // schema <= 4
+    pub timestamp: Option<String>,
+    #[serde(alias = "@time")] // schema >= 5
+    pub time: Option<String>,
+    #[serde(alias = "@level")]
+    pub level: String,
+    #[serde(alias = "@html", default)]
+    pub html: bool,
+    #[serde(alias = "$text")]
+    pub value: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Status {
+    #[serde(alias = "@status")]
+    pub status: String,
+    #[serde(alias = "@starttime")] // schema <= 4
+    pub start_time: Option<String>,
+    #[serde(alias = "@endtime")] // schema <= 4
+    pub end_time: Option<String>,
+    #[serde(alias = "@start")] // schema >= 5
+    pub start: Option<String>,
+    #[serde(alias = "@elapsed")] // schema >= 5, in seconds
+    pub elapsed: Option<String>,
+    /// Failure or skip reason, HTML when it starts with `*HTML*`.
+    #[serde(alias = "$text")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct Statistics {
+    #[serde(rename = "total")]
+    pub total: StatisticsTotal,
+    #[serde(rename = "tag")]
+    pub tags: StatisticsTags,
+    #[serde(rename = "suite")]
+    pub suites: StatisticsSuites,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct StatisticsTotal {
+    #[serde(rename = "stat")]
+    pub stats: StatisticsTag,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct StatisticsTags {
+    #[serde(rename = "stat", default)]
+    pub stats: Vec<StatisticsTag>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct StatisticsSuites {
+    #[serde(rename = "stat", default)]
+    pub stats: Vec<StatisticsTag>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct StatisticsTag {
+    #[serde(rename = "@pass")]
+    pub pass: u32,
+    #[serde(rename = "@fail")]
+    pub fail: u32,
+    #[serde(rename = "@skip")]
+    pub skip: u32,
+    #[serde(rename = "@id")]
+    pub id: Option<String>,
+    #[serde(rename = "@name")]
+    pub name: Option<String>,
+    #[serde(rename = "$text")]
+    pub text: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct Errors {
+    #[serde(rename = "msg", default)]
+    pub messages: Vec<Message>,
+}
+
+/// `<timeout value="1 minute"/>`, read as its value.
+fn timeout_value<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    #[derive(Deserialize)]
+    struct Timeout {
+        #[serde(rename = "@value")]
+        value: String,
+    }
+    Ok(Option::<Timeout>::deserialize(deserializer)?.map(|timeout| timeout.value))
+}
+
+/// Unknown elements are rewritten by the XML parser as an `<unknown>`
+/// element holding their JSON.
+fn raw_json<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Value, D::Error> {
+    let raw = String::deserialize(deserializer)?;
+    serde_json::from_str(&raw).map_err(de::Error::custom)
+}
+
+#[derive(Error, Debug)]
+pub enum ParserError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("XML error: {0}")]
+    Xml(#[from] quick_xml::Error),
+    #[error("XML deserialization error: {0}")]
+    Deserialization(#[from] quick_xml::DeError),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Malformed output file: {0}")]
+    Malformed(String),
+    #[error("Unsupported output file: {0}")]
+    UnsupportedFormat(String),
+    #[error("Parsing interrupted, the event consumer stopped")]
+    Interrupted,
+}
+
+/// Events of an output file being parsed on a blocking thread.
+pub struct OutputEventStream {
+    receiver: mpsc::Receiver<Result<OutputEvent, ParserError>>,
+}
+
+impl OutputEventStream {
+    pub async fn next(&mut self) -> Option<Result<OutputEvent, ParserError>> {
+        self.receiver.recv().await
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct RobotOutputParserService {
+    /// Keeps the elements the parser does not know as raw JSON in the body
+    /// they appear in, instead of failing the whole file.
+    tolerant: bool,
+}
+
+impl RobotOutputParserService {
+    pub fn new(tolerant: bool) -> Self {
+        Self { tolerant }
+    }
+
+    /// Parses the file incrementally on a blocking thread. Events are sent through
+    /// a bounded channel, so the parser waits for the consumer instead of buffering
+    /// the whole test run. The format is detected from the content before
+    /// returning, the file name is only used for logging.
+    pub fn stream_file<P: AsRef<Path>>(
+        &self,
+        file_name: String,
+        path: P,
+    ) -> Result<OutputEventStream, ParserError> {
+        info!("Parsing file: {:?}", path.as_ref());
+
+        let source = OutputSource::new(File::open(path)?)?;
+        info!("Detected {:?} output in {}", source.format, file_name);
+
+        let (sender, receiver) = mpsc::channel(EVENT_BUFFER_SIZE);
+
+        let parser = *self;
+        tokio::task::spawn_blocking(move || {
+            let result = parser.parse(source, |event| {
+                sender
+                    .blocking_send(Ok(event))
+                    .map_err(|_| ParserError::Interrupted)
+            });
+            match result {
+                // The consumer gave up on the file, e.g. one already imported.
+                Ok(()) | Err(ParserError::Interrupted) => {}
+                Err(e) => {
+                    error!("Failed to parse {}: {}", file_name, e);
+                    let _ = sender.blocking_send(Err(e));
+                }
+            }
+        });
+
+        Ok(OutputEventStream { receiver })
+    }
+
+    /// Parses an output file, calling `emit` for each event in document order.
+    /// The SHA-1 of the file is computed while reading and sent last.
+    pub fn parse<R: Read>(
+        &self,
+        source: OutputSource<R>,
+        mut emit: impl FnMut(OutputEvent) -> Result<(), ParserError>,
+    ) -> Result<(), ParserError> {
+        let OutputSource { format, mut reader } = source;
+        match format {
+            OutputFormat::Xml | OutputFormat::Junit => {
+                xml::parse(&mut reader, self.tolerant, &mut emit)?
+            }
+            OutputFormat::Json => json::parse(&mut reader, self.tolerant, &mut emit)?,
+        }
+        let sha1 = reader.into_inner().finish()?;
+        emit(OutputEvent::Finished { sha1 })
+    }
+}
+
+/// An output file whose format has been detected but not parsed yet.
+pub struct OutputSource<R> {
+    pub format: OutputFormat,
+    reader: BufReader<HashingReader<R>>,
+}
+
+impl<R: Read> OutputSource<R> {
+    pub fn new(source: R) -> Result<Self, ParserError> {
+        let mut reader = BufReader::new(HashingReader::new(source));
+        let format = OutputFormat::detect(&mut reader)?;
+        Ok(Self { format, reader })
+    }
+}
+
+struct HashingReader<R> {
+    inner: R,
+    hasher: sha1::Sha1,
+}
+
+impl<R: Read> HashingReader<R> {
+    fn new(inner: R) -> Self {
+        Self {
+            inner,
+            hasher: sha1::Sha1::new(),
+        }
+    }
+
+    /// Hashes whatever the parser did not read, e.g. trailing whitespace.
+    fn finish(mut self) -> Result<String, std::io::Error> {
+        std::io::copy(&mut self, &mut std::io::sink())?;
+        Ok(format!("{:x}", self.hasher.finalize()))
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.hasher.update(&buf[..read]);
+        Ok(read)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_file(path: &str) -> Vec<OutputEvent> {
+        let file = File::open(path).expect("fixture should exist");
+        let mut events = Vec::new();
+        let source = OutputSource::new(file).expect("fixture should be an output file");
+        RobotOutputParserService::new(false)
+            .parse(source, |event| {
+                events.push(event);
+                Ok(())
+            })
+            .expect("fixture should parse");
+        events
+    }
+
+    fn parse_content(content: &str, tolerant: bool) -> Result<Vec<OutputEvent>, ParserError> {
+        let mut events = Vec::new();
+        let source = OutputSource::new(content.as_bytes())?;
+        RobotOutputParserService::new(tolerant).parse(source, |event| {
+            events.push(event);
+            Ok(())
+        })?;
+        Ok(events)
+    }
+
+    fn tests(events: &[OutputEvent]) -> Vec<&Test> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                OutputEvent::Test(test) => Some(test.as_ref()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_xml() {
+        let events = parse_file("./src/services/resources/output_simplified.xml");
+
+        let OutputEvent::TestRun(test_run) = &events[0] else {
+            panic!("Expected the test run first, got {:?}", events[0]);
+        };
+        assert_eq!(test_run.generator, "Robot 7.1 (Python 3.10.4 on win32)");
+        assert_eq!(test_run.generated_date, "20241217 11:27:23.676");
+        assert!(!test_run.rpa);
+        assert_eq!(test_run.schema_version, "4");
+
+        let suite_ids: Vec<&str> = events
+            .iter()
+            .filter_map(|event| match event {
+                OutputEvent::SuiteStart(suite) => Some(suite.id.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(suite_ids, vec!["s1", "s1-s1", "s1-s1-s1"]);
+
+        let tests = tests(&events);
+        assert_eq!(tests.len(), 1);
+        assert_eq!(tests[0].id, "s1-s1-s1-t1");
+        assert_eq!(tests[0].name, "History Test");
+        assert_eq!(tests[0].line.as_deref(), Some("11"));
+        assert_eq!(
+            tests[0].doc.as_deref(),
+            Some("The aim of this test is to do something")
+        );
+        assert_eq!(tests[0].tags, vec!["Regression", "1324"]);
+        assert_eq!(tests[0].status.status, "PASS");
+
+        let OutputEvent::SuiteEnd(root_suite) = &events[events.len() - 4] else {
+            panic!(
+                "Expected the root suite end, got {:?}",
+                events[events.len() - 4]
+            );
+        };
+        let setup = root_suite.setup_keyword.as_ref().unwrap();
+        assert_eq!(setup.name, "Acceptance Setup");
+        assert_eq!(setup.library.as_deref(), Some("init-keywords"));
+        assert_eq!(setup.type_.as_deref(), Some("SETUP"));
+        assert!(root_suite.teardown_keyword.is_none());
+        assert_eq!(root_suite.status.status, "FAIL");
+
+        let OutputEvent::Statistics(statistics) = &events[events.len() - 3] else {
+            panic!("Expected statistics, got {:?}", events[events.len() - 3]);
+        };
+        assert_eq!(statistics.total.stats.pass, 7);
+        assert_eq!(statistics.total.stats.fail, 1);
+        assert_eq!(statistics.tags.stats.len(), 15);
+        assert_eq!(statistics.suites.stats.len(), 8);
+        assert_eq!(statistics.suites.stats[0].id.as_deref(), Some("s1"));
+
+        let OutputEvent::Errors(errors) = &events[events.len() - 2] else {
+            panic!("Expected errors, got {:?}", events[events.len() - 2]);
+        };
+        assert_eq!(errors.messages.len(), 2);
+        assert_eq!(
+            errors.messages[0].timestamp.as_deref(),
+            Some("20250115 10:52:56.694")
+        );
+        assert_eq!(errors.messages[0].level, "WARN");
+
+        assert_eq!(
+            events.last(),
+            Some(&OutputEvent::Finished {
+                sha1: "e8d12ac785d96d8775ba86e731e772ff30383703".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_xml_of_each_schema_version() {
+        for (fixture, schema_version) in [
+            ("output_rf4.xml", "2"),
+            ("output_rf5.xml", "3"),
+            ("output_rf6.xml", "4"),
+            ("output_rf7.xml", "5"),
+        ] {
+            let events = parse_file(&format!("./src/services/resources/{}", fixture));
+
+            let OutputEvent::TestRun(test_run) = &events[0] else {
+                panic!("{}: expected the test run first", fixture);
+            };
+            assert_eq!(test_run.schema_version, schema_version, "{}", fixture);
+
+            let tests = tests(&events);
+            assert_eq!(tests.len(), 2, "{}", fixture);
+            assert_eq!(tests[1].status.status, "FAIL", "{}", fixture);
+            assert_eq!(
+                tests[1].status.message.as_deref(),
+                Some("1 != 2"),
+                "{}",
+                fixture
+            );
+            assert_eq!(tests[0].status.message, None, "{}", fixture);
+            assert_eq!(tests[1].tags, vec!["smoke"], "{}", fixture);
+            if schema_version == "2" {
+                assert_eq!(tests[0].line, None, "{}", fixture);
+            } else {
+                assert_eq!(tests[0].line.as_deref(), Some("8"), "{}", fixture);
+            }
+
+            let status = &tests[0].status;
+            if schema_version == "5" {
+                assert_eq!(status.start.as_deref(), Some("2024-12-17T11:27:23.696012"));
+                assert_eq!(status.elapsed.as_deref(), Some("1.234000"));
+                assert_eq!(status.start_time, None);
+            } else {
+                assert!(status.start_time.is_some(), "{}", fixture);
+                assert!(status.end_time.is_some(), "{}", fixture);
+                assert_eq!(status.start, None, "{}", fixture);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_json_like_xml() {
+        let xml_events = parse_file("./src/services/resources/output_rf7.xml");
+        let json_events = parse_file("./src/services/resources/output_rf7.json");
+
+        let (OutputEvent::TestRun(json_run), OutputEvent::TestRun(xml_run)) =
+            (&json_events[0], &xml_events[0])
+        else {
+            panic!("Expected the test runs first");
+        };
+        assert_eq!(json_run.format, OutputFormat::Json);
+        assert_eq!(
+            TestRun {
+                format: OutputFormat::Xml,
+                ..json_run.clone()
+            },
+            *xml_run
+        );
+
+        let suite_starts = |events: &[OutputEvent]| -> Vec<SuiteStart> {
+            events
+                .iter()
+                .filter_map(|event| match event {
+                    OutputEvent::SuiteStart(suite) => Some(suite.clone()),
+                    _ => None,
+                })
+                .collect()
+        };
+        assert_eq!(suite_starts(&json_events), suite_starts(&xml_events));
+
+        let json_tests = tests(&json_events);
+        let xml_tests = tests(&xml_events);
+        assert_eq!(json_tests.len(), xml_tests.len());
+        for (json_test, xml_test) in json_tests.iter().zip(&xml_tests) {
+            assert_eq!(json_test.id, xml_test.id);
+            assert_eq!(json_test.name, xml_test.name);
+            assert_eq!(json_test.line, xml_test.line);
+            assert_eq!(json_test.tags, xml_test.tags);
+            assert_eq!(json_test.status.status, xml_test.status.status);
+            assert_eq!(json_test.status.start, xml_test.status.start);
+            assert_eq!(json_test.status.message, xml_test.status.message);
+        }
+        assert_eq!(json_tests[0].status.elapsed.as_deref(), Some("1.234"));
+
+        let BaseBody::Keyword(log) = &json_tests[0].keywords[0] else {
+            panic!("Expected a keyword, got {:?}", json_tests[0].keywords[0]);
+        };
+        assert_eq!(log.owner.as_deref(), Some("BuiltIn"));
+        assert_eq!(log.args, vec!["Adding item"]);
+        assert_eq!(log.msg[0].value, "Adding item");
+        assert_eq!(
+            log.msg[0].time.as_deref(),
+            Some("2024-12-17T11:27:23.697103")
+        );
+        let BaseBody::For(for_loop) = &json_tests[0].keywords[1] else {
+            panic!("Expected a FOR loop, got {:?}", json_tests[0].keywords[1]);
+        };
+        assert_eq!(for_loop.vars, vec!["${item}"]);
+        assert_eq!(for_loop.iters[0].vars[0].value.as_deref(), Some("apple"));
+        assert!(matches!(for_loop.iters[0].children[0], BaseBody::Var(_)));
+
+        let failed_keyword_messages = |test: &Test| match &test.keywords[0] {
+            BaseBody::Keyword(keyword) => keyword.msg.clone(),
+            body => panic!("Expected a keyword, got {:?}", body),
+        };
+        let json_messages = failed_keyword_messages(json_tests[1]);
+        assert_eq!(json_messages, failed_keyword_messages(xml_tests[1]));
+        assert!(!json_messages[0].html);
+        assert!(json_messages[1].html);
+        assert_eq!(
+            json_messages[1].value,
+            "<img src=\"selenium-screenshot-1.png\" width=\"800px\">"
+        );
+
+        let root_suite_end = |events: &[OutputEvent]| match &events[events.len() - 4] {
+            OutputEvent::SuiteEnd(suite_end) => suite_end.clone(),
+            event => panic!("Expected the root suite end, got {:?}", event),
+        };
+        let (json_suite, xml_suite) = (root_suite_end(&json_events), root_suite_end(&xml_events));
+        assert_eq!(json_suite.status.status, xml_suite.status.status);
+        assert_eq!(json_suite.metadata, xml_suite.metadata);
+        assert_eq!(
+            xml_suite.metadata,
+            vec![
+                ("Browser".to_string(), "firefox".to_string()),
+                ("Environment".to_string(), "staging".to_string())
+            ]
+        );
+        assert_eq!(
+            json_suite.setup_keyword.map(|keyword| keyword.name),
+            xml_suite.setup_keyword.map(|keyword| keyword.name)
+        );
+        assert_eq!(
+            json_suite
+                .teardown_keyword
+                .and_then(|keyword| keyword.type_),
+            Some("TEARDOWN".to_string())
+        );
+
+        let len = json_events.len();
+        assert_eq!(json_events[len - 3], xml_events[xml_events.len() - 3]);
+        assert_eq!(json_events[len - 2], xml_events[xml_events.len() - 2]);
+        assert!(matches!(json_events[len - 1], OutputEvent::Finished { .. }));
+    }
+
+    #[test]
+    fn test_detect_output_format() {
+        let detect = |content: &[u8]| OutputSource::new(content).map(|source| source.format);
+
+        assert_eq!(
+            detect(b"<?xml version=\"1.0\"?>").unwrap(),
+            OutputFormat::Xml
+        );
+        assert_eq!(
+            detect(b"\xEF\xBB\xBF\n  <robot>").unwrap(),
+            OutputFormat::Xml
+        );
+        assert_eq!(
+            detect(b"\n{\"generator\": \"\"}").unwrap(),
+            OutputFormat::Json
+        );
+        assert!(matches!(
+            detect(b"name,status"),
+            Err(ParserError::UnsupportedFormat(_))
+        ));
+        assert!(matches!(
+            detect(b" \n"),
+            Err(ParserError::UnsupportedFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_robot_xunit() {
+        let events = parse_file("./src/services/resources/junit_xunit.xml");
+        let robot_events = parse_file("./src/services/resources/output_rf7.xml");
+
+        let OutputEvent::TestRun(test_run) = &events[0] else {
+            panic!("Expected the test run first, got {:?}", events[0]);
+        };
+        assert_eq!(test_run.format, OutputFormat::Junit);
+        assert_eq!(test_run.generated_date, "2024-12-17T11:27:23.678000");
+
+        let robot_tests = tests(&robot_events);
+        let tests = tests(&events);
+        assert_eq!(tests.len(), robot_tests.len());
+        for (test, robot_test) in tests.iter().zip(&robot_tests) {
+            assert_eq!(test.id, robot_test.id);
+            assert_eq!(test.name, robot_test.name);
+            assert_eq!(test.status.status, robot_test.status.status);
+            assert_eq!(test.status.message, robot_test.status.message);
+        }
+        assert_eq!(
+            tests[0].status.start.as_deref(),
+            Some("2024-12-17T11:27:23.695500")
+        );
+        assert_eq!(
+            tests[1].status.start.as_deref(),
+            Some("2024-12-17T11:27:24.929500")
+        );
+        let BaseBody::Message(failure) = &tests[1].keywords[0] else {
+            panic!("Expected the failure message, got {:?}", tests[1].keywords);
+        };
+        assert_eq!(failure.level, "FAIL");
+        assert_eq!(failure.value, "1 != 2");
+
+        let OutputEvent::Statistics(statistics) = &events[events.len() - 2] else {
+            panic!("Expected statistics, got {:?}", events[events.len() - 2]);
+        };
+        let OutputEvent::Statistics(robot_statistics) = &robot_events[robot_events.len() - 3]
+        else {
+            panic!(
+                "Expected statistics, got {:?}",
+                robot_events[robot_events.len() - 3]
+            );
+        };
+        assert_eq!(statistics.total, robot_statistics.total);
+        assert_eq!(statistics.suites, robot_statistics.suites);
+        assert!(statistics.tags.stats.is_empty());
+    }
+
+    #[test]
+    fn test_parse_pytest_junit() {
+        let events = parse_file("./src/services/resources/junit_pytest.xml");
+
+        let OutputEvent::TestRun(test_run) = &events[0] else {
+            panic!("Expected the test run first, got {:?}", events[0]);
+        };
+        assert_eq!(test_run.format, OutputFormat::Junit);
+        assert_eq!(test_run.generated_date, "2025-02-11T14:03:10.118254");
+
+        let tests = tests(&events);
+        let names: Vec<&str> = tests.iter().map(|test| test.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "tests.test_cart.test_add_item",
+                "tests.test_cart.test_remove_item",
+                "tests.test_checkout.test_pay",
+                "tests.test_checkout.test_refund",
+                "tests.test_checkout.test_receipt",
+            ]
+        );
+        let statuses: Vec<&str> = tests
+            .iter()
+            .map(|test| test.status.status.as_str())
+            .collect();
+        assert_eq!(statuses, vec!["PASS", "FAIL", "FAIL", "SKIP", "PASS"]);
+        assert_eq!(tests[0].id, "s1-s1-t1");
+        assert_eq!(
+            tests[1].status.start.as_deref(),
+            Some("2025-02-11T14:03:10.130254")
+        );
+        assert_eq!(tests[1].status.elapsed.as_deref(), Some("0.031000"));
+        assert_eq!(tests[1].keywords.len(), 2);
+        assert_eq!(
+            tests[1].status.message.as_deref(),
+            Some("AssertionError: assert 1 == 2")
+        );
+        assert_eq!(
+            tests[3].status.message.as_deref(),
+            Some("refunds are not implemented")
+        );
+
+        let OutputEvent::SuiteEnd(root_suite) = &events[events.len() - 3] else {
+            panic!(
+                "Expected the root suite end, got {:?}",
+                events[events.len() - 3]
+            );
+        };
+        assert_eq!(root_suite.status.status, "FAIL");
+
+        let OutputEvent::Statistics(statistics) = &events[events.len() - 2] else {
+            panic!("Expected statistics, got {:?}", events[events.len() - 2]);
+        };
+        assert_eq!(
+            (
+                statistics.total.stats.pass,
+                statistics.total.stats.fail,
+                statistics.total.stats.skip
+            ),
+            (2, 2, 1)
+        );
+        let suites: Vec<&str> = statistics
+            .suites
+            .stats
+            .iter()
+            .map(|stat| stat.text.as_str())
+            .collect();
+        assert_eq!(suites, vec!["JUnit XML", "JUnit XML.pytest"]);
+    }
+
+    #[test]
+    fn test_parse_full_keyword_model() {
+        let xml = r#"<robot generator="Robot 7.1.1" generated="2024-12-17T11:27:23.676544" schemaversion="5">
+<suite id="s1" name="Shop">
+<test id="s1-t1" name="Checkout" line="3">
+<kw name="Open Cart" type="SETUP"><status status="PASS" start="2024-12-17T11:27:23.700000" elapsed="0.1"/></kw>
+<kw name="Pay ${amount} euros" source_name="Pay ${amount} euros" owner="shop">
+<arg>10</arg>
+<timeout value="1 minute"/>
+<status status="PASS" start="2024-12-17T11:27:23.800000" elapsed="0.1"/>
+</kw>
+<try>
+<branch type="TRY"><kw name="Fail"><status status="FAIL" start="2024-12-17T11:27:23.900000" elapsed="0"/></kw><status status="FAIL" start="2024-12-17T11:27:23.900000" elapsed="0"/></branch>
+<branch type="EXCEPT" pattern_type="glob" assign="${err}"><pattern>Error*</pattern><msg time="2024-12-17T11:27:23.900000" level="INFO">caught</msg><status status="PASS" start="2024-12-17T11:27:23.900000" elapsed="0"/></branch>
+<status status="PASS" start="2024-12-17T11:27:23.900000" elapsed="0"/>
+</try>
+<error><value>[Tmeout]</value><value>1 minute</value><msg time="2024-12-17T11:27:24.000000" level="FAIL">Non-existing setting 'Tmeout'.</msg><status status="FAIL" start="2024-12-17T11:27:24.000000" elapsed="0"/></error>
+<listener name="custom"><note>left by a listener</note></listener>
+<kw name="Close Cart" type="TEARDOWN"><status status="PASS" start="2024-12-17T11:27:24.100000" elapsed="0.1"/></kw>
+<timeout value="5 minutes"/>
+<status status="FAIL" start="2024-12-17T11:27:23.700000" elapsed="0.5">Non-existing setting 'Tmeout'.</status>
+</test>
+<status status="FAIL" start="2024-12-17T11:27:23.700000" elapsed="0.5"/>
+</suite>
+</robot>"#;
+
+        assert!(parse_content(xml, false).is_err());
+
+        let events = parse_content(xml, true).unwrap();
+        let test = tests(&events)[0];
+        assert_eq!(test.timeout.as_deref(), Some("5 minutes"));
+        assert_eq!(test.setup_keyword.as_ref().unwrap().name, "Open Cart");
+        assert_eq!(test.teardown_keyword.as_ref().unwrap().name, "Close Cart");
+        assert_eq!(test.keywords.len(), 4);
+
+        let BaseBody::Keyword(pay) = &test.keywords[0] else {
+            panic!("Expected a keyword, got {:?}", test.keywords[0]);
+        };
+        assert_eq!(pay.source_name.as_deref(), Some("Pay ${amount} euros"));
+        assert_eq!(pay.timeout.as_deref(), Some("1 minute"));
+
+        let BaseBody::Try(try_block) = &test.keywords[1] else {
+            panic!("Expected a TRY, got {:?}", test.keywords[1]);
+        };
+        let except = &try_block.branches[1];
+        assert_eq!(except.patterns, vec!["Error*"]);
+        assert_eq!(except.assign.as_deref(), Some("${err}"));
+        assert!(
+            matches!(&except.children[0], BaseBody::Message(message) if message.value == "caught")
+        );
+
+        let BaseBody::Error(error) = &test.keywords[2] else {
+            panic!("Expected an error, got {:?}", test.keywords[2]);
+        };
+        assert_eq!(error.values, vec!["[Tmeout]", "1 minute"]);
+        assert_eq!(error.msg[0].level, "FAIL");
+
+        assert_eq!(
+            test.keywords[3],
+            BaseBody::Unknown(serde_json::json!({
+                "element": "listener",
+                "attributes": {"name": "custom"},
+                "children": [{"element": "note", "text": "left by a listener"}]
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_json_unknown_body_item() {
+        let json = r#"{"generator": "Robot 8.0", "generated": "2024-12-17T11:27:23.676544",
+            "suite": {"name": "Shop", "tests": [{"name": "Checkout",
+                "setup": {"name": "Open Cart", "status": "PASS"},
+                "body": [
+                    {"type": "ERROR", "values": ["[Tmeout]"], "status": "FAIL"},
+                    {"type": "WAIT", "seconds": 3, "status": "PASS"}
+                ],
+                "status": "FAIL"}], "status": "FAIL"}}"#;
+
+        assert!(parse_content(json, false).is_err());
+
+        let events = parse_content(json, true).unwrap();
+        let test = tests(&events)[0];
+        assert_eq!(
+            test.setup_keyword
+                .as_ref()
+                .and_then(|keyword| keyword.type_.as_deref()),
+            Some("SETUP")
+        );
+        assert!(
+            matches!(&test.keywords[0], BaseBody::Error(error) if error.values == ["[Tmeout]"])
+        );
+        assert_eq!(
+            test.keywords[1],
+            BaseBody::Unknown(serde_json::json!({"type": "WAIT", "seconds": 3, "status": "PASS"}))
+        );
+    }
+}
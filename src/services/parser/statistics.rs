@@ -0,0 +1,135 @@
+use std::collections::BTreeMap;
+
+use super::{
+    OutputEvent, Statistics, StatisticsSuites, StatisticsTag, StatisticsTags, StatisticsTotal,
+    SuiteStart,
+};
+
+/// Rebuilds the `<statistics>` block for formats that do not record it, from
+/// the events as they are emitted. Suites are listed in document order with
+/// their full dotted name, tags alphabetically, like Robot Framework does.
+#[derive(Default)]
+pub struct StatisticsBuilder {
+    total: Counts,
+    tags: BTreeMap<String, Counts>,
+    suites: Vec<SuiteCounts>,
+    open_suites: Vec<usize>,
+}
+
+#[derive(Default, Clone, Copy)]
+struct Counts {
+    pass: u32,
+    fail: u32,
+    skip: u32,
+}
+
+struct SuiteCounts {
+    id: String,
+    name: String,
+    full_name: String,
+    counts: Counts,
+}
+
+impl Counts {
+    fn add(&mut self, status: &str) {
+        match status {
+            "PASS" => self.pass += 1,
+            "FAIL" => self.fail += 1,
+            "SKIP" => self.skip += 1,
+            _ => {}
+        }
+    }
+
+    fn to_stat(self, text: String, id: Option<String>, name: Option<String>) -> StatisticsTag {
+        StatisticsTag {
+            pass: self.pass,
+            fail: self.fail,
+            skip: self.skip,
+            id,
+            name,
+            text,
+        }
+    }
+}
+
+impl StatisticsBuilder {
+    pub fn record(&mut self, event: &OutputEvent) {
+        match event {
+            OutputEvent::SuiteStart(suite) => self.start_suite(&suite.id, &suite.name),
+            OutputEvent::SuiteEnd(_) => self.end_suite(),
+            OutputEvent::Test(test) => self.add_test(&test.status.status, &test.tags),
+            _ => {}
+        }
+    }
+
+    pub fn start_suite(&mut self, id: &str, name: &str) {
+        let full_name = match self.open_suites.last() {
+            Some(&parent) => format!("{}.{}", self.suites[parent].full_name, name),
+            None => name.to_string(),
+        };
+        self.open_suites.push(self.suites.len());
+        self.suites.push(SuiteCounts {
+            id: id.to_string(),
+            name: name.to_string(),
+            full_name,
+            counts: Counts::default(),
+        });
+    }
+
+    pub fn end_suite(&mut self) {
+        self.open_suites.pop();
+    }
+
+    pub fn add_test(&mut self, status: &str, tags: &[String]) {
+        self.total.add(status);
+        for tag in tags {
+            self.tags.entry(tag.clone()).or_default().add(status);
+        }
+        for &suite in &self.open_suites {
+            self.suites[suite].counts.add(status);
+        }
+    }
+
+    /// Adds a root suite above everything recorded so far, for outputs
+    /// combined after being parsed.
+    pub fn nest_under(&mut self, root: &SuiteStart) {
+        for suite in &mut self.suites {
+            suite.full_name = format!("{}.{}", root.name, suite.full_name);
+        }
+        self.suites.insert(
+            0,
+            SuiteCounts {
+                id: root.id.clone(),
+                name: root.name.clone(),
+                full_name: root.name.clone(),
+                counts: self.total,
+            },
+        );
+    }
+
+    pub fn build(self) -> Statistics {
+        Statistics {
+            total: StatisticsTotal {
+                stats: self.total.to_stat("All Tests".to_string(), None, None),
+            },
+            tags: StatisticsTags {
+                stats: self
+                    .tags
+                    .into_iter()
+                    .map(|(tag, counts)| counts.to_stat(tag, None, None))
+                    .collect(),
+            },
+            suites: StatisticsSuites {
+                stats: self
+                    .suites
+                    .into_iter()
+                    .map(|suite| {
+                        suite
+                            .counts
+                            .to_stat(suite.full_name, Some(suite.id), Some(suite.name))
+                    })
+                    .collect(),
+            },
+        }
+    }
+}
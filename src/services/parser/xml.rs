@@ -0,0 +1,386 @@
+use std::io::BufRead;
+
+use quick_xml::{
+    events::{BytesEnd, BytesStart, BytesText, Event},
+    Reader, Writer,
+};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+use super::{
+    Keyword, OutputEvent, OutputFormat, ParserError, Status, SuiteEnd, SuiteStart, Test, TestRun,
+};
+
+/// Walks an output.xml file with a pull parser. Suites are descended into
+/// recursively, everything below a suite (tests, keywords, status, statistics,
+/// errors) is read as one element and deserialized on its own, so only the
+/// currently open suites and the current element are held in memory.
+/// JUnit reports are recognized by their root element and handed over to
+/// the `junit` module.
+pub fn parse<R: BufRead>(
+    source: R,
+    tolerant: bool,
+    emit: &mut impl FnMut(OutputEvent) -> Result<(), ParserError>,
+) -> Result<(), ParserError> {
+    let mut parser = XmlOutputParser {
+        reader: Reader::from_reader(source),
+        buf: Vec::new(),
+        tolerant,
+    };
+    parser.parse_document(emit)
+}
+
+/// Elements of the output.xml schema, any version. In tolerant mode the
+/// others are replaced by an `<unknown>` element holding them as JSON.
+const KNOWN_ELEMENTS: &[&[u8]] = &[
+    b"robot",
+    b"suite",
+    b"test",
+    b"kw",
+    b"for",
+    b"iter",
+    b"while",
+    b"group",
+    b"if",
+    b"branch",
+    b"try",
+    b"variable",
+    b"return",
+    b"continue",
+    b"break",
+    b"error",
+    b"msg",
+    b"var",
+    b"arg",
+    b"doc",
+    b"tag",
+    b"timeout",
+    b"status",
+    b"value",
+    b"pattern",
+    b"meta",
+    b"statistics",
+    b"total",
+    b"stat",
+    b"errors",
+];
+
+pub(super) struct XmlOutputParser<R> {
+    reader: Reader<R>,
+    buf: Vec<u8>,
+    tolerant: bool,
+}
+
+impl<R: BufRead> XmlOutputParser<R> {
+    fn parse_document(
+        &mut self,
+        emit: &mut impl FnMut(OutputEvent) -> Result<(), ParserError>,
+    ) -> Result<(), ParserError> {
+        loop {
+            match self.next_event()? {
+                Event::Start(start) if start.name().as_ref() == b"robot" => {
+                    emit(OutputEvent::TestRun(parse_test_run(&start)?))?;
+                    return self.parse_robot(emit);
+                }
+                Event::Start(start)
+                    if matches!(start.name().as_ref(), b"testsuites" | b"testsuite") =>
+                {
+                    // The JUnit model ignores the elements it does not know.
+                    self.tolerant = false;
+                    return self.parse_junit(start, emit);
+                }
+                Event::Start(start) => {
+                    return Err(ParserError::Malformed(format!(
+                        "unexpected root element <{}>",
+                        String::from_utf8_lossy(start.name().as_ref())
+                    )))
+                }
+                Event::Eof => {
+                    return Err(ParserError::Malformed(
+                        "missing <robot> element".to_string(),
+                    ))
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn parse_robot(
+        &mut self,
+        emit: &mut impl FnMut(OutputEvent) -> Result<(), ParserError>,
+    ) -> Result<(), ParserError> {
+        loop {
+            match self.next_event()? {
+                Event::Start(start) => match start.name().as_ref() {
+                    b"suite" => self.parse_suite(start, emit)?,
+                    b"statistics" => emit(OutputEvent::Statistics(self.read_element(start)?))?,
+                    b"errors" => emit(OutputEvent::Errors(self.read_element(start)?))?,
+                    _ => self.skip_element(start)?,
+                },
+                Event::Empty(start) if start.name().as_ref() == b"errors" => {
+                    emit(OutputEvent::Errors(self.read_empty_element(start)?))?
+                }
+                Event::End(_) => return Ok(()),
+                Event::Eof => return Err(unexpected_eof("robot")),
+                _ => {}
+            }
+        }
+    }
+
+    fn parse_suite(
+        &mut self,
+        start: BytesStart<'static>,
+        emit: &mut impl FnMut(OutputEvent) -> Result<(), ParserError>,
+    ) -> Result<(), ParserError> {
+        let suite_start = parse_suite_start(&start)?;
+        let suite_id = suite_start.id.clone();
+        emit(OutputEvent::SuiteStart(suite_start))?;
+
+        let mut status: Option<Status> = None;
+        let mut doc = None;
+        let mut metadata = Vec::new();
+        let mut setup_keyword = None;
+        let mut teardown_keyword = None;
+        let mut has_children = false;
+
+        loop {
+            match self.next_event()? {
+                Event::Start(child) => match child.name().as_ref() {
+                    b"suite" => {
+                        has_children = true;
+                        self.parse_suite(child, emit)?;
+                    }
+                    b"test" => {
+                        has_children = true;
+                        let mut test: Test = self.read_element(child)?;
+                        test.split_setup_and_teardown();
+                        emit(OutputEvent::Test(Box::new(test)))?;
+                    }
+                    b"kw" => {
+                        let keyword: Keyword = self.read_element(child)?;
+                        let is_teardown = keyword.type_.as_deref() == Some("TEARDOWN");
+                        if is_teardown || (has_children && keyword.type_.is_none()) {
+                            teardown_keyword = Some(keyword);
+                        } else {
+                            setup_keyword = Some(keyword);
+                        }
+                    }
+                    b"status" => status = Some(self.read_element(child)?),
+                    b"doc" => doc = Some(self.read_text(child)?),
+                    b"meta" => {
+                        let name = required_attribute(&child, "name")?;
+                        metadata.push((name, self.read_text(child)?));
+                    }
+                    _ => self.skip_element(child)?,
+                },
+                Event::Empty(child) if child.name().as_ref() == b"status" => {
+                    status = Some(self.read_empty_element(child)?)
+                }
+                Event::Empty(child) if child.name().as_ref() == b"meta" => {
+                    metadata.push((required_attribute(&child, "name")?, String::new()))
+                }
+                Event::End(_) => break,
+                Event::Eof => return Err(unexpected_eof("suite")),
+                _ => {}
+            }
+        }
+
+        let status = status
+            .ok_or_else(|| ParserError::Malformed(format!("suite {} has no status", suite_id)))?;
+
+        emit(OutputEvent::SuiteEnd(Box::new(SuiteEnd {
+            status,
+            doc,
+            metadata,
+            setup_keyword,
+            teardown_keyword,
+        })))
+    }
+
+    pub(super) fn next_event(&mut self) -> Result<Event<'static>, ParserError> {
+        self.buf.clear();
+        let event = self.reader.read_event_into(&mut self.buf)?;
+        Ok(event.into_owned())
+    }
+
+    /// Copies the element and all its descendants into a standalone XML
+    /// document and deserializes it with the serde model.
+    pub(super) fn read_element<T: DeserializeOwned>(
+        &mut self,
+        start: BytesStart<'static>,
+    ) -> Result<T, ParserError> {
+        let mut writer = Writer::new(Vec::new());
+        writer.write_event(Event::Start(start))?;
+
+        let mut depth = 0;
+        loop {
+            let event = self.next_event()?;
+            match &event {
+                Event::Start(child) if self.is_unknown(child) => {
+                    let raw = self.read_raw_element(child.clone())?;
+                    write_unknown(&mut writer, raw)?;
+                    continue;
+                }
+                Event::Empty(child) if self.is_unknown(child) => {
+                    write_unknown(&mut writer, raw_element(child, Vec::new(), String::new())?)?;
+                    continue;
+                }
+                Event::Start(_) => depth += 1,
+                Event::End(_) if depth == 0 => {
+                    writer.write_event(event)?;
+                    break;
+                }
+                Event::End(_) => depth -= 1,
+                Event::Eof => return Err(unexpected_eof("element")),
+                _ => {}
+            }
+            writer.write_event(event)?;
+        }
+
+        deserialize(writer.into_inner())
+    }
+
+    pub(super) fn read_empty_element<T: DeserializeOwned>(
+        &mut self,
+        start: BytesStart<'static>,
+    ) -> Result<T, ParserError> {
+        let mut writer = Writer::new(Vec::new());
+        writer.write_event(Event::Empty(start))?;
+        deserialize(writer.into_inner())
+    }
+
+    fn is_unknown(&self, element: &BytesStart) -> bool {
+        self.tolerant && !KNOWN_ELEMENTS.contains(&element.name().as_ref())
+    }
+
+    /// The element as `{"element": name, "attributes": {..}, "children": [..],
+    /// "text": ".."}`, whatever it contains.
+    fn read_raw_element(&mut self, start: BytesStart<'static>) -> Result<Value, ParserError> {
+        let mut children = Vec::new();
+        let mut text = String::new();
+        loop {
+            match self.next_event()? {
+                Event::Start(child) => children.push(self.read_raw_element(child)?),
+                Event::Empty(child) => {
+                    children.push(raw_element(&child, Vec::new(), String::new())?)
+                }
+                Event::Text(content) => text.push_str(&content.unescape()?),
+                Event::CData(content) => text.push_str(&String::from_utf8_lossy(&content)),
+                Event::End(_) => return raw_element(&start, children, text),
+                Event::Eof => return Err(unexpected_eof("element")),
+                _ => {}
+            }
+        }
+    }
+
+    fn read_text(&mut self, start: BytesStart<'static>) -> Result<String, ParserError> {
+        let mut text = String::new();
+        loop {
+            match self.next_event()? {
+                Event::Text(content) => text.push_str(&content.unescape()?),
+                Event::CData(content) => text.push_str(&String::from_utf8_lossy(&content)),
+                Event::End(_) => return Ok(text),
+                Event::Start(child) => self.skip_element(child)?,
+                Event::Eof => {
+                    return Err(unexpected_eof(&String::from_utf8_lossy(
+                        start.name().as_ref(),
+                    )))
+                }
+                _ => {}
+            }
+        }
+    }
+
+    pub(super) fn skip_element(&mut self, start: BytesStart<'static>) -> Result<(), ParserError> {
+        self.buf.clear();
+        self.reader.read_to_end_into(start.name(), &mut self.buf)?;
+        Ok(())
+    }
+}
+
+fn deserialize<T: DeserializeOwned>(content: Vec<u8>) -> Result<T, ParserError> {
+    let content = String::from_utf8(content)
+        .map_err(|e| ParserError::Malformed(format!("invalid UTF-8: {}", e)))?;
+    Ok(quick_xml::de::from_str(&content)?)
+}
+
+fn raw_element(
+    start: &BytesStart,
+    children: Vec<Value>,
+    text: String,
+) -> Result<Value, ParserError> {
+    let mut attributes = Map::new();
+    for attribute in start.attributes() {
+        let attribute = attribute.map_err(quick_xml::Error::from)?;
+        attributes.insert(
+            String::from_utf8_lossy(attribute.key.as_ref()).into_owned(),
+            Value::String(attribute.unescape_value()?.into_owned()),
+        );
+    }
+
+    let mut element = Map::new();
+    element.insert(
+        "element".to_string(),
+        Value::String(String::from_utf8_lossy(start.name().as_ref()).into_owned()),
+    );
+    if !attributes.is_empty() {
+        element.insert("attributes".to_string(), Value::Object(attributes));
+    }
+    if !children.is_empty() {
+        element.insert("children".to_string(), Value::Array(children));
+    }
+    if !text.trim().is_empty() {
+        element.insert("text".to_string(), Value::String(text));
+    }
+    Ok(Value::Object(element))
+}
+
+/// Read back as `BaseBody::Unknown` by the serde model.
+fn write_unknown(writer: &mut Writer<Vec<u8>>, raw: Value) -> Result<(), ParserError> {
+    writer.write_event(Event::Start(BytesStart::new("unknown")))?;
+    writer.write_event(Event::Text(BytesText::new(&raw.to_string())))?;
+    writer.write_event(Event::End(BytesEnd::new("unknown")))?;
+    Ok(())
+}
+
+fn parse_test_run(start: &BytesStart) -> Result<TestRun, ParserError> {
+    Ok(TestRun {
+        format: OutputFormat::Xml,
+        generator: required_attribute(start, "generator")?,
+        generated_date: required_attribute(start, "generated")?,
+        rpa: attribute(start, "rpa")?.is_some_and(|rpa| rpa == "true"),
+        schema_version: required_attribute(start, "schemaversion")?,
+    })
+}
+
+fn parse_suite_start(start: &BytesStart) -> Result<SuiteStart, ParserError> {
+    Ok(SuiteStart {
+        id: required_attribute(start, "id")?,
+        name: required_attribute(start, "name")?,
+        source_file: attribute(start, "source")?,
+    })
+}
+
+pub(super) fn attribute(start: &BytesStart, name: &str) -> Result<Option<String>, ParserError> {
+    match start
+        .try_get_attribute(name)
+        .map_err(quick_xml::Error::from)?
+    {
+        Some(attribute) => Ok(Some(attribute.unescape_value()?.into_owned())),
+        None => Ok(None),
+    }
+}
+
+fn required_attribute(start: &BytesStart, name: &str) -> Result<String, ParserError> {
+    attribute(start, name)?.ok_or_else(|| {
+        ParserError::Malformed(format!(
+            "missing attribute {} on <{}>",
+            name,
+            String::from_utf8_lossy(start.name().as_ref())
+        ))
+    })
+}
+
+pub(super) fn unexpected_eof(element: &str) -> ParserError {
+    ParserError::Malformed(format!("unexpected end of file inside <{}>", element))
+}
@@ -1,11 +1,17 @@
-use std::sync::Arc;
+use std::{cmp::Reverse, sync::Arc};
 
 use tracing::info;
 
 use crate::{
-    models::projects::{
-        api::{ProjectOverviewResponse, ProjectResponse},
-        domain::NewProject,
+    models::{
+        projects::{
+            api::{ApiTestRunPage, ProjectOverviewResponse, ProjectResponse},
+            domain::NewProject,
+        },
+        robot::{
+            api::{ApiFlakyTest, ApiProjectFailureGroup, ApiTestHistory},
+            domain::{TestHistoryRequest, TestIdentity, TestRunFilter, TestRunPageRequest},
+        },
     },
     repositories::projects::ProjectsRepository,
     utils,
@@ -40,18 +46,22 @@ impl ProjectsService {
         let project_overviews = projects
             .iter()
             .map(|project| {
-                let test_run_data = projects_test_run_data
+                // Most recently run context first.
+                let mut contexts: Vec<_> = projects_test_run_data
                     .iter()
-                    .find(|data| data.project_id == project.id);
+                    .filter(|data| data.project_id == project.id)
+                    .collect();
+                contexts.sort_by_key(|data| Reverse(data.test_run_date));
 
-                let test_run_count = test_run_data.map(|data| data.test_run_count).unwrap_or(0);
+                let test_run_count = contexts.iter().map(|data| data.test_run_count).sum();
 
                 ProjectOverviewResponse {
                     id: project.id,
                     name: project.name.clone(),
                     create_date: utils::date::format_datetime(project.create_date),
-                    test_run_count: test_run_count,
-                    last_test_run_summary: test_run_data.map(|data| data.to_api()),
+                    test_run_count,
+                    last_test_run_summary: contexts.first().map(|data| data.to_api()),
+                    contexts: contexts.iter().map(|data| data.to_context_api()).collect(),
                 }
             })
             .collect();
@@ -83,17 +93,97 @@ impl ProjectsService {
     pub async fn get_project_by_id(
         &self,
         project_id: i32,
+        filter: &TestRunFilter,
     ) -> Result<Option<ProjectResponse>, Box<dyn std::error::Error>> {
         let project_data = self.repository.get_project_by_id(project_id).await?;
         match project_data {
             Some(project_data) => {
                 let test_runs_summaries = self
                     .robot_service
-                    .get_test_runs_summaries_by_project_id(project_id)
+                    .get_test_runs_summaries_by_project_id(project_id, filter)
                     .await?;
                 Ok(Some(project_data.to_project_response(test_runs_summaries)))
             }
             None => Ok(None),
         }
     }
+
+    pub async fn get_test_runs_page(
+        &self,
+        project_id: i32,
+        filter: &TestRunFilter,
+        page: &TestRunPageRequest,
+    ) -> Result<Option<ApiTestRunPage>, Box<dyn std::error::Error>> {
+        if self
+            .repository
+            .get_project_by_id(project_id)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+        let test_runs = self
+            .robot_service
+            .get_test_runs_page_by_project_id(project_id, filter, page)
+            .await?;
+        Ok(Some(test_runs.to_api()))
+    }
+
+    pub async fn get_flaky_tests(
+        &self,
+        project_id: i32,
+        limit: i64,
+    ) -> Result<Option<Vec<ApiFlakyTest>>, Box<dyn std::error::Error>> {
+        if self
+            .repository
+            .get_project_by_id(project_id)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+        let tests = self
+            .robot_service
+            .get_flaky_tests_by_project_id(project_id, limit)
+            .await?;
+        Ok(Some(tests.iter().map(|test| test.to_api()).collect()))
+    }
+
+    pub async fn get_failure_groups(
+        &self,
+        project_id: i32,
+        filter: &TestRunFilter,
+        limit: i64,
+    ) -> Result<Option<Vec<ApiProjectFailureGroup>>, Box<dyn std::error::Error>> {
+        if self
+            .repository
+            .get_project_by_id(project_id)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+        let groups = self
+            .robot_service
+            .get_failure_groups_by_project_id(project_id, filter, limit)
+            .await?;
+        Ok(Some(groups.iter().map(|group| group.to_api()).collect()))
+    }
+
+    pub async fn get_test_history(
+        &self,
+        test: TestIdentity,
+        request: &TestHistoryRequest,
+    ) -> Result<Option<ApiTestHistory>, Box<dyn std::error::Error>> {
+        if self
+            .repository
+            .get_project_by_id(test.project_id)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+        let history = self.robot_service.get_test_history(test, request).await?;
+        Ok(Some(history.to_api()))
+    }
 }
@@ -1,19 +1,219 @@
-use serde_json::Value;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs::File,
+    future::Future,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use futures_util::{stream, StreamExt};
+
+use itertools::Itertools;
+use sha1::Digest;
+use sqlx::PgConnection;
+use tokio::{io::AsyncReadExt, runtime::Handle, sync::mpsc};
 use tracing::{info, warn};
 
 use crate::{
     models::{
         self,
-        robot::domain::{ProjectTestRunSummary, SuiteKeywords},
+        robot::{
+            db::RerunTargetDB,
+            domain::{
+                ArtifactContent, CiMetadata, ContentChunks, FailureGroup, FlakyTest, OutputFile,
+                ProjectFailureGroup, ProjectTestRunSummary, SearchMatch, SearchRequest,
+                StatisticType, SuiteKeywords, SuiteNode, SuiteTestPage, SuiteTestsRequest,
+                TagStatisticChange, TestAttempt, TestHistory, TestHistoryRequest, TestIdentity,
+                TestKeywords, TestRunComparison, TestRunFilter, TestRunPage, TestRunPageRequest,
+                TestRunStatistic, TestRunSuite,
+            },
+        },
+        robot_legacy::{ErrorDBLegacy, SuiteDBLegacy, TestDBLegacy, TestRunDBLegacy},
     },
-    repositories::robot::RobotRepository,
+    repositories::robot::{RobotRepository, SuiteRow, TestRow},
 };
 
-use super::{mappers, parser::TestRun};
+use super::{
+    archive::{self, Artifact},
+    mappers::{self, robot::TimestampFormat},
+    parser::{
+        OutputEvent, OutputEventStream, RobotOutputParserService, StatisticsBuilder, SuiteStart,
+        TestRun,
+    },
+};
 
+pub use super::mappers::robot::MappingErrors;
+
+/// Pending tests written in one go, which is what makes large imports fast.
+const TEST_BATCH_SIZE: usize = 1000;
+const SUITE_ID_BLOCK: i32 = 100;
+const TEST_ID_BLOCK: i32 = 1000;
+/// Large contents, e.g. artifacts, are written in chunks of this size.
+const CONTENT_CHUNK_SIZE: u64 = 1024 * 1024;
+
+#[derive(Default)]
 pub struct TestRunMetadata {
     pub app_name: String,
     pub app_version: String,
+    pub environment: Option<String>,
+    pub branch: Option<String>,
+    pub ci: CiMetadata,
+    /// Sorted by name.
+    pub labels: Vec<(String, String)>,
+}
+
+/// Outcome of an upload, which gives the existing test run when its output
+/// files were already imported.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImportedTestRun {
+    Created(i32),
+    Existing(i32),
+}
+
+/// An uploaded output file being parsed.
+pub struct OutputPart {
+    pub file_name: String,
+    pub file: PathBuf,
+    pub events: OutputEventStream,
+}
+
+/// What is kept of an output file once its suites and tests are written.
+struct WrittenPart {
+    header: TestRun,
+    root_suite: SuiteDBLegacy,
+    sha1: String,
+}
+
+/// Where the suites of an output go when several outputs are combined.
+struct Nesting {
+    parent_suite_id: i32,
+    prefix: String,
+}
+
+impl Nesting {
+    /// `s1-s1-t1` of the second output becomes `s1-s2-s1-t1`.
+    fn identifier(&self, id: &str) -> String {
+        match id.strip_prefix("s1") {
+            Some(rest) => format!("{}{}", self.prefix, rest),
+            None => format!("{}-{}", self.prefix, id),
+        }
+    }
+
+    fn nest(&self, event: &mut OutputEvent) {
+        match event {
+            OutputEvent::SuiteStart(suite_start) => {
+                suite_start.id = self.identifier(&suite_start.id)
+            }
+            OutputEvent::Test(test) => test.id = self.identifier(&test.id),
+            _ => {}
+        }
+    }
+}
+
+/// A suite of the test run a rerun is merged into, with its current tests by
+/// name.
+struct RerunSuite {
+    id: i32,
+    identifier: String,
+    tests: HashMap<String, RerunTest>,
+    test_count: usize,
+}
+
+struct RerunTest {
+    id: i32,
+    identifier: String,
+    attempt: i32,
+}
+
+/// Tests written by a rerun, with the earlier attempts they replace.
+#[derive(Default)]
+struct WrittenRerun {
+    test_ids: Vec<i32>,
+    replaced: Vec<(i32, i32)>,
+}
+
+/// Buffers the suites and tests of a run and writes them in batches. Their
+/// ids are reserved in blocks, so that children can reference a suite before
+/// it is written.
+struct TreeWriter<'a> {
+    repository: &'a RobotRepository,
+    test_run_id: i32,
+    suite_ids: Vec<i32>,
+    test_ids: Vec<i32>,
+    suites: Vec<SuiteRow>,
+    tests: Vec<TestRow>,
+}
+
+impl<'a> TreeWriter<'a> {
+    fn new(repository: &'a RobotRepository, test_run_id: i32) -> Self {
+        Self {
+            repository,
+            test_run_id,
+            suite_ids: Vec::new(),
+            test_ids: Vec::new(),
+            suites: Vec::new(),
+            tests: Vec::new(),
+        }
+    }
+
+    async fn reserve_suite_id(&mut self) -> Result<i32, sqlx::Error> {
+        if self.suite_ids.is_empty() {
+            self.suite_ids = self.repository.reserve_suite_ids(SUITE_ID_BLOCK).await?;
+            self.suite_ids.reverse();
+        }
+        Ok(self.suite_ids.pop().expect("reserved suite ids"))
+    }
+
+    async fn add_suite(
+        &mut self,
+        conn: &mut PgConnection,
+        id: i32,
+        parent_suite_id: Option<i32>,
+        full_name: String,
+        suite: SuiteDBLegacy,
+    ) -> Result<(), sqlx::Error> {
+        self.suites.push(SuiteRow {
+            id,
+            parent_suite_id,
+            full_name,
+            suite,
+        });
+        self.flush_full(conn).await
+    }
+
+    /// Returns the id the test will be written with.
+    async fn add_test(
+        &mut self,
+        conn: &mut PgConnection,
+        suite_id: i32,
+        test: TestDBLegacy,
+    ) -> Result<i32, sqlx::Error> {
+        if self.test_ids.is_empty() {
+            self.test_ids = self.repository.reserve_test_ids(TEST_ID_BLOCK).await?;
+            self.test_ids.reverse();
+        }
+        let id = self.test_ids.pop().expect("reserved test ids");
+        self.tests.push(TestRow { id, suite_id, test });
+        self.flush_full(conn).await?;
+        Ok(id)
+    }
+
+    async fn flush_full(&mut self, conn: &mut PgConnection) -> Result<(), sqlx::Error> {
+        if self.suites.len() + self.tests.len() >= TEST_BATCH_SIZE {
+            self.flush(conn).await?;
+        }
+        Ok(())
+    }
+
+    async fn flush(&mut self, conn: &mut PgConnection) -> Result<(), sqlx::Error> {
+        self.repository
+            .insert_suites(conn, self.test_run_id, &self.suites)
+            .await?;
+        self.suites.clear();
+        self.repository.insert_tests(conn, &self.tests).await?;
+        self.tests.clear();
+        Ok(())
+    }
 }
 
 pub struct RobotService {
@@ -25,29 +225,727 @@ impl RobotService {
         Self { repository }
     }
 
+    /// Writes the parsed events as they arrive, in one transaction so that a
+    /// failed import leaves nothing behind. The test run id is reserved up
+    /// front so suites and tests can reference it; the `test_runs` row itself is
+    /// written last, once the SHA-1 of the files is known. `saving` is awaited
+    /// then, once the output files are read. A single output file is hashed
+    /// first, so that one already imported is not parsed again.
     pub async fn save_test_run(
         &self,
-        parsed_test_run: TestRun,
+        mut parts: Vec<OutputPart>,
+        artifacts: Vec<Artifact>,
         metadata: TestRunMetadata,
         project_id: i32,
+        saving: impl Future<Output = ()>,
+    ) -> Result<ImportedTestRun, Box<dyn std::error::Error>> {
+        if let [part] = parts.as_slice() {
+            let path = part.file.clone();
+            let sha1 = tokio::task::spawn_blocking(move || file_sha1(&path)).await??;
+            if let Some(existing_id) = self.repository.get_test_run_id_by_sha1(&sha1).await? {
+                info!(
+                    "Test run with sha1 {} already exists, id: {}",
+                    sha1, existing_id
+                );
+                return Ok(ImportedTestRun::Existing(existing_id));
+            }
+            if self.repository.is_sha1_already_inserted(&sha1).await? {
+                warn!("Output file with sha1 {} already imported", sha1);
+                Err("Test run already imported")?;
+            }
+        }
+
+        let test_run_id = self.repository.reserve_test_run_id().await?;
+        let mut tx = self.repository.begin().await?;
+
+        let (test_run, sha1s) = self
+            .write_test_run(&mut tx, test_run_id, &mut parts, &artifacts, &metadata)
+            .await?;
+
+        // Dropping the transaction rolls back what was written.
+        if let Some(existing_id) = self
+            .repository
+            .get_test_run_id_by_sha1(&test_run.sha1)
+            .await?
+        {
+            info!(
+                "Test run with sha1 {} already exists, id: {}",
+                test_run.sha1, existing_id
+            );
+            return Ok(ImportedTestRun::Existing(existing_id));
+        }
+        for sha1 in &sha1s {
+            if self.repository.is_sha1_already_inserted(sha1).await? {
+                warn!("Output file with sha1 {} already imported", sha1);
+                Err("Test run already imported")?;
+            }
+        }
+
+        saving.await;
+        info!("Saving test run with sha1 {}", test_run.sha1);
+        let result = self
+            .write_test_run_row(&mut tx, test_run_id, &test_run, project_id, &parts, &sha1s)
+            .await;
+        if let Err(e) = result {
+            // A concurrent upload of the same files committed first.
+            if is_unique_violation(e.as_ref()) {
+                tx.rollback().await?;
+                if let Some(existing_id) = self
+                    .repository
+                    .get_test_run_id_by_sha1(&test_run.sha1)
+                    .await?
+                {
+                    info!(
+                        "Test run with sha1 {} imported concurrently, id: {}",
+                        test_run.sha1, existing_id
+                    );
+                    return Ok(ImportedTestRun::Existing(existing_id));
+                }
+                Err("Test run already imported")?;
+            }
+            return Err(e);
+        }
+        self.repository
+            .update_test_flakiness(&mut tx, test_run_id)
+            .await?;
+        tx.commit().await?;
+
+        info!("Saved test run, id: {}", test_run_id);
+        Ok(ImportedTestRun::Created(test_run_id))
+    }
+
+    /// Writes the `test_runs` row and keeps the output files it was imported
+    /// from. The unique SHA-1s make it fail if the files were imported since.
+    async fn write_test_run_row(
+        &self,
+        conn: &mut PgConnection,
+        test_run_id: i32,
+        test_run: &TestRunDBLegacy,
+        project_id: i32,
+        parts: &[OutputPart],
+        sha1s: &[String],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.repository
+            .insert_test_run(conn, test_run_id, test_run, project_id)
+            .await?;
+        for (index, (part, sha1)) in parts.iter().zip(sha1s).enumerate() {
+            self.store_output_file(conn, &part.file, sha1).await?;
+            self.repository
+                .insert_test_run_part(
+                    conn,
+                    test_run_id,
+                    index as i32,
+                    Some(&part.file_name),
+                    sha1,
+                    false,
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Several outputs (e.g. pabot shards) are combined the way `rebot` does:
+    /// their root suites become the children of a new root suite, errors are
+    /// kept from all of them and statistics are computed again. Returns the
+    /// `test_runs` row, left to the caller to write, and the SHA-1 of each
+    /// output.
+    async fn write_test_run(
+        &self,
+        conn: &mut PgConnection,
+        test_run_id: i32,
+        parts: &mut [OutputPart],
+        artifacts: &[Artifact],
+        metadata: &TestRunMetadata,
+    ) -> Result<(TestRunDBLegacy, Vec<String>), Box<dyn std::error::Error>> {
+        let mut tree = TreeWriter::new(&self.repository, test_run_id);
+        let combined_suite_id = match parts.len() {
+            0 => Err("No output file to import")?,
+            1 => None,
+            _ => Some(tree.reserve_suite_id().await?),
+        };
+        let mut statistics = combined_suite_id.map(|_| StatisticsBuilder::default());
+
+        let mut written_parts = Vec::new();
+        for (index, part) in parts.iter_mut().enumerate() {
+            let nesting = combined_suite_id.map(|parent_suite_id| Nesting {
+                parent_suite_id,
+                prefix: format!("s1-s{}", index + 1),
+            });
+            let written_part = self
+                .write_part(
+                    conn,
+                    &mut tree,
+                    &mut part.events,
+                    nesting.as_ref(),
+                    statistics.as_mut(),
+                )
+                .await?;
+            written_parts.push(written_part);
+        }
+
+        for (index, part) in written_parts.iter().enumerate() {
+            if written_parts[..index]
+                .iter()
+                .any(|other| other.sha1 == part.sha1)
+            {
+                Err(format!("{} was uploaded twice", parts[index].file_name))?;
+            }
+        }
+
+        self.write_artifacts(conn, test_run_id, artifacts).await?;
+
+        let headers: Vec<TestRun> = written_parts
+            .iter()
+            .map(|part| part.header.clone())
+            .collect();
+        let sha1s: Vec<String> = written_parts.iter().map(|part| part.sha1.clone()).collect();
+        let test_run = match combined_suite_id {
+            None => mappers::robot::map_test_run(&headers[0], &sha1s[0], metadata)?,
+            Some(combined_suite_id) => {
+                let root_suites: Vec<SuiteDBLegacy> = written_parts
+                    .into_iter()
+                    .map(|part| part.root_suite)
+                    .collect();
+                let combined_suite = mappers::robot::map_combined_suite(&root_suites);
+                let combined_suite_start = SuiteStart {
+                    id: combined_suite.identifier.clone(),
+                    name: combined_suite.name.clone(),
+                    source_file: None,
+                };
+                let full_name = combined_suite.name.clone();
+                tree.add_suite(conn, combined_suite_id, None, full_name, combined_suite)
+                    .await?;
+                tree.flush(conn).await?;
+
+                let mut statistics = statistics.take().ok_or("Missing statistics")?;
+                statistics.nest_under(&combined_suite_start);
+                let statistics = mappers::robot::map_statistics(&statistics.build());
+                self.repository
+                    .insert_statistics(conn, test_run_id, &statistics)
+                    .await?;
+
+                let sha1 = combined_sha1(&sha1s);
+                mappers::robot::map_combined_test_run(&headers, &sha1, metadata)?
+            }
+        };
+        Ok((test_run, sha1s))
+    }
+
+    async fn store_output_file(
+        &self,
+        conn: &mut PgConnection,
+        file: &Path,
+        sha1: &str,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let file_sha1 = parsed_test_run.sha1.as_ref();
-        if self.repository.is_sha1_already_inserted(file_sha1).await? {
-            warn!("Test run with sha1 {} already exists", &file_sha1);
+        if self.repository.is_output_file_stored(sha1).await? {
+            return Ok(());
+        }
+        let size = tokio::fs::metadata(file).await?.len() as i64;
+        let path = file.to_path_buf();
+        let compressed =
+            tokio::task::spawn_blocking(move || archive::compress_file(&path)).await??;
+        let (content_id, _) = self.store_content(conn, compressed.path()).await?;
+        if !self
+            .repository
+            .insert_output_file(conn, sha1, size, content_id)
+            .await?
+        {
+            self.repository.delete_content(conn, content_id).await?;
+        }
+        Ok(())
+    }
+
+    /// Writes the suites, tests and errors of one output file. Statistics are
+    /// written too, unless the output is nested into a combined run. Values
+    /// that cannot be stored are all collected before failing.
+    async fn write_part(
+        &self,
+        conn: &mut PgConnection,
+        tree: &mut TreeWriter<'_>,
+        events: &mut OutputEventStream,
+        nesting: Option<&Nesting>,
+        mut statistics: Option<&mut StatisticsBuilder>,
+    ) -> Result<WrittenPart, Box<dyn std::error::Error>> {
+        let mut header = None;
+        let mut format = TimestampFormat::Legacy;
+        let mut open_suites: Vec<(i32, SuiteStart)> = Vec::new();
+        let mut root_suite = None;
+        let mut mapping_errors = MappingErrors::default();
+
+        while let Some(event) = events.next().await {
+            let mut event = event?;
+            if let Some(nesting) = nesting {
+                nesting.nest(&mut event);
+            }
+            if let Some(statistics) = statistics.as_deref_mut() {
+                statistics.record(&event);
+            }
+
+            match event {
+                OutputEvent::TestRun(test_run) => {
+                    format = TimestampFormat::of(&test_run);
+                    header = Some(test_run);
+                }
+                OutputEvent::SuiteStart(suite_start) => {
+                    let suite_id = tree.reserve_suite_id().await?;
+                    open_suites.push((suite_id, suite_start));
+                }
+                OutputEvent::Test(test) => {
+                    let (suite_id, _) = open_suites.last().ok_or("Test outside of a suite")?;
+                    if let Some(test) = mapping_errors.take(mappers::robot::map_test(*test, format))
+                    {
+                        tree.add_test(conn, *suite_id, test).await?;
+                    }
+                }
+                OutputEvent::SuiteEnd(suite_end) => {
+                    let (suite_id, suite_start) =
+                        open_suites.pop().ok_or("Suite end without a suite")?;
+                    let full_name = open_suites
+                        .iter()
+                        .map(|(_, parent)| parent.name.as_str())
+                        .chain([suite_start.name.as_str()])
+                        .join(".");
+                    let parent_suite_id = match open_suites.last() {
+                        Some((parent_suite_id, _)) => Some(*parent_suite_id),
+                        None => nesting.map(|nesting| nesting.parent_suite_id),
+                    };
+                    let suite = mappers::robot::map_suite(&suite_start, *suite_end, format);
+                    let Some(suite) = mapping_errors.take(suite) else {
+                        continue;
+                    };
+                    if open_suites.is_empty() {
+                        root_suite = Some(suite.clone());
+                    }
+                    tree.add_suite(conn, suite_id, parent_suite_id, full_name, suite)
+                        .await?;
+                }
+                OutputEvent::Statistics(parsed_statistics) => {
+                    if statistics.is_none() {
+                        let parsed_statistics = mappers::robot::map_statistics(&parsed_statistics);
+                        self.repository
+                            .insert_statistics(conn, tree.test_run_id, &parsed_statistics)
+                            .await?;
+                    }
+                }
+                OutputEvent::Errors(errors) => {
+                    if let Some(errors) =
+                        mapping_errors.take(mappers::robot::map_errors(&errors, format))
+                    {
+                        self.repository
+                            .insert_errors(conn, tree.test_run_id, &errors)
+                            .await?;
+                    }
+                }
+                OutputEvent::Finished { sha1 } => {
+                    if !mapping_errors.is_empty() {
+                        Err(mapping_errors)?;
+                    }
+                    tree.flush(conn).await?;
+                    return Ok(WrittenPart {
+                        header: header.ok_or("Missing test run header")?,
+                        root_suite: root_suite.ok_or("Missing root suite")?,
+                        sha1,
+                    });
+                }
+            }
+        }
+
+        Err("Output file parsing ended unexpectedly")?
+    }
+
+    /// Artifacts of several archives may clash, the first one is kept.
+    async fn write_artifacts(
+        &self,
+        conn: &mut PgConnection,
+        test_run_id: i32,
+        artifacts: &[Artifact],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut paths = HashSet::new();
+        for artifact in artifacts {
+            if !paths.insert(artifact.path.as_str()) {
+                warn!("Ignoring artifact {} found twice", artifact.path);
+                continue;
+            }
+            let (content_id, size) = self.store_content(conn, &artifact.file).await?;
+            let content_type = mime_guess::from_path(&artifact.path).first_or_octet_stream();
+            self.repository
+                .insert_test_run_artifact(
+                    conn,
+                    test_run_id,
+                    &artifact.path,
+                    content_type.as_ref(),
+                    size,
+                    content_id,
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Writes the file one chunk at a time, so that only one chunk is ever in
+    /// memory. Returns the id of the content and its size.
+    async fn store_content(
+        &self,
+        conn: &mut PgConnection,
+        file: &Path,
+    ) -> Result<(i64, i64), Box<dyn std::error::Error>> {
+        let content_id = self.repository.reserve_content_id().await?;
+        let mut file = tokio::fs::File::open(file).await?;
+        let mut chunk = Vec::with_capacity(CONTENT_CHUNK_SIZE as usize);
+        let mut position = 0;
+        let mut size = 0;
+        loop {
+            chunk.clear();
+            (&mut file)
+                .take(CONTENT_CHUNK_SIZE)
+                .read_to_end(&mut chunk)
+                .await?;
+            if chunk.is_empty() {
+                return Ok((content_id, size));
+            }
+            self.repository
+                .insert_content_chunk(conn, content_id, position, &chunk)
+                .await?;
+            position += 1;
+            size += chunk.len() as i64;
+        }
+    }
+
+    /// Merges the output of a rerun into a test run, like `rebot --merge`.
+    /// Tests are matched by suite full name and test name: the rerun result
+    /// replaces the current one, which is kept as an earlier attempt. Tests
+    /// the run did not have are added to their suite. Statistics and suite
+    /// statuses are then computed again. Returns `false` when there is no such
+    /// test run.
+    pub async fn save_rerun(
+        &self,
+        test_run_id: i32,
+        mut part: OutputPart,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let mut tx = self.repository.begin().await?;
+        let targets = self
+            .repository
+            .get_rerun_targets_by_test_run_id(&mut tx, test_run_id)
+            .await?;
+        if targets.is_empty() {
+            return Ok(false);
+        }
+        let mut suites = rerun_suites(targets);
+        let part_index = self
+            .repository
+            .get_test_run_parts_by_test_run_id(test_run_id)
+            .await?
+            .len() as i32;
+
+        let mut written = WrittenRerun::default();
+        let sha1 = self
+            .write_rerun(&mut tx, test_run_id, &mut part, &mut suites, &mut written)
+            .await?;
+        if self.repository.is_sha1_already_inserted(&sha1).await? {
+            warn!("Test run with sha1 {} already exists", &sha1);
             Err("Test run already imported")?;
         }
+        self.store_output_file(&mut tx, &part.file, &sha1).await?;
+        self.repository
+            .insert_test_run_part(
+                &mut tx,
+                test_run_id,
+                part_index,
+                Some(&part.file_name),
+                &sha1,
+                true,
+            )
+            .await?;
+        self.repository
+            .recompute_test_flakiness(&mut tx, test_run_id)
+            .await?;
+        tx.commit().await?;
 
-        let test_run = mappers::robot::map_test_run(&parsed_test_run, &metadata)?;
+        info!(
+            "Merged rerun into test run {}, {} tests replaced, {} added",
+            test_run_id,
+            written.replaced.len(),
+            written.test_ids.len() - written.replaced.len()
+        );
+        Ok(true)
+    }
+
+    /// Writes the tests of the rerun and merges them into the test run.
+    /// Returns the SHA-1 of its output.
+    async fn write_rerun(
+        &self,
+        conn: &mut PgConnection,
+        test_run_id: i32,
+        part: &mut OutputPart,
+        suites: &mut HashMap<String, RerunSuite>,
+        written: &mut WrittenRerun,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let mut format = TimestampFormat::Legacy;
+        let mut suite_path: Vec<String> = Vec::new();
+        let mut errors = Vec::new();
+        let mut mapping_errors = MappingErrors::default();
+        let mut tree = TreeWriter::new(&self.repository, test_run_id);
+
+        let sha1 = loop {
+            let event = match part.events.next().await {
+                Some(event) => event?,
+                None => Err("Output file parsing ended unexpectedly")?,
+            };
+            match event {
+                OutputEvent::TestRun(test_run) => format = TimestampFormat::of(&test_run),
+                OutputEvent::SuiteStart(suite_start) => {
+                    suite_path.push(suite_start.name);
+                    let full_name = suite_path.join(".");
+                    if !suites.contains_key(&full_name) {
+                        Err(format!(
+                            "Suite {} is not part of test run {}",
+                            full_name, test_run_id
+                        ))?;
+                    }
+                }
+                OutputEvent::SuiteEnd(_) => {
+                    suite_path.pop();
+                }
+                OutputEvent::Test(test) => {
+                    let suite = suites
+                        .get_mut(&suite_path.join("."))
+                        .ok_or("Test outside of a suite")?;
+                    let Some(mut test) =
+                        mapping_errors.take(mappers::robot::map_test(*test, format))
+                    else {
+                        continue;
+                    };
+                    let previous = suite.tests.get(&test.name);
+                    match previous {
+                        Some(previous) => {
+                            test.identifier = previous.identifier.clone();
+                            test.attempt = previous.attempt + 1;
+                        }
+                        None => {
+                            suite.test_count += 1;
+                            test.identifier = format!("{}-t{}", suite.identifier, suite.test_count);
+                        }
+                    }
+                    let previous_id = previous.map(|previous| previous.id);
+                    let name = test.name.clone();
+                    let identifier = test.identifier.clone();
+                    let attempt = test.attempt;
+                    let test_id = tree.add_test(conn, suite.id, test).await?;
+                    written.test_ids.push(test_id);
+                    if let Some(previous_id) = previous_id {
+                        written.replaced.push((previous_id, test_id));
+                    }
+                    suite.tests.insert(
+                        name,
+                        RerunTest {
+                            id: test_id,
+                            identifier,
+                            attempt,
+                        },
+                    );
+                }
+                // Computed again from the merged tests.
+                OutputEvent::Statistics(_) => {}
+                OutputEvent::Errors(parsed_errors) => {
+                    let parsed_errors = mappers::robot::map_errors(&parsed_errors, format);
+                    errors = mapping_errors.take(parsed_errors).unwrap_or_default();
+                }
+                OutputEvent::Finished { sha1 } => break sha1,
+            }
+        };
+        if !mapping_errors.is_empty() {
+            Err(mapping_errors)?;
+        }
+        tree.flush(conn).await?;
 
-        info!("Saving test run with sha1 {}", file_sha1);
-        let test_run_id = self
+        let (previous_test_ids, test_ids): (Vec<i32>, Vec<i32>) =
+            written.replaced.iter().copied().unzip();
+        self.repository
+            .supersede_tests(conn, &previous_test_ids, &test_ids)
+            .await?;
+        self.merge_rerun_results(conn, test_run_id, &errors).await?;
+        Ok(sha1)
+    }
+
+    /// Updates statistics and suite statuses from the current tests of the
+    /// run and adds the errors of the rerun.
+    async fn merge_rerun_results(
+        &self,
+        conn: &mut PgConnection,
+        test_run_id: i32,
+        errors: &[ErrorDBLegacy],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let suites = self
             .repository
-            .insert_test_run(&test_run, project_id)
+            .get_suites_by_test_run_id(conn, test_run_id)
+            .await?;
+
+        let mut statistics = StatisticsBuilder::default();
+        for suite in &suites {
+            record_suite(&mut statistics, suite);
+        }
+        let statistics = statistics.build();
+
+        let mut suite_ids = Vec::new();
+        let mut statuses = Vec::new();
+        let mut pending = suites.iter().collect::<Vec<_>>();
+        while let Some(suite) = pending.pop() {
+            let stat = statistics
+                .suites
+                .stats
+                .iter()
+                .find(|stat| stat.id.as_deref() == Some(suite.identifier.as_str()));
+            let status = match stat {
+                Some(stat) if stat.fail > 0 => "FAIL",
+                Some(stat) if stat.pass > 0 => "PASS",
+                Some(stat) if stat.skip > 0 => "SKIP",
+                _ => suite.status.as_str(),
+            };
+            if status != suite.status {
+                suite_ids.push(suite.id);
+                statuses.push(status.to_string());
+            }
+            pending.extend(&suite.suites);
+        }
+        self.repository
+            .update_suite_statuses(conn, &suite_ids, &statuses)
+            .await?;
+
+        let statistics = mappers::robot::map_statistics(&statistics);
+        self.repository.delete_statistics(conn, test_run_id).await?;
+        self.repository
+            .insert_statistics(conn, test_run_id, &statistics)
+            .await?;
+        self.repository
+            .insert_errors(conn, test_run_id, errors)
             .await?;
-        info!("Saved test run, id: {}", test_run_id);
         Ok(())
     }
 
+    /// Parses the stored output files of a test run again, e.g. after a parser
+    /// fix, and replaces its suites, tests, statistics and errors while keeping
+    /// its id. Reruns are merged again in the order they were uploaded. The
+    /// new tree is written under a reserved id first, the old one is only
+    /// replaced once it is complete, all in one transaction. Returns `false`
+    /// when there is no such test run.
+    pub async fn reimport_test_run(
+        &self,
+        test_run_id: i32,
+        parser: &RobotOutputParserService,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let Some(metadata) = self.repository.get_test_run_metadata(test_run_id).await? else {
+            return Ok(false);
+        };
+        info!("Importing test run {} again", test_run_id);
+
+        let dir = tempfile::tempdir()?;
+        let mut outputs = Vec::new();
+        let mut reruns = Vec::new();
+        let parts = self
+            .repository
+            .get_test_run_parts_by_test_run_id(test_run_id)
+            .await?;
+        for (index, part) in parts.into_iter().enumerate() {
+            let output_file = self
+                .repository
+                .get_output_file(&part.sha1)
+                .await?
+                .ok_or_else(|| {
+                    format!(
+                        "The output file {} of test run {} is not stored",
+                        part.file_name.as_deref().unwrap_or(&part.sha1),
+                        test_run_id
+                    )
+                })?;
+            let file = dir.path().join(index.to_string());
+            let content = BlockingContentReader::new(output_file.content);
+            let path = file.clone();
+            tokio::task::spawn_blocking(move || {
+                io::copy(&mut archive::decompress(content), &mut File::create(path)?)
+            })
+            .await??;
+
+            let file_name = part.file_name.unwrap_or(part.sha1);
+            if part.rerun {
+                reruns.push((file_name, file));
+            } else {
+                outputs.push((file_name, file));
+            }
+        }
+
+        let new_test_run_id = self.repository.reserve_test_run_id().await?;
+        let mut tx = self.repository.begin().await?;
+        let result = self
+            .rewrite_test_run(&mut tx, new_test_run_id, outputs, reruns, &metadata, parser)
+            .await;
+        let result = match result {
+            Ok(test_run) => self
+                .repository
+                .replace_test_run_tree(&mut tx, test_run_id, new_test_run_id, &test_run)
+                .await
+                .map_err(Into::into),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            warn!("Import of test run {} again failed: {}", test_run_id, e);
+            return Err(e);
+        }
+        self.repository
+            .recompute_test_flakiness(&mut tx, test_run_id)
+            .await?;
+        tx.commit().await?;
+
+        info!("Imported test run {} again", test_run_id);
+        Ok(true)
+    }
+
+    async fn rewrite_test_run(
+        &self,
+        conn: &mut PgConnection,
+        test_run_id: i32,
+        outputs: Vec<(String, PathBuf)>,
+        reruns: Vec<(String, PathBuf)>,
+        metadata: &TestRunMetadata,
+        parser: &RobotOutputParserService,
+    ) -> Result<TestRunDBLegacy, Box<dyn std::error::Error>> {
+        let mut parts = Vec::new();
+        for (file_name, file) in outputs {
+            let events = parser.stream_file(file_name.clone(), &file)?;
+            parts.push(OutputPart {
+                file_name,
+                file,
+                events,
+            });
+        }
+        let (test_run, _) = self
+            .write_test_run(conn, test_run_id, &mut parts, &[], metadata)
+            .await?;
+
+        for (file_name, file) in reruns {
+            let events = parser.stream_file(file_name.clone(), &file)?;
+            let mut part = OutputPart {
+                file_name,
+                file,
+                events,
+            };
+            let targets = self
+                .repository
+                .get_rerun_targets_by_test_run_id(conn, test_run_id)
+                .await?;
+            self.write_rerun(
+                conn,
+                test_run_id,
+                &mut part,
+                &mut rerun_suites(targets),
+                &mut WrittenRerun::default(),
+            )
+            .await?;
+        }
+        Ok(test_run)
+    }
+
+    /// The latest run of each environment and branch of the projects, with
+    /// the number of runs of that context.
     pub async fn get_latest_test_runs_data_by_project_ids(
         &self,
         project_ids: &Vec<i32>,
@@ -63,17 +961,40 @@ impl RobotService {
         Ok(summaries)
     }
 
+    /// `metadata` keeps the runs whose root suite has all of these entries.
     pub async fn get_test_runs_summaries_by_project_id(
         &self,
         project_id: i32,
+        filter: &TestRunFilter,
     ) -> Result<Vec<ProjectTestRunSummary>, Box<dyn std::error::Error>> {
         let summaries = self
             .repository
-            .get_test_runs_summaries_by_project_id(project_id)
+            .get_test_runs_summaries_by_project_id(project_id, filter)
             .await?;
         Ok(summaries)
     }
 
+    pub async fn get_test_runs_page_by_project_id(
+        &self,
+        project_id: i32,
+        filter: &TestRunFilter,
+        page: &TestRunPageRequest,
+    ) -> Result<TestRunPage, Box<dyn std::error::Error>> {
+        let (summaries, next_cursor) = self
+            .repository
+            .get_test_runs_page_by_project_id(project_id, filter, page)
+            .await?;
+        let total_count = self
+            .repository
+            .count_test_runs_by_project_id(project_id, filter)
+            .await?;
+        Ok(TestRunPage {
+            summaries,
+            total_count,
+            next_cursor,
+        })
+    }
+
     pub async fn get_test_run_by_id(
         &self,
         id: i32,
@@ -90,11 +1011,437 @@ impl RobotService {
         Ok(keywords)
     }
 
+    pub async fn get_test_run_artifact(
+        &self,
+        test_run_id: i32,
+        path: &str,
+    ) -> Result<Option<ArtifactContent>, Box<dyn std::error::Error>> {
+        let artifact = self
+            .repository
+            .get_test_run_artifact(test_run_id, path)
+            .await?;
+        Ok(artifact)
+    }
+
+    /// The output file as it was uploaded, found by its SHA-1.
+    pub async fn get_output_file(
+        &self,
+        sha1: &str,
+    ) -> Result<Option<OutputFile>, Box<dyn std::error::Error>> {
+        let Some(mut output_file) = self.repository.get_output_file(sha1).await? else {
+            return Ok(None);
+        };
+        output_file.content = decompress_content(output_file.content);
+        Ok(Some(output_file))
+    }
+
+    pub async fn get_test_attempts_by_test_id(
+        &self,
+        id: i32,
+    ) -> Result<Vec<TestAttempt>, Box<dyn std::error::Error>> {
+        let attempts = self.repository.get_test_attempts_by_test_id(id).await?;
+        Ok(attempts)
+    }
+
     pub async fn get_test_keywords_by_test_id(
         &self,
         id: i32,
-    ) -> Result<Option<Value>, Box<dyn std::error::Error>> {
+    ) -> Result<Option<TestKeywords>, Box<dyn std::error::Error>> {
         let keywords = self.repository.get_test_keywords_by_test_id(id).await?;
         Ok(keywords)
     }
+
+    /// The root suites of the run without their children, `None` when the
+    /// run does not exist.
+    pub async fn get_root_suites_by_test_run_id(
+        &self,
+        test_run_id: i32,
+    ) -> Result<Option<Vec<SuiteNode>>, Box<dyn std::error::Error>> {
+        if !self.repository.is_test_run_existing(test_run_id).await? {
+            return Ok(None);
+        }
+        let suites = self
+            .repository
+            .get_suite_nodes(Some(test_run_id), None)
+            .await?;
+        Ok(Some(suites))
+    }
+
+    /// `None` when the suite does not exist.
+    pub async fn get_child_suites_by_suite_id(
+        &self,
+        suite_id: i32,
+    ) -> Result<Option<Vec<SuiteNode>>, Box<dyn std::error::Error>> {
+        if !self.repository.is_suite_existing(suite_id).await? {
+            return Ok(None);
+        }
+        let suites = self
+            .repository
+            .get_suite_nodes(None, Some(suite_id))
+            .await?;
+        Ok(Some(suites))
+    }
+
+    /// `None` when the suite does not exist.
+    pub async fn get_tests_page_by_suite_id(
+        &self,
+        suite_id: i32,
+        request: &SuiteTestsRequest,
+    ) -> Result<Option<SuiteTestPage>, Box<dyn std::error::Error>> {
+        if !self.repository.is_suite_existing(suite_id).await? {
+            return Ok(None);
+        }
+        let (tests, next_cursor) = self
+            .repository
+            .get_tests_page_by_suite_id(suite_id, request)
+            .await?;
+        Ok(Some(SuiteTestPage { tests, next_cursor }))
+    }
+
+    /// What changed from the base run to the target run, `None` when either
+    /// does not exist.
+    pub async fn compare_test_runs(
+        &self,
+        base_test_run_id: i32,
+        target_test_run_id: i32,
+    ) -> Result<Option<TestRunComparison>, Box<dyn std::error::Error>> {
+        for test_run_id in [base_test_run_id, target_test_run_id] {
+            if !self.repository.is_test_run_existing(test_run_id).await? {
+                return Ok(None);
+            }
+        }
+
+        let tests = self
+            .repository
+            .get_test_run_comparison(base_test_run_id, target_test_run_id)
+            .await?;
+        let base_statistics = self
+            .repository
+            .get_test_run_statistics_by_test_run_id(base_test_run_id)
+            .await?;
+        let target_statistics = self
+            .repository
+            .get_test_run_statistics_by_test_run_id(target_test_run_id)
+            .await?;
+        Ok(Some(TestRunComparison {
+            base_test_run_id,
+            target_test_run_id,
+            tests,
+            tags: compare_tag_statistics(base_statistics, target_statistics),
+        }))
+    }
+
+    /// `None` when the run does not exist.
+    pub async fn get_failure_groups_by_test_run_id(
+        &self,
+        test_run_id: i32,
+    ) -> Result<Option<Vec<FailureGroup>>, Box<dyn std::error::Error>> {
+        if !self.repository.is_test_run_existing(test_run_id).await? {
+            return Ok(None);
+        }
+        let groups = self
+            .repository
+            .get_failure_groups_by_test_run_id(test_run_id)
+            .await?;
+        Ok(Some(groups))
+    }
+
+    pub async fn get_failure_groups_by_project_id(
+        &self,
+        project_id: i32,
+        filter: &TestRunFilter,
+        limit: i64,
+    ) -> Result<Vec<ProjectFailureGroup>, Box<dyn std::error::Error>> {
+        let groups = self
+            .repository
+            .get_failure_groups_by_project_id(project_id, filter, limit)
+            .await?;
+        Ok(groups)
+    }
+
+    pub async fn search(
+        &self,
+        request: &SearchRequest,
+    ) -> Result<Vec<SearchMatch>, Box<dyn std::error::Error>> {
+        let matches = self.repository.search(request).await?;
+        Ok(matches)
+    }
+
+    pub async fn get_flaky_tests_by_project_id(
+        &self,
+        project_id: i32,
+        limit: i64,
+    ) -> Result<Vec<FlakyTest>, Box<dyn std::error::Error>> {
+        let tests = self
+            .repository
+            .get_flaky_tests_by_project_id(project_id, limit)
+            .await?;
+        Ok(tests)
+    }
+
+    /// The outcomes of a test in every run of its project, whichever run the
+    /// test id comes from.
+    pub async fn get_test_history_by_test_id(
+        &self,
+        test_id: i32,
+        request: &TestHistoryRequest,
+    ) -> Result<Option<TestHistory>, Box<dyn std::error::Error>> {
+        match self
+            .repository
+            .get_test_identity_by_test_id(test_id)
+            .await?
+        {
+            Some(test) => Ok(Some(self.get_test_history(test, request).await?)),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn get_test_history(
+        &self,
+        test: TestIdentity,
+        request: &TestHistoryRequest,
+    ) -> Result<TestHistory, Box<dyn std::error::Error>> {
+        let (entries, next_cursor) = self.repository.get_test_history(&test, request).await?;
+        Ok(TestHistory {
+            test,
+            entries,
+            next_cursor,
+        })
+    }
+}
+
+/// Reads stored chunks from blocking code, e.g. to decompress them. It must
+/// not be used on the async runtime itself.
+struct BlockingContentReader {
+    runtime: Handle,
+    chunks: ContentChunks,
+    chunk: io::Cursor<Vec<u8>>,
+}
+
+impl BlockingContentReader {
+    fn new(chunks: ContentChunks) -> Self {
+        BlockingContentReader {
+            runtime: Handle::current(),
+            chunks,
+            chunk: io::Cursor::default(),
+        }
+    }
+}
+
+impl Read for BlockingContentReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let read = Read::read(&mut self.chunk, buf)?;
+            if read > 0 || buf.is_empty() {
+                return Ok(read);
+            }
+            match self.runtime.block_on(self.chunks.next()) {
+                Some(chunk) => self.chunk = io::Cursor::new(chunk?),
+                None => return Ok(0),
+            }
+        }
+    }
+}
+
+/// Decompresses a stored output file on the blocking pool, a chunk at a time
+/// as the returned stream is read.
+fn decompress_content(chunks: ContentChunks) -> ContentChunks {
+    let (sender, receiver) = mpsc::channel(2);
+    let reader = BlockingContentReader::new(chunks);
+    tokio::task::spawn_blocking(move || {
+        let mut content = archive::decompress(reader);
+        loop {
+            let mut chunk = Vec::new();
+            let chunk = match (&mut content)
+                .take(CONTENT_CHUNK_SIZE)
+                .read_to_end(&mut chunk)
+            {
+                Ok(0) => return,
+                Ok(_) => Ok(chunk),
+                Err(e) => Err(e),
+            };
+            let failed = chunk.is_err();
+            // The receiver is gone when the download was interrupted.
+            if sender.blocking_send(chunk).is_err() || failed {
+                return;
+            }
+        }
+    });
+    stream::unfold(receiver, |mut receiver| async move {
+        let chunk = receiver.recv().await?;
+        Some((chunk, receiver))
+    })
+    .boxed()
+}
+
+/// Identifies a combined run by its parts, whatever order they were uploaded in.
+/// Same as the SHA-1 the parser computes while reading the file.
+fn file_sha1(path: &Path) -> io::Result<String> {
+    let mut hasher = sha1::Sha1::new();
+    io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn combined_sha1(sha1s: &[String]) -> String {
+    let mut sha1s = sha1s.to_vec();
+    sha1s.sort();
+    format!("{:x}", sha1::Sha1::digest(sha1s.join("\n")))
+}
+
+fn is_unique_violation(e: &(dyn std::error::Error + 'static)) -> bool {
+    e.downcast_ref::<sqlx::Error>()
+        .and_then(|e| e.as_database_error())
+        .is_some_and(|e| e.is_unique_violation())
+}
+
+fn rerun_suites(targets: Vec<RerunTargetDB>) -> HashMap<String, RerunSuite> {
+    let mut suites: HashMap<String, RerunSuite> = HashMap::new();
+    for target in targets {
+        let suite = suites.entry(target.path).or_insert_with(|| RerunSuite {
+            id: target.id,
+            identifier: target.identifier,
+            tests: HashMap::new(),
+            test_count: 0,
+        });
+        if let (Some(id), Some(identifier), Some(name), Some(attempt)) = (
+            target.test_id,
+            target.test_identifier,
+            target.test_name,
+            target.test_attempt,
+        ) {
+            suite.test_count += 1;
+            suite.tests.insert(
+                name,
+                RerunTest {
+                    id,
+                    identifier,
+                    attempt,
+                },
+            );
+        }
+    }
+    suites
+}
+
+/// The tags whose counts changed, ordered by name.
+fn compare_tag_statistics(
+    base: Vec<TestRunStatistic>,
+    target: Vec<TestRunStatistic>,
+) -> Vec<TagStatisticChange> {
+    let mut tags: BTreeMap<String, TagStatisticChange> = BTreeMap::new();
+    let tag_statistics = |statistics: Vec<TestRunStatistic>| {
+        statistics
+            .into_iter()
+            .filter(|statistic| matches!(statistic.stat_type, StatisticType::Tag))
+    };
+    for statistic in tag_statistics(base) {
+        tags.insert(
+            statistic.text.clone(),
+            TagStatisticChange {
+                tag: statistic.text.clone(),
+                base: Some(statistic),
+                target: None,
+            },
+        );
+    }
+    for statistic in tag_statistics(target) {
+        let tag = statistic.text.clone();
+        tags.entry(tag.clone())
+            .or_insert_with(|| TagStatisticChange {
+                tag,
+                base: None,
+                target: None,
+            })
+            .target = Some(statistic);
+    }
+    tags.into_values()
+        .filter(TagStatisticChange::is_changed)
+        .collect()
+}
+
+fn record_suite(statistics: &mut StatisticsBuilder, suite: &TestRunSuite) {
+    statistics.start_suite(&suite.identifier, &suite.name);
+    for test in &suite.tests {
+        statistics.add_test(&test.status, &test.tags);
+    }
+    for child in &suite.suites {
+        record_suite(statistics, child);
+    }
+    statistics.end_suite();
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::robot::domain::{ComparedResult, TestChange, TestComparison};
+
+    use super::*;
+
+    fn tag(text: &str, pass_count: i32, fail_count: i32) -> TestRunStatistic {
+        TestRunStatistic {
+            id: 0,
+            stat_type: StatisticType::Tag,
+            pass_count,
+            fail_count,
+            skip_count: 0,
+            identifier: None,
+            name: None,
+            text: text.to_string(),
+        }
+    }
+
+    fn result(status: &str) -> Option<ComparedResult> {
+        Some(ComparedResult {
+            test_id: 1,
+            status: status.to_string(),
+            duration_ms: 0,
+        })
+    }
+
+    #[test]
+    fn test_compare_tag_statistics_keeps_changed_tags() {
+        let tags = compare_tag_statistics(
+            vec![tag("smoke", 2, 0), tag("login", 1, 1), tag("legacy", 1, 0)],
+            vec![tag("smoke", 1, 1), tag("login", 1, 1), tag("api", 3, 0)],
+        );
+        let changes: Vec<_> = tags
+            .iter()
+            .map(|tag| (tag.tag.as_str(), tag.base.is_some(), tag.target.is_some()))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                ("api", false, true),
+                ("legacy", true, false),
+                ("smoke", true, true)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_comparison_change() {
+        let change = |base, target| {
+            TestComparison {
+                suite: "Tests".to_string(),
+                name: "Login".to_string(),
+                base,
+                target,
+            }
+            .change()
+        };
+        assert_eq!(
+            change(result("PASS"), result("FAIL")),
+            TestChange::NewlyFailing
+        );
+        assert_eq!(change(result("FAIL"), result("PASS")), TestChange::Fixed);
+        assert_eq!(
+            change(result("FAIL"), result("FAIL")),
+            TestChange::StillFailing
+        );
+        assert_eq!(
+            change(result("PASS"), result("SKIP")),
+            TestChange::StillPassing
+        );
+        assert_eq!(change(None, result("FAIL")), TestChange::Added);
+        assert_eq!(change(result("PASS"), None), TestChange::Removed);
+    }
 }
@@ -1,10 +1,23 @@
-use chrono::{Duration, NaiveDateTime};
+use chrono::{Duration, NaiveDate, NaiveDateTime};
 use sqlx::postgres::types::PgInterval;
 
 pub fn format_datetime(date: NaiveDateTime) -> String {
     date.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string()
 }
 
+/// Reads a date as given by `format_datetime`, or a day, which starts at
+/// midnight, or ends just before the next one with `end_of_day`.
+pub fn parse_datetime(value: &str, end_of_day: bool) -> Option<NaiveDateTime> {
+    if let Ok(day) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        let start = day.and_hms_opt(0, 0, 0)?;
+        return Some(match end_of_day {
+            true => start + Duration::days(1) - Duration::microseconds(1),
+            false => start,
+        });
+    }
+    NaiveDateTime::parse_from_str(value.trim_end_matches('Z'), "%Y-%m-%dT%H:%M:%S%.f").ok()
+}
+
 pub fn pg_interval_to_duration(pg_interval: PgInterval) -> Duration {
     let months = pg_interval.months as i64;
     let days = pg_interval.days as i64;
@@ -2,7 +2,7 @@ use std::process::Command;
 
 fn main() {
     let status = Command::new("typeshare")
-        .args(&[
+        .args([
             "./src/models/",
             "--lang=typescript",
             "--output-file",